@@ -0,0 +1,241 @@
+//! Derive macro for domain-specific [dirge](https://docs.rs/dirge) path newtypes.
+//!
+//! Use it through dirge's `derive` feature rather than depending on this crate directly.
+//! The generated code derives `RefCastCustom`, so the crate using it must also depend on
+//! `ref-cast`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, Path, Type, parse_macro_input, spanned::Spanned};
+
+/// Generates the boilerplate for a newtype over one of dirge's owned path types.
+///
+/// Applied to a tuple struct wrapping an owned dirge type (e.g. `RelPathBuf`), the derive
+/// declares the borrowed counterpart named by `borrowed = ...` and implements `Deref`,
+/// `AsRef<Path>`, `Borrow`, `ToOwned` and `Debug` between them, together with validating
+/// constructors. See the `dirge` crate documentation for the attribute reference.
+#[proc_macro_derive(DirgePath, attributes(dirge))]
+pub fn derive_dirge_path(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Options {
+    borrowed: Ident,
+    validate: Option<Path>,
+    serde: bool,
+}
+
+fn parse_options(input: &DeriveInput) -> syn::Result<Options> {
+    let mut borrowed = None;
+    let mut validate = None;
+    let mut serde = false;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("dirge")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("borrowed") {
+                borrowed = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("validate") {
+                validate = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("serde") {
+                serde = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `borrowed`, `validate` or `serde`"))
+            }
+        })?;
+    }
+
+    let borrowed = borrowed.ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            "missing `#[dirge(borrowed = Name)]` attribute",
+        )
+    })?;
+
+    Ok(Options {
+        borrowed,
+        validate,
+        serde,
+    })
+}
+
+fn inner_type(input: &DeriveInput) -> syn::Result<&Type> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "DirgePath can only be derived for tuple structs",
+        ));
+    };
+
+    match &data.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(&fields.unnamed[0].ty),
+        _ => Err(syn::Error::new(
+            data.fields.span(),
+            "DirgePath requires a tuple struct with exactly one field",
+        )),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "DirgePath does not support generic types",
+        ));
+    }
+
+    let options = parse_options(&input)?;
+    let inner = inner_type(&input)?;
+    let owned = &input.ident;
+    let borrowed = &options.borrowed;
+    let vis = &input.vis;
+
+    let target = quote!(<#inner as ::core::ops::Deref>::Target);
+    let borrowed_doc = format!("Borrowed counterpart of [`{owned}`].");
+
+    let validate = match &options.validate {
+        Some(f) => quote!(#f(inner)?;),
+        None => quote!(let _ = inner;),
+    };
+
+    let serde = options.serde.then(|| {
+        quote! {
+            impl ::dirge::__private::serde::Serialize for #owned {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: ::dirge::__private::serde::Serializer,
+                {
+                    ::dirge::__private::serde::Serialize::serialize(&self.0, serializer)
+                }
+            }
+
+            impl ::dirge::__private::serde::Serialize for #borrowed {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: ::dirge::__private::serde::Serializer,
+                {
+                    ::dirge::__private::serde::Serialize::serialize(&self.0, serializer)
+                }
+            }
+
+            impl<'de> ::dirge::__private::serde::Deserialize<'de> for #owned {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: ::dirge::__private::serde::Deserializer<'de>,
+                {
+                    let inner = <#inner as ::dirge::__private::serde::Deserialize>::deserialize(
+                        deserializer,
+                    )?;
+                    Self::from_inner(inner).map_err(::dirge::__private::serde::de::Error::custom)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[doc = #borrowed_doc]
+        #[derive(PartialEq, Eq, ::dirge::__private::ref_cast::RefCastCustom)]
+        #[repr(transparent)]
+        #vis struct #borrowed(#target);
+
+        impl #borrowed {
+            // Private so the only ways to a `&#borrowed` are `from_inner` and `Deref` on the
+            // owned type, both of which have run the validator.
+            #[::dirge::__private::ref_cast::ref_cast_custom]
+            fn __dirge_cast(inner: &#target) -> &Self;
+
+            fn __dirge_validate(inner: &#target) -> ::std::io::Result<()> {
+                #validate
+                Ok(())
+            }
+
+            /// Wraps an already-typed path after running the validator on it.
+            #vis fn from_inner(inner: &#target) -> ::std::io::Result<&Self> {
+                Self::__dirge_validate(inner)?;
+                Ok(Self::__dirge_cast(inner))
+            }
+        }
+
+        impl #owned {
+            /// Creates the path, applying both dirge's invariant and the validator.
+            #vis fn new<P: ::core::convert::AsRef<::std::path::Path>>(
+                p: P,
+            ) -> ::std::io::Result<Self> {
+                Self::from_inner(<#inner>::new(p)?)
+            }
+
+            /// Wraps an already-typed path after running the validator on it.
+            #vis fn from_inner(inner: #inner) -> ::std::io::Result<Self> {
+                #borrowed::__dirge_validate(&inner)?;
+                Ok(Self(inner))
+            }
+
+            /// Unwraps the underlying dirge path.
+            #vis fn into_inner(self) -> #inner {
+                self.0
+            }
+        }
+
+        impl ::core::fmt::Debug for #borrowed {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl ::core::fmt::Debug for #owned {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl ::core::ops::Deref for #borrowed {
+            type Target = #target;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl ::core::ops::Deref for #owned {
+            type Target = #borrowed;
+
+            fn deref(&self) -> &Self::Target {
+                #borrowed::__dirge_cast(&self.0)
+            }
+        }
+
+        impl ::core::convert::AsRef<::std::path::Path> for #borrowed {
+            fn as_ref(&self) -> &::std::path::Path {
+                self.0.as_ref()
+            }
+        }
+
+        impl ::core::convert::AsRef<::std::path::Path> for #owned {
+            fn as_ref(&self) -> &::std::path::Path {
+                self.0.as_ref()
+            }
+        }
+
+        impl ::core::borrow::Borrow<#borrowed> for #owned {
+            fn borrow(&self) -> &#borrowed {
+                self
+            }
+        }
+
+        impl ::std::borrow::ToOwned for #borrowed {
+            type Owned = #owned;
+
+            fn to_owned(&self) -> Self::Owned {
+                #owned(self.0.to_owned())
+            }
+        }
+
+        #serde
+    })
+}
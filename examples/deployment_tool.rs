@@ -128,10 +128,8 @@ impl ServerConfig {
     ///
     /// By using AbsPath, we guarantee that log files will always
     /// be written to the correct location regardless of working directory.
-    pub fn log_file_for(&self, component: &str) -> AbsPathBuf {
-        let mut log_file = self.log_dir.to_owned();
-        log_file.push(format!("{}.log", component));
-        log_file
+    pub fn log_file_for(&self, component: &str) -> io::Result<AbsPathBuf> {
+        self.log_dir.try_join(format!("{}.log", component))
     }
 }
 
@@ -184,7 +182,7 @@ impl SecurePathProcessor {
         // This would be dangerous with raw strings:
         // User could input: "../../etc/passwd"
         // But normalization makes it safe:
-        let normalized = user_input.to_norm_path_buf()?;
+        let normalized = user_input.to_norm_path_buf();
 
         println!("User input: {:?}", user_input);
         println!("Normalized: {:?}", normalized);
@@ -195,7 +193,9 @@ impl SecurePathProcessor {
     /// Validate that a normalized path doesn't escape a sandbox
     pub fn validate_sandbox_escape(path: &NormPath, sandbox: &AbsPath) -> Result<(), String> {
         // Convert to absolute path for proper validation
-        let abs_path = sandbox.join(path);
+        let abs_path = sandbox
+            .try_join(path)
+            .map_err(|err| format!("Path {:?} cannot be joined: {}", path, err))?;
 
         if !abs_path.starts_with(sandbox) {
             return Err(format!(
@@ -222,7 +222,7 @@ fn main() -> io::Result<()> {
     println!("   ✅ Server configuration created successfully!");
     println!(
         "   📝 Deployment log: {:?}",
-        server_config.log_file_for("deployment")
+        server_config.log_file_for("deployment")?
     );
 
     // 2. Deployment configuration with mixed path types
@@ -235,9 +235,9 @@ fn main() -> io::Result<()> {
             "Cargo.toml".to_rel_path_buf()?,
         ],
         vec![
-            "target/debug".to_norm_path_buf()?,
-            ".git".to_norm_path_buf()?,
-            "temp/../cache".to_norm_path_buf()?, // Gets normalized to "cache"
+            "target/debug".to_norm_path_buf(),
+            ".git".to_norm_path_buf(),
+            "temp/../cache".to_norm_path_buf(), // Gets normalized to "cache"
         ],
     );
 
@@ -309,7 +309,7 @@ mod tests {
         let config = DeploymentConfig::new(
             "/opt/app".to_abs_path_buf().unwrap(),
             vec!["src/**/*.rs".to_rel_path_buf().unwrap()],
-            vec!["target".to_norm_path_buf().unwrap()],
+            vec!["target".to_norm_path_buf()],
         );
 
         assert!(config.validate_source_patterns().is_ok());
@@ -317,7 +317,7 @@ mod tests {
 
     #[test]
     fn test_path_normalization() {
-        let normalized = "path/./to/../file.txt".to_norm_path_buf().unwrap();
+        let normalized = "path/./to/../file.txt".to_norm_path_buf();
         assert_eq!(normalized.to_string_lossy(), "path/file.txt");
     }
 
@@ -326,10 +326,10 @@ mod tests {
         let config = DeploymentConfig::new(
             "/opt/app".to_abs_path_buf().unwrap(),
             vec![],
-            vec!["target".to_norm_path_buf().unwrap()],
+            vec!["target".to_norm_path_buf()],
         );
 
-        let test_path = "target/debug/myapp".to_norm_path_buf().unwrap();
+        let test_path = "target/debug/myapp".to_norm_path_buf();
         assert!(config.is_excluded(&test_path));
     }
 }
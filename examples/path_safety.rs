@@ -47,7 +47,7 @@ fn main() -> io::Result<()> {
     ];
 
     for path in dangerous_paths {
-        let normalized = path.to_norm_path_buf()?;
+        let normalized = path.to_norm_path_buf();
         println!("   '{}' -> '{}'", path, normalized.display());
     }
 
@@ -74,8 +74,8 @@ fn main() -> io::Result<()> {
             "templates/error.html".to_rel_path_buf()?,
         ],
         vec![
-            "user/./profile/../settings.json".to_norm_path_buf()?,
-            "uploads/image.png".to_norm_path_buf()?,
+            "user/./profile/../settings.json".to_norm_path_buf(),
+            "uploads/image.png".to_norm_path_buf(),
         ],
     );
 
@@ -117,15 +117,15 @@ mod tests {
     #[test]
     fn test_path_normalization_safety() {
         // Dangerous input gets normalized (leading .. are preserved when they can't be resolved)
-        let dangerous = "../../../etc/passwd".to_norm_path_buf().unwrap();
-        assert_eq!(dangerous.to_string_lossy(), "../etc/passwd");
+        let dangerous = "../../../etc/passwd".to_norm_path_buf();
+        assert_eq!(dangerous.to_string_lossy(), "../../../etc/passwd");
 
         // Complex paths get simplified
-        let complex = "path/./to/../from/./file.txt".to_norm_path_buf().unwrap();
+        let complex = "path/./to/../from/./file.txt".to_norm_path_buf();
         assert_eq!(complex.to_string_lossy(), "path/from/file.txt");
 
         // Path traversal within a path gets resolved
-        let traversal = "safe/../../etc/passwd".to_norm_path_buf().unwrap();
+        let traversal = "safe/../../etc/passwd".to_norm_path_buf();
         assert_eq!(traversal.to_string_lossy(), "../etc/passwd");
     }
 
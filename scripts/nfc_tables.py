@@ -0,0 +1,87 @@
+#!/usr/bin/env python3
+"""Regenerates the normalization tables in src/nfc.rs.
+
+The tables come from the Unicode Character Database bundled with Python's `unicodedata`, so
+the Unicode version follows the Python that runs this. Everything from the "Generated from"
+comment to the end of the last table is replaced; the tests after it are kept:
+
+    python3 scripts/nfc_tables.py
+"""
+
+import pathlib
+import unicodedata as ucd
+
+TARGET = pathlib.Path(__file__).resolve().parent.parent / "src" / "nfc.rs"
+MARKER = "// Generated from the Unicode Character Database"
+
+
+def rows(items, per):
+    return "\n".join("    " + " ".join(items[i : i + per]) for i in range(0, len(items), per))
+
+
+def combining_classes():
+    ranges = []
+    for cp in range(0x110000):
+        ccc = ucd.combining(chr(cp))
+        if not ccc:
+            continue
+        if ranges and ranges[-1][1] == cp - 1 and ranges[-1][2] == ccc:
+            ranges[-1][1] = cp
+        else:
+            ranges.append([cp, cp, ccc])
+    return ranges
+
+
+def decompositions():
+    """Canonical decompositions one level deep, and the primary composites among them.
+
+    A two-character decomposition is a primary composite unless it is excluded from
+    composition, which shows as the character not surviving a round trip through NFD and NFC.
+    """
+    decomposed, composites = [], []
+    for cp in range(0x110000):
+        mapping = ucd.decomposition(chr(cp))
+        if not mapping or mapping.startswith("<"):
+            continue
+        parts = [int(p, 16) for p in mapping.split()]
+        decomposed.append((cp, parts[0], parts[1] if len(parts) > 1 else 0))
+        if len(parts) == 2 and ucd.normalize("NFC", ucd.normalize("NFD", chr(cp))) == chr(cp):
+            composites.append((parts[0], parts[1], cp))
+    return decomposed, sorted(composites)
+
+
+def tables():
+    decomposed, composites = decompositions()
+    return f"""{MARKER}, version {ucd.unidata_version}, through Python's
+// `unicodedata`; the tables below change only with a new Unicode version. Regenerate them with
+// `scripts/nfc_tables.py`.
+
+/// Canonical combining classes, as `(first, last, class)` ranges of code points.
+#[rustfmt::skip]
+static COMBINING_CLASSES: &[(u32, u32, u8)] = &[
+{rows([f"(0x{a:x}, 0x{b:x}, {k})," for a, b, k in combining_classes()], 4)}
+];
+
+/// Canonical decompositions, one level deep, as `(code point, first, second or 0)`.
+#[rustfmt::skip]
+static DECOMPOSITIONS: &[(u32, u32, u32)] = &[
+{rows([f"(0x{a:x}, 0x{b:x}, 0x{c:x})," for a, b, c in decomposed], 3)}
+];
+
+/// Primary composites, as `(first, second, composite)` sorted by the pair.
+#[rustfmt::skip]
+static COMPOSITIONS: &[(u32, u32, u32)] = &[
+{rows([f"(0x{a:x}, 0x{b:x}, 0x{c:x})," for a, b, c in composites], 3)}
+];
+"""
+
+
+def main():
+    source = TARGET.read_text()
+    start = source.index(MARKER)
+    end = source.index("\n];\n", source.index("static COMPOSITIONS")) + len("\n];\n")
+    TARGET.write_text(source[:start] + tables() + source[end:])
+
+
+if __name__ == "__main__":
+    main()
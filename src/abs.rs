@@ -1,33 +1,115 @@
 use std::{
-    borrow::Borrow,
+    borrow::{Borrow, Cow},
+    ffi::OsStr,
     io,
     ops::Deref,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf, StripPrefixError},
+    sync::Arc,
 };
 
 use std::fmt;
 
 use ref_cast::RefCast;
 
+use crate::{EscapedDisplay, LossyPolicy, NonUtf8Error, RelPath, RelPathBuf, cast};
+
 /// Equivalent to [PathBuf], but guaranteed to be absolute.
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 #[repr(transparent)]
 pub struct AbsPathBuf(PathBuf);
 
 /// Equivalent to [Path], but guaranteed to be absolute.
-#[derive(RefCast, PartialEq, Eq)]
+#[derive(RefCast, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct AbsPath(Path);
 
+/// Iterator returned by [AbsPath::ancestors].
+#[derive(Debug, Clone)]
+pub struct AbsAncestors<'a>(std::path::Ancestors<'a>);
+
+impl<'a> Iterator for AbsAncestors<'a> {
+    type Item = &'a AbsPath;
+
+    fn next(&mut self) -> Option<&'a AbsPath> {
+        self.0.next().map(AbsPath::ref_cast)
+    }
+}
+
+impl std::iter::FusedIterator for AbsAncestors<'_> {}
+
 impl fmt::Debug for AbsPath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self.0, f)
+        crate::utf8::debug(&self.0, f)
     }
 }
 
 impl fmt::Debug for AbsPathBuf {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self.0, f)
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Displays the path like [Path::display], replacing non-UTF-8 content with U+FFFD. Use
+/// [AbsPath::display_escaped] to keep such bytes distinguishable.
+///
+/// For a path that is valid UTF-8, parsing the output with [FromStr](std::str::FromStr) gives
+/// back the same path, byte for byte, trailing separator included. Otherwise the output is
+/// lossy: it parses, if at all, to a different path.
+impl fmt::Display for AbsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0.display(), f)
+    }
+}
+
+impl fmt::Display for AbsPathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+/// Parses with the same rules as [AbsPathBuf::new]. The inverse of `Display` for paths that
+/// are valid UTF-8.
+impl std::str::FromStr for AbsPathBuf {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        AbsPathBuf::new(s)
+    }
+}
+
+/// Rejects relative paths with [io::ErrorKind::InvalidInput] instead of resolving them against
+/// the current directory as [AbsPathBuf::new] does, so the result does not depend on process
+/// state. The buffer is reused.
+impl TryFrom<PathBuf> for AbsPathBuf {
+    type Error = io::Error;
+
+    fn try_from(p: PathBuf) -> io::Result<Self> {
+        AbsPath::new(&p)?;
+        Ok(AbsPathBuf(p))
+    }
+}
+
+impl TryFrom<&Path> for AbsPathBuf {
+    type Error = io::Error;
+
+    fn try_from(p: &Path) -> io::Result<Self> {
+        AbsPath::new(p).map(ToOwned::to_owned)
+    }
+}
+
+impl TryFrom<String> for AbsPathBuf {
+    type Error = io::Error;
+
+    fn try_from(s: String) -> io::Result<Self> {
+        AbsPathBuf::try_from(PathBuf::from(s))
+    }
+}
+
+impl TryFrom<std::ffi::OsString> for AbsPathBuf {
+    type Error = io::Error;
+
+    fn try_from(s: std::ffi::OsString) -> io::Result<Self> {
+        AbsPathBuf::try_from(PathBuf::from(s))
     }
 }
 
@@ -35,6 +117,16 @@ impl AbsPathBuf {
     pub fn new<P: AsRef<Path>>(p: P) -> io::Result<Self> {
         p.as_ref().to_abs_path_buf()
     }
+
+    /// Wraps `p` without checking the invariant; callers must have established it.
+    pub(crate) fn from_path_buf_unchecked(p: PathBuf) -> Self {
+        AbsPathBuf(p)
+    }
+
+    /// Exposes the inner buffer for in-place edits; callers must keep the path absolute.
+    pub(crate) fn as_mut_path_buf_unchecked(&mut self) -> &mut PathBuf {
+        &mut self.0
+    }
 }
 
 impl AsRef<Path> for AbsPathBuf {
@@ -49,13 +141,72 @@ impl AsRef<Path> for AbsPath {
     }
 }
 
+impl AsRef<OsStr> for AbsPathBuf {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl AsRef<OsStr> for AbsPath {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl AsRef<AbsPath> for AbsPathBuf {
+    fn as_ref(&self) -> &AbsPath {
+        self
+    }
+}
+
+impl AsRef<AbsPath> for AbsPath {
+    fn as_ref(&self) -> &AbsPath {
+        self
+    }
+}
+
+impl From<&AbsPath> for Arc<AbsPath> {
+    fn from(p: &AbsPath) -> Self {
+        AbsPath::from_arc_unchecked(Arc::from(&p.0))
+    }
+}
+
+impl From<AbsPathBuf> for Arc<AbsPath> {
+    fn from(p: AbsPathBuf) -> Self {
+        Arc::from(&*p)
+    }
+}
+
+impl From<AbsPathBuf> for PathBuf {
+    fn from(p: AbsPathBuf) -> Self {
+        p.0
+    }
+}
+
+impl From<AbsPathBuf> for Box<AbsPath> {
+    fn from(p: AbsPathBuf) -> Self {
+        AbsPath::from_box_unchecked(p.0.into_boxed_path())
+    }
+}
+
+impl From<&AbsPath> for Box<AbsPath> {
+    fn from(p: &AbsPath) -> Self {
+        Box::from(p.to_owned())
+    }
+}
+
 pub trait ToAbsPathBuf: AsRef<Path> {
     fn to_abs_path_buf(&self) -> io::Result<AbsPathBuf>;
 }
 
 impl<P: AsRef<Path>> ToAbsPathBuf for P {
     fn to_abs_path_buf(&self) -> io::Result<AbsPathBuf> {
-        Ok(AbsPathBuf(std::path::absolute(self)?))
+        let path = std::path::absolute(self)?;
+        // Checked after resolving: the current directory prepended to a relative path may not
+        // be UTF-8 even when the path is.
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(&path)?;
+        Ok(AbsPathBuf(path))
     }
 }
 
@@ -98,12 +249,336 @@ impl AbsPathBuf {
         self.0.capacity()
     }
 
-    pub fn push<P: AsRef<Path>>(&mut self, path: P) {
+    /// Consumes the path, returning the inner [PathBuf] without copying it.
+    pub fn into_path_buf(self) -> PathBuf {
+        self.0
+    }
+
+    /// Like [AbsPathBuf::new], taking over the string's buffer when it is owned and already
+    /// absolute. A relative string is resolved against the current directory, as by `new`.
+    pub fn from_utf8_cow(s: Cow<'_, str>) -> io::Result<Self> {
+        match s {
+            Cow::Owned(s) if Path::new(&s).is_absolute() => AbsPathBuf::try_from(s),
+            s => AbsPathBuf::new(&*s),
+        }
+    }
+
+    /// Views a slice of paths as [PathBuf]s, without copying.
+    pub fn cast_slice(paths: &[AbsPathBuf]) -> &[PathBuf] {
+        cast::inner_slice(paths)
+    }
+
+    /// Converts a vector of paths into [PathBuf]s, reusing its allocation.
+    pub fn into_path_buf_vec(paths: Vec<AbsPathBuf>) -> Vec<PathBuf> {
+        cast::into_inner_vec(paths)
+    }
+
+    /// Views a slice of [PathBuf]s as this type, without copying, if every path satisfies its
+    /// invariant. Otherwise fails with the error for the first path that does not.
+    pub fn try_cast_slice(paths: &[PathBuf]) -> io::Result<&[AbsPathBuf]> {
+        for path in paths {
+            AbsPath::new(path)?;
+        }
+        Ok(cast::slice_unchecked(paths))
+    }
+
+    /// Converts a vector of [PathBuf]s into this type, reusing its allocation, if every path
+    /// satisfies its invariant. Otherwise fails with the error for the first path that does not.
+    pub fn try_from_path_buf_vec(paths: Vec<PathBuf>) -> io::Result<Vec<AbsPathBuf>> {
+        for path in &paths {
+            AbsPath::new(path)?;
+        }
+        Ok(cast::vec_unchecked(paths))
+    }
+
+    /// Extends the path with `path`, which as a [RelPath] can never replace it.
+    pub fn push<P: AsRef<RelPath>>(&mut self, path: P) {
+        self.0.push(path.as_ref());
+    }
+
+    /// Like [AbsPathBuf::push], for a `path` that is not yet known to be a [RelPath]. Fails as
+    /// [AbsPath::try_join] does, leaving the path unchanged.
+    pub fn try_push<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        crate::rel::check_joinable(path)?;
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(path)?;
         self.0.push(path);
+        Ok(())
+    }
+
+    /// Like [PathBuf::set_extension]; the path stays absolute.
+    ///
+    /// Panics under the `utf8-strict` feature if `extension` is not valid UTF-8.
+    pub fn set_extension<S: AsRef<OsStr>>(&mut self, extension: S) -> bool {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::assert(Path::new(&extension));
+        self.0.set_extension(extension)
+    }
+
+    /// Like [PathBuf::set_file_name], validating `file_name` as [AbsPath::with_file_name] does
+    /// and leaving the path unchanged if it is rejected.
+    pub fn set_file_name<S: AsRef<OsStr>>(&mut self, file_name: S) -> io::Result<()> {
+        self.0
+            .set_file_name(crate::rel::check_segment(file_name.as_ref())?);
+        Ok(())
+    }
+
+    /// Truncates the path to `ancestor`, keeping the allocated capacity.
+    ///
+    /// Loops that build many sibling paths can reset one buffer with this instead of joining
+    /// onto the parent each time. Fails with [io::ErrorKind::InvalidInput], leaving the path
+    /// unchanged, if `ancestor` is not an ancestor of (or equal to) the path.
+    ///
+    /// ```
+    /// use dirge::AbsPathBuf;
+    ///
+    /// let root = AbsPathBuf::new("/srv/site").unwrap();
+    /// let mut buf = root.clone();
+    /// for name in ["a.html", "b.html"] {
+    ///     buf.clear_to(&root).unwrap();
+    ///     buf.try_push(name).unwrap();
+    /// }
+    /// assert_eq!(buf, AbsPathBuf::new("/srv/site/b.html").unwrap());
+    /// ```
+    pub fn clear_to<P: AsRef<AbsPath>>(&mut self, ancestor: P) -> io::Result<()> {
+        let ancestor = &ancestor.as_ref().0;
+        if !self.0.starts_with(ancestor) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path does not start with the given ancestor",
+            ));
+        }
+        while self.0 != *ancestor {
+            self.0.pop();
+        }
+        Ok(())
+    }
+
+    /// Truncates the path to its root, such as `/` or `C:\`, keeping the allocated capacity.
+    pub fn truncate_to_root(&mut self) {
+        while self.0.pop() {}
+    }
+}
+
+impl AbsPath {
+    /// Views `p` as an [AbsPath] without copying it, if it is absolute.
+    ///
+    /// Unlike [AbsPathBuf::new], a relative path is an error rather than being resolved against
+    /// the current directory.
+    ///
+    /// ```
+    /// use dirge::AbsPath;
+    /// use std::path::Path;
+    ///
+    /// # #[cfg(unix)] {
+    /// let stored = Path::new("/etc/hosts");
+    /// let abs: &AbsPath = AbsPath::new(stored).unwrap();
+    /// assert_eq!(abs.file_name().unwrap(), "hosts");
+    /// # }
+    /// assert!(AbsPath::new("relative").is_err());
+    /// ```
+    pub fn new<P: AsRef<Path> + ?Sized>(p: &P) -> io::Result<&AbsPath> {
+        let path = p.as_ref();
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(path)?;
+        if path.is_absolute() {
+            Ok(AbsPath::ref_cast(path))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path must be absolute",
+            ))
+        }
+    }
+
+    /// Views `p` as an [AbsPath] without checking the invariant; callers must have established it.
+    pub(crate) fn from_path_unchecked(p: &Path) -> &AbsPath {
+        AbsPath::ref_cast(p)
+    }
+
+    /// Joins the name of an entry read from this directory, which is always a single normal
+    /// component. Under the `utf8-strict` feature the caller must have checked it.
+    pub(crate) fn join_entry(&self, name: &OsStr) -> AbsPathBuf {
+        AbsPathBuf(self.0.join(name))
+    }
+
+    /// Creates an owned [AbsPathBuf] with `path` adjoined to `self`.
+    ///
+    /// `path` is a [RelPath], so unlike with [Path::join] it always extends `self` and the
+    /// result is absolute. Use [AbsPath::try_join] to join a path that has not been checked yet.
+    pub fn join<P: AsRef<RelPath>>(&self, path: P) -> AbsPathBuf {
+        AbsPathBuf(self.0.join(path.as_ref()))
+    }
+
+    /// Like [AbsPath::join], for a `path` that is not yet known to be a [RelPath]. Fails with
+    /// [io::ErrorKind::InvalidInput] if it has a root or, on Windows, a prefix such as `D:`,
+    /// since `D:foo` would make the result drive-relative rather than absolute, and under the
+    /// `utf8-strict` feature if it is not valid UTF-8.
+    ///
+    /// `..` components are kept, so the result can still leave `self`; code confining
+    /// untrusted paths to a directory should also use something like
+    /// [ReadOnlyJail](crate::jail::ReadOnlyJail).
+    ///
+    /// ```
+    /// # #[cfg(unix)] {
+    /// use dirge::AbsPathBuf;
+    ///
+    /// let root = AbsPathBuf::new("/srv/uploads").unwrap();
+    /// assert_eq!(root.try_join("a/b.png").unwrap(), "/srv/uploads/a/b.png");
+    /// assert!(root.try_join("/etc/passwd").is_err());
+    /// # }
+    /// ```
+    pub fn try_join<P: AsRef<Path>>(&self, path: P) -> io::Result<AbsPathBuf> {
+        let path = path.as_ref();
+        crate::rel::check_joinable(path)?;
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(path)?;
+        Ok(AbsPathBuf(self.0.join(path)))
+    }
+
+    /// Like [Path::parent]; the parent of an absolute path is absolute.
+    pub fn parent(&self) -> Option<&AbsPath> {
+        self.0.parent().map(AbsPath::ref_cast)
+    }
+
+    /// Like [Path::ancestors]: `self`, then each parent in turn. Every ancestor of an absolute
+    /// path is absolute.
+    ///
+    /// ```
+    /// # #[cfg(unix)] {
+    /// use dirge::AbsPathBuf;
+    ///
+    /// let file = AbsPathBuf::new("/src/app/Cargo.toml").unwrap();
+    /// let dirs: Vec<_> = file.ancestors().skip(1).collect();
+    /// assert_eq!(dirs, ["/src/app", "/src", "/"]);
+    /// # }
+    /// ```
+    pub fn ancestors(&self) -> AbsAncestors<'_> {
+        AbsAncestors(self.0.ancestors())
+    }
+
+    /// Like [Path::strip_prefix]; what remains after an absolute prefix is relative.
+    ///
+    /// ```
+    /// # #[cfg(unix)] {
+    /// use dirge::{AbsPathBuf, RelPath};
+    ///
+    /// let file = AbsPathBuf::new("/src/app/Cargo.toml").unwrap();
+    /// let root = AbsPathBuf::new("/src").unwrap();
+    /// let rest: &RelPath = file.strip_prefix(&root).unwrap();
+    /// assert_eq!(rest, "app/Cargo.toml");
+    /// # }
+    /// ```
+    pub fn strip_prefix<P: AsRef<AbsPath>>(&self, base: P) -> Result<&RelPath, StripPrefixError> {
+        let rest = self.0.strip_prefix(&base.as_ref().0)?;
+        Ok(RelPath::from_path_unchecked(rest))
+    }
+
+    /// Computes the relative path that leads from the directory `base` to `self`, climbing
+    /// with `..` where needed, for relative symlinks and portable build files. A path relative
+    /// to itself is `.`.
+    ///
+    /// Both paths are [normalized](crate::normalize) first and never touch the file system, so
+    /// the result can be wrong if a component of `base` is a symlink. Fails with
+    /// [io::ErrorKind::InvalidInput] if the paths have different prefixes, such as two Windows
+    /// drives, since no relative path connects them.
+    ///
+    /// ```
+    /// # #[cfg(unix)] {
+    /// use dirge::AbsPathBuf;
+    ///
+    /// let target = AbsPathBuf::new("/srv/shared/lib.so").unwrap();
+    /// let dir = AbsPathBuf::new("/srv/app/bin").unwrap();
+    /// assert_eq!(target.relative_to(&dir).unwrap(), "../../shared/lib.so");
+    /// # }
+    /// ```
+    pub fn relative_to<P: AsRef<AbsPath>>(&self, base: P) -> io::Result<RelPathBuf> {
+        let base = base.as_ref();
+        let to = crate::normalize(&self.0);
+        let from = crate::normalize(&base.0);
+        let to: Vec<Component> = to.components().collect();
+        let from: Vec<Component> = from.components().collect();
+
+        if to.first() != from.first() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{}: no relative path leads there from {}",
+                    self.0.display(),
+                    base.0.display()
+                ),
+            ));
+        }
+
+        let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+        let mut rel = PathBuf::new();
+        for _ in common..from.len() {
+            rel.push("..");
+        }
+        rel.extend(&to[common..]);
+        if rel.as_os_str().is_empty() {
+            rel.push(".");
+        }
+        Ok(RelPathBuf::from_path_buf_unchecked(rel))
+    }
+
+    /// Like [Path::with_file_name], but fails with [io::ErrorKind::InvalidInput] unless
+    /// `file_name` is a single normal component: not empty, `.` or `..`, and without separators.
+    /// Under the `utf8-strict` feature it must also be valid UTF-8.
+    pub fn with_file_name<S: AsRef<OsStr>>(&self, file_name: S) -> io::Result<AbsPathBuf> {
+        let file_name = crate::rel::check_segment(file_name.as_ref())?;
+        Ok(AbsPathBuf(self.0.with_file_name(file_name)))
+    }
+
+    /// Like [Path::with_extension].
+    ///
+    /// Panics under the `utf8-strict` feature if `extension` is not valid UTF-8.
+    pub fn with_extension<S: AsRef<OsStr>>(&self, extension: S) -> AbsPathBuf {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::assert(Path::new(&extension));
+        AbsPathBuf(self.0.with_extension(extension))
+    }
+
+    /// Like [Path::canonicalize], with errors that name the path.
+    pub fn canonicalize(&self) -> io::Result<AbsPathBuf> {
+        let canonical = self
+            .0
+            .canonicalize()
+            .map_err(|err| crate::fs::with_path(err, &self.0))?;
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check_os(&canonical)?;
+        Ok(AbsPathBuf(canonical))
+    }
+
+    /// Converts the path to a [String], handling non-UTF-8 content according to `policy`.
+    pub fn to_string_policy(&self, policy: LossyPolicy) -> io::Result<String> {
+        crate::utf8::to_string_policy(&self.0, policy)
+    }
+
+    /// Returns an object that displays the path with non-UTF-8 bytes escaped as `\xNN`.
+    ///
+    /// See [LossyPolicy::Escape].
+    pub fn display_escaped(&self) -> EscapedDisplay<'_> {
+        EscapedDisplay(&self.0)
     }
 
-    pub fn join<P: AsRef<Path>>(&self, path: P) -> AbsPathBuf {
-        AbsPathBuf(self.0.join(path))
+    /// Views the path as a string, failing with the raw bytes if it is not valid UTF-8.
+    ///
+    /// Never allocates, and unlike [Path::to_string_lossy] never loses information; the result
+    /// converts back with [AbsPathBuf::from_utf8_cow].
+    pub fn to_cow_str(&self) -> Result<Cow<'_, str>, NonUtf8Error> {
+        crate::utf8::to_cow_str(&self.0)
+    }
+}
+
+#[cfg(feature = "utf8-strict")]
+impl AbsPath {
+    /// Returns the path as a string slice.
+    ///
+    /// Only available under the `utf8-strict` feature, which guarantees the path is valid UTF-8.
+    pub fn as_str(&self) -> &str {
+        crate::utf8::as_str(&self.0)
     }
 }
 
@@ -113,7 +588,7 @@ impl serde::Serialize for AbsPathBuf {
     where
         S: serde::Serializer,
     {
-        self.0.serialize(serializer)
+        (**self).serialize(serializer)
     }
 }
 
@@ -124,6 +599,8 @@ impl<'de> serde::Deserialize<'de> for AbsPathBuf {
         D: serde::Deserializer<'de>,
     {
         let path_buf = PathBuf::deserialize(deserializer)?;
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(&path_buf).map_err(serde::de::Error::custom)?;
         if path_buf.is_absolute() {
             Ok(AbsPathBuf(path_buf))
         } else {
@@ -134,11 +611,36 @@ impl<'de> serde::Deserialize<'de> for AbsPathBuf {
 
 #[cfg(feature = "serde")]
 impl serde::Serialize for AbsPath {
+    /// On Windows, a verbatim prefix is dropped where [crate::win::simplify_verbatim] allows.
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        self.0.serialize(serializer)
+        crate::win::serialize_path(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for &'a AbsPath
+where
+    'de: 'a,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let path = <&Path>::deserialize(deserializer)?;
+        AbsPath::new(path).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Box<AbsPath> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        AbsPathBuf::deserialize(deserializer).map(Box::from)
     }
 }
 
@@ -174,4 +676,21 @@ mod serde_tests {
         use serde_test::{Token, assert_ser_tokens};
         assert_ser_tokens(&abs_path, &[Token::Str("/home/user/file.txt")]);
     }
+
+    #[test]
+    fn test_abs_path_deserialize_borrowed() {
+        let path_buf = AbsPathBuf::new("/home/user/file.txt").unwrap();
+        let abs_path: &AbsPath = &path_buf;
+        assert_tokens(&abs_path, &[Token::BorrowedStr("/home/user/file.txt")]);
+        assert_de_tokens_error::<&AbsPath>(
+            &[Token::BorrowedStr("relative/path")],
+            "path must be absolute",
+        );
+    }
+
+    #[test]
+    fn test_boxed_abs_path() {
+        let boxed: Box<AbsPath> = AbsPathBuf::new("/home/user/file.txt").unwrap().into();
+        assert_tokens(&boxed, &[Token::Str("/home/user/file.txt")]);
+    }
 }
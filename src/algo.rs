@@ -0,0 +1,63 @@
+//! Algorithms over collections of paths.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::{AbsPath, AbsPathBuf, RelPath, RelPathBuf};
+
+/// Returns the longest common leading sequence of components of `paths`.
+///
+/// Comparison is component-wise, so `src/lib` and `src/library` share `src`, and no
+/// normalization is performed first. The result is empty if `paths` is empty or the paths
+/// have no component in common.
+///
+/// ```
+/// use dirge::{RelPathBuf, algo};
+///
+/// let paths = ["src/bin/a.rs", "src/bin/b.rs", "src/lib.rs"].map(|p| RelPathBuf::new(p).unwrap());
+/// assert_eq!(algo::common_prefix(&paths), RelPathBuf::new("src").unwrap());
+/// ```
+pub fn common_prefix<I>(paths: I) -> RelPathBuf
+where
+    I: IntoIterator,
+    I::Item: AsRef<RelPath>,
+{
+    RelPathBuf::from_path_buf_unchecked(fold(paths, |p| &**AsRef::<RelPath>::as_ref(p)))
+}
+
+/// Like [common_prefix], for absolute paths.
+///
+/// Returns `None` if `paths` is empty or the paths share no root, e.g. `C:\a` and `D:\a` on
+/// Windows, where drive letters and UNC shares must match exactly.
+pub fn common_abs_prefix<I>(paths: I) -> Option<AbsPathBuf>
+where
+    I: IntoIterator,
+    I::Item: AsRef<AbsPath>,
+{
+    let prefix = fold(paths, |p| &**AsRef::<AbsPath>::as_ref(p));
+    // Absolute paths share a root only if the common part is itself absolute.
+    prefix
+        .is_absolute()
+        .then(|| AbsPathBuf::from_path_buf_unchecked(prefix))
+}
+
+/// Narrows the first path's components down to those every later path shares, in one pass.
+fn fold<T>(paths: impl IntoIterator<Item = T>, as_path: impl Fn(&T) -> &Path) -> PathBuf {
+    let mut paths = paths.into_iter();
+    let Some(first) = paths.next() else {
+        return PathBuf::new();
+    };
+    let first = as_path(&first).to_path_buf();
+    let mut common: Vec<Component<'_>> = first.components().collect();
+    for path in paths {
+        let shared = common
+            .iter()
+            .zip(as_path(&path).components())
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        common.truncate(shared);
+        if common.is_empty() {
+            break;
+        }
+    }
+    common.iter().map(|c| c.as_os_str()).collect::<PathBuf>()
+}
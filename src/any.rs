@@ -0,0 +1,186 @@
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{AbsPathBuf, Kind, NormPathBuf, PathKind, RelPathBuf};
+
+/// Dyn-compatible view of any dirge path, for heterogeneous collections such as
+/// `Vec<Box<dyn AnyPath>>`.
+///
+/// The methods are prefixed with `any_` so they don't clash with [PathKind]'s, which the
+/// same types implement.
+pub trait AnyPath {
+    fn any_path(&self) -> &Path;
+
+    fn any_kind(&self) -> Kind;
+
+    fn to_path_value(&self) -> PathValue;
+}
+
+impl<P: PathKind + ?Sized> AnyPath for P {
+    fn any_path(&self) -> &Path {
+        self.as_ref()
+    }
+
+    fn any_kind(&self) -> Kind {
+        P::KIND
+    }
+
+    fn to_path_value(&self) -> PathValue {
+        let path = self.as_ref().to_path_buf();
+        // The kind tells us which invariant `path` already satisfies.
+        match P::KIND {
+            Kind::Absolute => PathValue::Abs(AbsPathBuf::from_path_buf_unchecked(path)),
+            Kind::Relative => PathValue::Rel(RelPathBuf::from_path_buf_unchecked(path)),
+            Kind::Normalized => PathValue::Norm(NormPathBuf::from_path_buf_unchecked(path)),
+        }
+    }
+}
+
+impl fmt::Debug for dyn AnyPath + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.any_path(), f)
+    }
+}
+
+/// An owned path that is one of dirge's path types.
+///
+/// Useful where a value may be "either an absolute or a relative path". When deserialized,
+/// absolute input becomes [PathValue::Abs] and relative input [PathValue::Rel];
+/// [PathValue::Norm] is only produced explicitly.
+#[derive(PartialEq, Eq, Clone)]
+pub enum PathValue {
+    Abs(AbsPathBuf),
+    Rel(RelPathBuf),
+    Norm(NormPathBuf),
+}
+
+impl PathValue {
+    /// Classifies `p` as absolute or relative without touching the filesystem.
+    ///
+    /// Fails on the Windows paths that are neither, such as `\x` and `D:x`; see [RelPathBuf].
+    pub fn new<P: Into<PathBuf>>(p: P) -> io::Result<Self> {
+        let path = p.into();
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(&path)?;
+        if path.is_absolute() {
+            Ok(PathValue::Abs(AbsPathBuf::from_path_buf_unchecked(path)))
+        } else {
+            RelPathBuf::try_from(path).map(PathValue::Rel)
+        }
+    }
+
+    pub fn as_path(&self) -> &Path {
+        self.as_ref()
+    }
+
+    pub fn kind(&self) -> Kind {
+        match self {
+            PathValue::Abs(_) => Kind::Absolute,
+            PathValue::Rel(_) => Kind::Relative,
+            PathValue::Norm(_) => Kind::Normalized,
+        }
+    }
+}
+
+impl fmt::Debug for PathValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathValue::Abs(p) => f.debug_tuple("Abs").field(p).finish(),
+            PathValue::Rel(p) => f.debug_tuple("Rel").field(p).finish(),
+            PathValue::Norm(p) => f.debug_tuple("Norm").field(p).finish(),
+        }
+    }
+}
+
+impl AsRef<Path> for PathValue {
+    fn as_ref(&self) -> &Path {
+        match self {
+            PathValue::Abs(p) => p.as_ref(),
+            PathValue::Rel(p) => p.as_ref(),
+            PathValue::Norm(p) => p.as_ref(),
+        }
+    }
+}
+
+impl AnyPath for PathValue {
+    fn any_path(&self) -> &Path {
+        self.as_ref()
+    }
+
+    fn any_kind(&self) -> Kind {
+        PathValue::kind(self)
+    }
+
+    fn to_path_value(&self) -> PathValue {
+        self.clone()
+    }
+}
+
+impl From<AbsPathBuf> for PathValue {
+    fn from(p: AbsPathBuf) -> Self {
+        PathValue::Abs(p)
+    }
+}
+
+impl From<RelPathBuf> for PathValue {
+    fn from(p: RelPathBuf) -> Self {
+        PathValue::Rel(p)
+    }
+}
+
+impl From<NormPathBuf> for PathValue {
+    fn from(p: NormPathBuf) -> Self {
+        PathValue::Norm(p)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PathValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.as_path().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PathValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let path_buf = PathBuf::deserialize(deserializer)?;
+        PathValue::new(path_buf).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use serde_test::{Token, assert_de_tokens, assert_tokens};
+
+    #[test]
+    fn test_path_value_abs() {
+        let value = PathValue::new("/etc/app.toml").unwrap();
+        assert_eq!(value.kind(), Kind::Absolute);
+        assert_tokens(&value, &[Token::Str("/etc/app.toml")]);
+    }
+
+    #[test]
+    fn test_path_value_rel() {
+        let value = PathValue::new("conf/app.toml").unwrap();
+        assert_eq!(value.kind(), Kind::Relative);
+        assert_tokens(&value, &[Token::Str("conf/app.toml")]);
+    }
+
+    #[test]
+    fn test_path_value_norm_deserializes_as_rel() {
+        let norm = PathValue::from(NormPathBuf::new("a/./b").unwrap());
+        let expected = PathValue::Rel(RelPathBuf::new("a/b").unwrap());
+        assert_ne!(norm, expected);
+        assert_de_tokens(&expected, &[Token::Str("a/b")]);
+    }
+}
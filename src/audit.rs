@@ -0,0 +1,79 @@
+use std::io;
+
+use crate::{AbsPath, AbsPathBuf};
+
+/// A problem found by [AbsPath::audit_ancestors].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditFinding {
+    /// The component the issue applies to.
+    pub path: AbsPathBuf,
+    pub issue: AuditIssue,
+}
+
+/// The kinds of issue reported by [AbsPath::audit_ancestors].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditIssue {
+    /// The component is a symlink, so what it refers to can change underneath the caller.
+    Symlink,
+    /// Any user can write to the component, and it lacks the sticky bit.
+    WorldWritable,
+    /// Members of the owning group can write to the component, and it lacks the sticky bit.
+    GroupWritable,
+    /// The component is owned by neither root nor the current user; holds the owner's uid.
+    UntrustedOwner(u32),
+}
+
+impl AbsPath {
+    /// Checks the path and each of its ancestors for properties that make it unsafe to trust.
+    ///
+    /// Modelled on the checks systemd and OpenSSH perform before loading configuration or keys.
+    /// Components are examined lexically and without following symlinks; components that do not
+    /// exist are skipped. An empty result means no issues were found.
+    ///
+    /// Permission and ownership checks are only performed on Unix.
+    pub fn audit_ancestors(&self) -> io::Result<Vec<AuditFinding>> {
+        #[cfg(unix)]
+        let uid = crate::fs::current_uid()?;
+
+        let mut findings = Vec::new();
+        for ancestor in self.ancestors() {
+            let metadata = match ancestor.symlink_metadata() {
+                Ok(metadata) => metadata,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(crate::fs::with_path(e, ancestor)),
+            };
+
+            let mut report = |issue| {
+                findings.push(AuditFinding {
+                    path: ancestor.to_owned(),
+                    issue,
+                })
+            };
+
+            if metadata.file_type().is_symlink() {
+                report(AuditIssue::Symlink);
+            }
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+
+                let mode = metadata.mode();
+                let sticky = mode & 0o1000 != 0;
+                if !metadata.file_type().is_symlink() && !sticky {
+                    if mode & 0o002 != 0 {
+                        report(AuditIssue::WorldWritable);
+                    } else if mode & 0o020 != 0 {
+                        report(AuditIssue::GroupWritable);
+                    }
+                }
+                if metadata.uid() != 0 && metadata.uid() != uid {
+                    report(AuditIssue::UntrustedOwner(metadata.uid()));
+                }
+            }
+        }
+
+        findings.reverse();
+        Ok(findings)
+    }
+}
@@ -0,0 +1,102 @@
+//! Operations over large collections of paths.
+
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::{num::NonZeroUsize, thread};
+
+use crate::{AbsPath, AbsPathBuf};
+
+/// Rewrites every path in `paths` that lies at or below `from` to lie at the same place below
+/// `to`, returning how many were rewritten.
+///
+/// Matching is component-wise, like [std::path::Path::starts_with], so `/ws` does not match
+/// `/ws-old`. On Unix the paths are edited in place, so a prefix that is not longer than the
+/// old one never reallocates; elsewhere each rewritten path gets a new buffer.
+///
+/// ```
+/// # #[cfg(unix)] {
+/// use dirge::{AbsPathBuf, bulk};
+///
+/// let abs = |s: &str| AbsPathBuf::new(s).unwrap();
+/// let mut paths = vec![abs("/old/ws/src/a.rs"), abs("/old/ws"), abs("/other")];
+/// let n = bulk::rewrite_prefix(&mut paths, abs("/old/ws"), abs("/ws"));
+/// assert_eq!(n, 2);
+/// assert_eq!(paths, [abs("/ws/src/a.rs"), abs("/ws"), abs("/other")]);
+/// # }
+/// ```
+pub fn rewrite_prefix<P, Q>(paths: &mut [AbsPathBuf], from: P, to: Q) -> usize
+where
+    P: AsRef<AbsPath>,
+    Q: AsRef<AbsPath>,
+{
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let mut rewritten = 0;
+    for path in paths {
+        if rewrite_one(path, from, to) {
+            rewritten += 1;
+        }
+    }
+    rewritten
+}
+
+/// Like [rewrite_prefix], splitting the work across up to `threads` scoped threads.
+pub fn rewrite_prefix_parallel<P, Q>(
+    paths: &mut [AbsPathBuf],
+    from: P,
+    to: Q,
+    threads: NonZeroUsize,
+) -> usize
+where
+    P: AsRef<AbsPath>,
+    Q: AsRef<AbsPath>,
+{
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let chunk = paths.len().div_ceil(threads.get()).max(1);
+    thread::scope(|s| {
+        let handles: Vec<_> = paths
+            .chunks_mut(chunk)
+            .map(|chunk| s.spawn(move || rewrite_prefix(chunk, from, to)))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
+fn rewrite_one(path: &mut AbsPathBuf, from: &AbsPath, to: &AbsPath) -> bool {
+    let Ok(rest) = path.strip_prefix(from) else {
+        return false;
+    };
+
+    #[cfg(unix)]
+    {
+        // `rest` is a suffix of the path's own bytes.
+        let keep = rest.as_os_str().len();
+        let buf = path.as_mut_path_buf_unchecked();
+        *buf = splice_prefix(std::mem::take(buf), keep, to);
+    }
+    #[cfg(not(unix))]
+    {
+        *path = if rest.as_os_str().is_empty() {
+            to.to_owned()
+        } else {
+            to.join(rest)
+        };
+    }
+    true
+}
+
+/// Replaces everything but the last `keep` bytes of `path` with `to`, in place.
+#[cfg(unix)]
+fn splice_prefix(path: PathBuf, keep: usize, to: &AbsPath) -> PathBuf {
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+    let mut bytes = path.into_os_string().into_vec();
+    let start = bytes.len() - keep;
+    let to = to.as_os_str().as_bytes();
+    let sep: &[u8] = if keep > 0 && !to.ends_with(b"/") {
+        b"/"
+    } else {
+        b""
+    };
+    bytes.splice(..start, to.iter().chain(sep).copied());
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
@@ -0,0 +1,243 @@
+//! An in-memory cache keyed by relative paths, with invalidation of whole subtrees.
+//!
+//! ```
+//! use dirge::{RelPathBuf, cache::PathCache};
+//!
+//! let rel = |s: &str| RelPathBuf::new(s).unwrap();
+//! let mut cache = PathCache::new();
+//! cache.insert(rel("assets/a.png"), 1);
+//! cache.insert(rel("assets/ui/b.png"), 2);
+//! cache.insert(rel("assets-old/c.png"), 3);
+//!
+//! // Renaming `assets` invalidates everything below it, but not `assets-old`.
+//! let evicted = cache.invalidate_prefix(rel("assets"));
+//! assert_eq!(evicted.len(), 2);
+//! assert_eq!(cache.get(rel("assets-old/./c.png")), Some(&3));
+//! ```
+//!
+//! [CanonCache] memoizes [AbsPath::canonicalize] for processes that resolve the same paths over
+//! and over.
+
+use std::{collections::BTreeMap, io, time::SystemTime};
+
+use crate::{AbsPath, AbsPathBuf, RelPath, RelPathBuf};
+
+/// Values cached per relative path.
+///
+/// Keys are normalized lexically, so `a/./b` and `a/c/../b` name the same entry. Entries are
+/// kept in component order, which puts every descendant of a directory directly after it.
+#[derive(Debug, Clone)]
+pub struct PathCache<V> {
+    entries: BTreeMap<RelPathBuf, V>,
+}
+
+impl<V> Default for PathCache<V> {
+    fn default() -> Self {
+        PathCache {
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+impl<V> PathCache<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Caches `value` for `path`, returning the value it replaces.
+    pub fn insert<P: AsRef<RelPath>>(&mut self, path: P, value: V) -> Option<V> {
+        self.entries.insert(key(path.as_ref()), value)
+    }
+
+    pub fn get<P: AsRef<RelPath>>(&self, path: P) -> Option<&V> {
+        self.entries.get(&key(path.as_ref()))
+    }
+
+    pub fn get_mut<P: AsRef<RelPath>>(&mut self, path: P) -> Option<&mut V> {
+        self.entries.get_mut(&key(path.as_ref()))
+    }
+
+    /// Returns the cached value for `path`, computing and caching it first if needed.
+    pub fn get_or_insert_with<P, F>(&mut self, path: P, f: F) -> &mut V
+    where
+        P: AsRef<RelPath>,
+        F: FnOnce() -> V,
+    {
+        self.entries.entry(key(path.as_ref())).or_insert_with(f)
+    }
+
+    /// Removes the entry for exactly `path`.
+    pub fn invalidate<P: AsRef<RelPath>>(&mut self, path: P) -> Option<V> {
+        self.entries.remove(&key(path.as_ref()))
+    }
+
+    /// Removes the entry for `prefix` and for every path below it, returning them in order.
+    ///
+    /// This is what a change to a directory needs: after a rename or removal of `prefix`,
+    /// nothing cached beneath it is valid. Matching is component-wise, so invalidating `a`
+    /// keeps `a-b`. Invalidating `.` clears the cache.
+    pub fn invalidate_prefix<P: AsRef<RelPath>>(&mut self, prefix: P) -> Vec<(RelPathBuf, V)> {
+        let prefix = key(prefix.as_ref());
+        if prefix.as_os_str() == "." {
+            return std::mem::take(&mut self.entries).into_iter().collect();
+        }
+
+        // Descendants sort directly after `prefix`, so the subtree is one contiguous range.
+        let mut subtree = self.entries.split_off(&prefix);
+        let end = subtree.keys().find(|k| !k.starts_with(&prefix)).cloned();
+        if let Some(end) = end {
+            self.entries.append(&mut subtree.split_off(&end));
+        }
+        subtree.into_iter().collect()
+    }
+
+    /// Iterates over the entries in component order.
+    pub fn iter(&self) -> impl Iterator<Item = (&RelPath, &V)> {
+        self.entries.iter().map(|(k, v)| (&**k, v))
+    }
+}
+
+/// A bounded cache of canonicalized absolute paths.
+///
+/// A hit costs one `lstat` instead of the one-per-component walk `canonicalize` performs: the
+/// entry is reused only while the modification time of the path itself is unchanged, which
+/// catches the path being replaced, e.g. a symlink being retargeted. Changes further up, such
+/// as an ancestor symlink moving, are not detected; feed them from a file watcher into
+/// [CanonCache::invalidate_prefix].
+///
+/// Keys are taken as given and not normalized, since `..` after a symlink resolves differently
+/// on disk than lexically. When full, the least recently used entry is evicted.
+///
+/// ```
+/// use dirge::{AbsPathBuf, cache::CanonCache};
+///
+/// let mut cache = CanonCache::new(1024);
+/// let dir = AbsPathBuf::new(std::env::temp_dir()).unwrap();
+/// let first = cache.canonicalize(&dir).unwrap();
+/// assert_eq!(cache.canonicalize(&dir).unwrap(), first);
+/// assert_eq!(cache.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CanonCache {
+    entries: BTreeMap<AbsPathBuf, CanonEntry>,
+    /// Each entry's path keyed by its `used` tick, so the least recently used comes first.
+    recency: BTreeMap<u64, AbsPathBuf>,
+    capacity: usize,
+    tick: u64,
+}
+
+#[derive(Debug, Clone)]
+struct CanonEntry {
+    canonical: AbsPathBuf,
+    mtime: Option<SystemTime>,
+    used: u64,
+}
+
+impl CanonCache {
+    /// A cache holding at most `capacity` entries. A capacity of zero caches nothing.
+    pub fn new(capacity: usize) -> Self {
+        CanonCache {
+            entries: BTreeMap::new(),
+            recency: BTreeMap::new(),
+            capacity,
+            tick: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Like [AbsPath::canonicalize], answering from the cache while the entry is still valid.
+    pub fn canonicalize<P: AsRef<AbsPath>>(&mut self, path: P) -> io::Result<AbsPathBuf> {
+        let path = path.as_ref();
+        self.tick += 1;
+        let mtime = mtime(path);
+
+        if let Some(entry) = self.entries.get_mut(path) {
+            if entry.mtime.is_some() && entry.mtime == mtime {
+                let path = self.recency.remove(&entry.used).expect("entry has a tick");
+                entry.used = self.tick;
+                self.recency.insert(self.tick, path);
+                return Ok(entry.canonical.clone());
+            }
+            self.remove(path);
+        }
+
+        let canonical = path.canonicalize()?;
+        if self.capacity == 0 {
+            return Ok(canonical);
+        }
+        if self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        self.recency.insert(self.tick, path.to_owned());
+        self.entries.insert(
+            path.to_owned(),
+            CanonEntry {
+                canonical: canonical.clone(),
+                mtime,
+                used: self.tick,
+            },
+        );
+        Ok(canonical)
+    }
+
+    /// Forgets `path`. Returns whether it was cached.
+    pub fn invalidate<P: AsRef<AbsPath>>(&mut self, path: P) -> bool {
+        self.remove(path.as_ref()).is_some()
+    }
+
+    /// Forgets every entry whose path or canonical form lies at or below `prefix`, returning
+    /// how many were removed.
+    pub fn invalidate_prefix<P: AsRef<AbsPath>>(&mut self, prefix: P) -> usize {
+        let prefix = prefix.as_ref();
+        let before = self.entries.len();
+        self.entries.retain(|path, entry| {
+            !path.starts_with(prefix) && !entry.canonical.starts_with(prefix)
+        });
+        let entries = &self.entries;
+        self.recency.retain(|_, path| entries.contains_key(path));
+        before - self.entries.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    fn remove(&mut self, path: &AbsPath) -> Option<CanonEntry> {
+        let entry = self.entries.remove(path)?;
+        self.recency.remove(&entry.used);
+        Some(entry)
+    }
+
+    fn evict(&mut self) {
+        if let Some((_, oldest)) = self.recency.pop_first() {
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+fn mtime(path: &AbsPath) -> Option<SystemTime> {
+    std::fs::symlink_metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+fn key(path: &RelPath) -> RelPathBuf {
+    // Normalizing a relative path never makes it absolute.
+    RelPathBuf::from_path_buf_unchecked(crate::normalize(path))
+}
@@ -0,0 +1,85 @@
+//! Typed access to the directories Cargo hands to builds, tests and build scripts.
+//!
+//! [manifest_dir] and [out_dir] only read the environment Cargo sets up. [workspace_root] and
+//! [target_dir] ask Cargo itself and need the `cargo-locate` feature.
+
+use std::io;
+
+use crate::AbsPathBuf;
+
+fn env_dir(var: &str) -> io::Result<AbsPathBuf> {
+    match std::env::var_os(var) {
+        Some(dir) if !dir.is_empty() => AbsPathBuf::new(dir),
+        _ => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("environment variable {var} is not set"),
+        )),
+    }
+}
+
+/// The directory containing the current package's `Cargo.toml`, from `CARGO_MANIFEST_DIR`.
+///
+/// Cargo sets the variable for build scripts, tests and `cargo run`. Use
+/// `env!("CARGO_MANIFEST_DIR")` instead if the path should be baked in at compile time.
+pub fn manifest_dir() -> io::Result<AbsPathBuf> {
+    env_dir("CARGO_MANIFEST_DIR")
+}
+
+/// The directory a build script should write its output to, from `OUT_DIR`.
+///
+/// Only set while a build script runs.
+pub fn out_dir() -> io::Result<AbsPathBuf> {
+    env_dir("OUT_DIR")
+}
+
+/// The root of the workspace the current package belongs to.
+///
+/// Runs `cargo locate-project --workspace` from [manifest_dir], or from the current directory
+/// outside of Cargo, using the `cargo` binary from `CARGO` if set.
+///
+/// ```no_run
+/// let root = dirge::cargo::workspace_root().unwrap();
+/// assert!(root.try_join("Cargo.toml").unwrap().is_file());
+/// ```
+#[cfg(feature = "cargo-locate")]
+pub fn workspace_root() -> io::Result<AbsPathBuf> {
+    use std::process::Command;
+
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut cmd = Command::new(cargo);
+    cmd.args(["locate-project", "--workspace", "--message-format", "plain"]);
+    if let Ok(dir) = manifest_dir() {
+        cmd.current_dir(dir);
+    }
+
+    let output = cmd.output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "cargo locate-project failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "cargo output is not UTF-8"))?;
+    let manifest = AbsPathBuf::new(stdout.trim_end())?;
+    manifest.parent().map(ToOwned::to_owned).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: manifest has no parent directory", manifest.display()),
+        )
+    })
+}
+
+/// The directory Cargo builds into.
+///
+/// This is `CARGO_TARGET_DIR` if set, relative to the current directory like Cargo treats it,
+/// and `target` under [workspace_root] otherwise. A `build.target-dir` set in Cargo's
+/// configuration files is not consulted.
+#[cfg(feature = "cargo-locate")]
+pub fn target_dir() -> io::Result<AbsPathBuf> {
+    match std::env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) if !dir.is_empty() => AbsPathBuf::new(dir),
+        _ => workspace_root()?.try_join("target"),
+    }
+}
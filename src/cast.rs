@@ -0,0 +1,91 @@
+//! Layout casts between the path types and the std types they wrap.
+//!
+//! This is the only module allowed to use `unsafe`. Every cast here relies on one fact: each
+//! type passed to `transparent!` is declared `#[repr(transparent)]` with the listed std type
+//! as its only field, so it has the same size, alignment and pointer metadata, and a pointer to
+//! one is a valid pointer to the other. The `const` assertions below check the sized half of
+//! that at compile time.
+//!
+//! The casts are memory-safe whatever the contents, so the functions themselves are safe.
+//! Those that go from the std type to a path type skip its invariant and are named
+//! `..._unchecked`; callers validate first, as with the types' other unchecked constructors.
+
+#![allow(unsafe_code)]
+
+use std::{
+    mem::ManuallyDrop,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{AbsPath, AbsPathBuf, NormPath, NormPathBuf, RelPath, RelPathBuf};
+
+/// An owned path type with the same layout as `Inner`.
+///
+/// # Safety
+///
+/// `Self` must be `#[repr(transparent)]` with an `Inner` as its only field. Implemented only by
+/// `transparent!`.
+pub(crate) unsafe trait Transparent: Sized {
+    type Inner;
+}
+
+macro_rules! transparent {
+    ($($owned:ident($owned_inner:ty), $borrowed:ident($borrowed_inner:ty);)*) => {$(
+        // SAFETY: see the module documentation.
+        unsafe impl Transparent for $owned {
+            type Inner = $owned_inner;
+        }
+
+        const _: () = {
+            assert!(size_of::<$owned>() == size_of::<$owned_inner>());
+            assert!(align_of::<$owned>() == align_of::<$owned_inner>());
+        };
+
+        impl $borrowed {
+            pub(crate) fn from_arc_unchecked(arc: Arc<$borrowed_inner>) -> Arc<Self> {
+                // SAFETY: see the module documentation.
+                unsafe { Arc::from_raw(Arc::into_raw(arc) as *const Self) }
+            }
+
+            pub(crate) fn from_box_unchecked(boxed: Box<$borrowed_inner>) -> Box<Self> {
+                // SAFETY: see the module documentation.
+                unsafe { Box::from_raw(Box::into_raw(boxed) as *mut Self) }
+            }
+        }
+    )*};
+}
+
+transparent! {
+    AbsPathBuf(PathBuf), AbsPath(Path);
+    RelPathBuf(PathBuf), RelPath(Path);
+    NormPathBuf(PathBuf), NormPath(Path);
+}
+
+/// Views a slice of paths as the type they wrap.
+pub(crate) fn inner_slice<T: Transparent>(paths: &[T]) -> &[T::Inner] {
+    // SAFETY: see the module documentation.
+    unsafe { std::slice::from_raw_parts(paths.as_ptr().cast(), paths.len()) }
+}
+
+/// Views a slice of the wrapped type as paths. The caller has checked every element.
+pub(crate) fn slice_unchecked<T: Transparent>(paths: &[T::Inner]) -> &[T] {
+    // SAFETY: see the module documentation.
+    unsafe { std::slice::from_raw_parts(paths.as_ptr().cast(), paths.len()) }
+}
+
+/// Converts a vector of paths into the type they wrap, reusing its allocation.
+pub(crate) fn into_inner_vec<T: Transparent>(paths: Vec<T>) -> Vec<T::Inner> {
+    let mut paths = ManuallyDrop::new(paths);
+    // SAFETY: see the module documentation. Equal layout and alignment mean the allocation
+    // fits the new element type, and ownership moves to the new vector.
+    unsafe { Vec::from_raw_parts(paths.as_mut_ptr().cast(), paths.len(), paths.capacity()) }
+}
+
+/// Converts a vector of the wrapped type into paths, reusing its allocation. The caller has
+/// checked every element.
+pub(crate) fn vec_unchecked<T: Transparent>(paths: Vec<T::Inner>) -> Vec<T> {
+    let mut paths = ManuallyDrop::new(paths);
+    // SAFETY: as in `into_inner_vec`.
+    unsafe { Vec::from_raw_parts(paths.as_mut_ptr().cast(), paths.len(), paths.capacity()) }
+}
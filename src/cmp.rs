@@ -0,0 +1,65 @@
+//! Comparisons between dirge's path types and the standard path and string types.
+//!
+//! Mirrors the matrix [std::path] provides for [Path] and [PathBuf], plus plain strings, so
+//! `abs == Path::new("/etc")` and `rel == "a/b"` work without dereferencing. All of them
+//! compare components like [Path] does, so `a//b` equals `a/b`.
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use crate::{AbsPath, AbsPathBuf, NormPath, NormPathBuf, RelPath, RelPathBuf};
+
+macro_rules! impl_cmp {
+    ($(($lhs:ty, $rhs:ty)),* $(,)?) => {
+        $(
+            impl<'a> PartialEq<$rhs> for $lhs {
+                fn eq(&self, other: &$rhs) -> bool {
+                    <Path as PartialEq>::eq(self.as_ref(), other.as_ref())
+                }
+            }
+
+            impl<'a> PartialEq<$lhs> for $rhs {
+                fn eq(&self, other: &$lhs) -> bool {
+                    <Path as PartialEq>::eq(self.as_ref(), other.as_ref())
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_cmp_all {
+    ($slice:ty, $buf:ty) => {
+        impl_cmp!(
+            ($buf, $slice),
+            ($buf, &'a $slice),
+            ($buf, Path),
+            ($buf, &'a Path),
+            ($buf, PathBuf),
+            ($slice, Path),
+            ($slice, PathBuf),
+            (&'a $slice, PathBuf),
+            ($buf, str),
+            ($buf, &'a str),
+            ($buf, String),
+            ($slice, str),
+            ($slice, &'a str),
+            ($slice, String),
+            (&'a $slice, str),
+            (&'a $slice, String),
+            ($buf, OsStr),
+            ($buf, &'a OsStr),
+            ($buf, OsString),
+            ($slice, OsStr),
+            ($slice, &'a OsStr),
+            ($slice, OsString),
+            (&'a $slice, OsStr),
+            (&'a $slice, OsString),
+        );
+    };
+}
+
+impl_cmp_all!(AbsPath, AbsPathBuf);
+impl_cmp_all!(RelPath, RelPathBuf);
+impl_cmp_all!(NormPath, NormPathBuf);
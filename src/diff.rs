@@ -0,0 +1,242 @@
+//! Comparing two sets of relative paths, e.g. a generated directory layout against a snapshot.
+//!
+//! ```
+//! use dirge::{RelPathBuf, diff};
+//!
+//! let rel = |s: &str| RelPathBuf::new(s).unwrap();
+//! let before = [rel("a.txt"), rel("b/c.txt")];
+//! let after = [rel("b/c.txt"), rel("d.txt")];
+//!
+//! let diff = diff::tree_diff(before, after);
+//! assert_eq!(diff.to_string(), "- a.txt\n  b/c.txt\n+ d.txt\n");
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsStr,
+    fmt,
+};
+
+use crate::{RelPath, RelPathBuf};
+
+/// The result of [tree_diff]. Each set is ordered component-wise.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TreeDiff {
+    /// Paths only in the second tree.
+    pub added: BTreeSet<RelPathBuf>,
+    /// Paths only in the first tree.
+    pub removed: BTreeSet<RelPathBuf>,
+    /// Paths in both trees.
+    pub common: BTreeSet<RelPathBuf>,
+}
+
+/// Compares the paths in `a` against those in `b`. Duplicates are ignored.
+pub fn tree_diff<A, B>(a: A, b: B) -> TreeDiff
+where
+    A: IntoIterator<Item = RelPathBuf>,
+    B: IntoIterator<Item = RelPathBuf>,
+{
+    let mut removed: BTreeSet<_> = a.into_iter().collect();
+    let mut diff = TreeDiff::default();
+    for path in b {
+        if removed.remove(&path) {
+            diff.common.insert(path);
+        } else if !diff.common.contains(&path) {
+            diff.added.insert(path);
+        }
+    }
+    diff.removed = removed;
+    diff
+}
+
+impl TreeDiff {
+    /// Returns `true` if both trees contain the same paths.
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Returns an object that displays only the added and removed paths.
+    pub fn changes(&self) -> Changes<'_> {
+        Changes(self)
+    }
+
+    fn render(&self, f: &mut fmt::Formatter<'_>, with_common: bool) -> fmt::Result {
+        let mut lines: Vec<(&RelPathBuf, char)> = self
+            .added
+            .iter()
+            .map(|p| (p, '+'))
+            .chain(self.removed.iter().map(|p| (p, '-')))
+            .collect();
+        if with_common {
+            lines.extend(self.common.iter().map(|p| (p, ' ')));
+        }
+        lines.sort();
+
+        for (path, marker) in lines {
+            writeln!(f, "{marker} {}", Portable(path))?;
+        }
+        Ok(())
+    }
+}
+
+/// One line per path, sorted, prefixed with `+` (added), `-` (removed) or a space (common).
+///
+/// Separators are always `/`, so the rendering is the same on every platform.
+impl fmt::Display for TreeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, true)
+    }
+}
+
+/// Displays a [TreeDiff] without its common paths. Created by [TreeDiff::changes].
+pub struct Changes<'a>(&'a TreeDiff);
+
+impl fmt::Display for Changes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.render(f, false)
+    }
+}
+
+/// A file that moved from one path to another with its contents unchanged, as found by
+/// [detect_renames].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rename {
+    pub from: RelPathBuf,
+    pub to: RelPathBuf,
+}
+
+/// The most removed × added pairs [detect_renames] ranks for one digest and file name.
+pub const MAX_RANKED_PAIRS: usize = 1 << 16;
+
+/// Pairs paths that disappeared from `old` with paths that appeared in `new` with the same
+/// digest, so a sync can move files instead of deleting and copying them.
+///
+/// The manifests map paths to content digests, e.g. from [crate::fs::hash_tree]. Only paths
+/// missing from the other manifest take part; a path present in both is never a rename
+/// source, so a new file with the same contents as a kept one is a copy, not a move. When a
+/// digest is shared by several candidates, pairs are chosen by path similarity: the same file
+/// name first, then the same extension, then the most leading directories in common. Ties are
+/// broken by path order, so the result is deterministic. Where more than [MAX_RANKED_PAIRS]
+/// candidates share a digest and a file name (or, failing that, an extension), they are paired
+/// in path order without ranking. Renames are returned sorted by source.
+///
+/// ```
+/// use dirge::{RelPathBuf, diff::{Rename, detect_renames}};
+/// use std::collections::BTreeMap;
+///
+/// let rel = |s: &str| RelPathBuf::new(s).unwrap();
+/// let old = BTreeMap::from([(rel("src/util.rs"), 1), (rel("README"), 2)]);
+/// let new = BTreeMap::from([(rel("src/util/mod.rs"), 1), (rel("README"), 2)]);
+///
+/// assert_eq!(
+///     detect_renames(&old, &new),
+///     [Rename { from: rel("src/util.rs"), to: rel("src/util/mod.rs") }]
+/// );
+/// ```
+pub fn detect_renames<D: Ord>(
+    old: &BTreeMap<RelPathBuf, D>,
+    new: &BTreeMap<RelPathBuf, D>,
+) -> Vec<Rename> {
+    let mut candidates: BTreeMap<&D, (Vec<&RelPathBuf>, Vec<&RelPathBuf>)> = BTreeMap::new();
+    for (path, digest) in old.iter().filter(|(p, _)| !new.contains_key(*p)) {
+        candidates.entry(digest).or_default().0.push(path);
+    }
+    for (path, digest) in new.iter().filter(|(p, _)| !old.contains_key(*p)) {
+        if let Some((_, added)) = candidates.get_mut(digest) {
+            added.push(path);
+        }
+    }
+
+    let mut renames = Vec::new();
+    for (mut removed, mut added) in candidates.into_values() {
+        // Each pass only ranks paths that share a key, so a digest shared by many files with
+        // different names doesn't pair every removed path with every added one.
+        let keys: [fn(&RelPath) -> Option<&OsStr>; 3] =
+            [|p| p.file_name(), |p| p.extension(), |_| None];
+        for key in keys {
+            let mut buckets: BTreeMap<Option<&OsStr>, (Vec<&RelPathBuf>, Vec<&RelPathBuf>)> =
+                BTreeMap::new();
+            for &path in &removed {
+                buckets.entry(key(path)).or_default().0.push(path);
+            }
+            for &path in &added {
+                if let Some((_, bucket)) = buckets.get_mut(&key(path)) {
+                    bucket.push(path);
+                }
+            }
+
+            let (mut used_from, mut used_to) = (BTreeSet::new(), BTreeSet::new());
+            for (from, to) in buckets.into_values() {
+                for (from, to) in pair(&from, &to) {
+                    used_from.insert(from);
+                    used_to.insert(to);
+                    renames.push(Rename {
+                        from: from.clone(),
+                        to: to.clone(),
+                    });
+                }
+            }
+            removed.retain(|p| !used_from.contains(p));
+            added.retain(|p| !used_to.contains(p));
+        }
+    }
+    renames.sort();
+    renames
+}
+
+/// Pairs the removed and added paths of one bucket, most leading directories in common first.
+/// Past [MAX_RANKED_PAIRS] candidate pairs they are paired in path order instead.
+fn pair<'a>(
+    removed: &[&'a RelPathBuf],
+    added: &[&'a RelPathBuf],
+) -> Vec<(&'a RelPathBuf, &'a RelPathBuf)> {
+    if removed.len().saturating_mul(added.len()) > MAX_RANKED_PAIRS {
+        return removed.iter().copied().zip(added.iter().copied()).collect();
+    }
+
+    let mut pairs: Vec<(usize, &RelPathBuf, &RelPathBuf)> = removed
+        .iter()
+        .flat_map(|&from| {
+            added
+                .iter()
+                .map(move |&to| (shared_dirs(from, to), from, to))
+        })
+        .collect();
+    pairs.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| (a.1, a.2).cmp(&(b.1, b.2))));
+
+    let (mut used_from, mut used_to) = (BTreeSet::new(), BTreeSet::new());
+    pairs
+        .into_iter()
+        .filter(|&(_, from, to)| {
+            !used_from.contains(from) && !used_to.contains(to) && {
+                used_from.insert(from);
+                used_to.insert(to);
+                true
+            }
+        })
+        .map(|(_, from, to)| (from, to))
+        .collect()
+}
+
+fn shared_dirs(a: &RelPath, b: &RelPath) -> usize {
+    let dirs = |p: &RelPath| p.parent().map(|p| p.components().count()).unwrap_or(0);
+    a.components()
+        .zip(b.components())
+        .take(dirs(a).min(dirs(b)))
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+struct Portable<'a>(&'a RelPathBuf);
+
+impl fmt::Display for Portable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, component) in self.0.components().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            write!(f, "{}", component.as_os_str().display())?;
+        }
+        Ok(())
+    }
+}
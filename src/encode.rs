@@ -0,0 +1,124 @@
+//! Encoding a list of paths into a single string, losslessly.
+//!
+//! Splitting `PATH`-style values on `:` (or `;` on Windows) breaks as soon as a path contains
+//! the separator. [join_list] escapes instead, so [split_list] always gets the original paths
+//! back:
+//!
+//! - the separator is [SEPARATOR],
+//! - a separator inside a path is written as `\` followed by it,
+//! - a backslash is written as `\\`,
+//! - a byte that is not valid UTF-8 is written as `\xNN`, like [crate::LossyPolicy::Escape].
+//!
+//! Windows paths therefore come out with doubled backslashes.
+//!
+//! ```
+//! # #[cfg(unix)] {
+//! use dirge::{AbsPathBuf, encode};
+//!
+//! let paths = [
+//!     AbsPathBuf::new("/usr/bin").unwrap(),
+//!     AbsPathBuf::new("/opt/odd:name").unwrap(),
+//! ];
+//! let joined = encode::join_list(&paths);
+//! assert_eq!(joined, r"/usr/bin:/opt/odd\:name");
+//! assert_eq!(encode::split_list(&joined).unwrap(), paths);
+//! # }
+//! ```
+
+use std::{ffi::OsString, fmt::Write, io, path::PathBuf};
+
+use crate::{AbsPath, AbsPathBuf};
+
+/// The character separating paths in a list: `;` on Windows and `:` elsewhere.
+pub const SEPARATOR: char = if cfg!(windows) { ';' } else { ':' };
+
+/// Joins `paths` into one string, escaping as described in the [module docs](self).
+///
+/// An empty list encodes to the empty string.
+pub fn join_list<I, P>(paths: I) -> String
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<AbsPath>,
+{
+    let mut out = String::new();
+    for (i, path) in paths.into_iter().enumerate() {
+        if i > 0 {
+            out.push(SEPARATOR);
+        }
+        for chunk in path.as_ref().as_os_str().as_encoded_bytes().utf8_chunks() {
+            for c in chunk.valid().chars() {
+                if c == '\\' || c == SEPARATOR {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+            for byte in chunk.invalid() {
+                let _ = write!(out, "\\x{byte:02X}");
+            }
+        }
+    }
+    out
+}
+
+/// Splits a string produced by [join_list] back into paths.
+///
+/// Fails with [io::ErrorKind::InvalidData] on a malformed escape or an entry that is not an
+/// absolute path.
+pub fn split_list(s: &str) -> io::Result<Vec<AbsPathBuf>> {
+    if s.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    let mut current = Vec::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('x') => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    let byte = (hex.len() == 2)
+                        .then(|| u8::from_str_radix(&hex, 16).ok())
+                        .flatten()
+                        .ok_or_else(|| invalid("malformed \\x escape"))?;
+                    current.push(byte);
+                }
+                Some(c) if c == '\\' || c == SEPARATOR => {
+                    current.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                _ => return Err(invalid("malformed escape")),
+            },
+            c if c == SEPARATOR => paths.push(to_abs(std::mem::take(&mut current))?),
+            c => current.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    paths.push(to_abs(current)?);
+    Ok(paths)
+}
+
+fn to_abs(bytes: Vec<u8>) -> io::Result<AbsPathBuf> {
+    let path = PathBuf::from(to_os_string(bytes)?);
+    if !path.is_absolute() {
+        return Err(invalid("list entry is not an absolute path"));
+    }
+    #[cfg(feature = "utf8-strict")]
+    crate::utf8::check(&path).map_err(|_| invalid("list entry is not valid UTF-8"))?;
+    Ok(AbsPathBuf::from_path_buf_unchecked(path))
+}
+
+#[cfg(unix)]
+fn to_os_string(bytes: Vec<u8>) -> io::Result<OsString> {
+    use std::os::unix::ffi::OsStringExt;
+    Ok(OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn to_os_string(bytes: Vec<u8>) -> io::Result<OsString> {
+    String::from_utf8(bytes)
+        .map(OsString::from)
+        .map_err(|_| invalid("list entry is not valid UTF-8"))
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_owned())
+}
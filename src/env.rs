@@ -0,0 +1,212 @@
+//! A snapshot of the process environment that path resolution depends on.
+//!
+//! Helpers that read `HOME`, `PATH` or the current directory behind the caller's back are hard
+//! to test and break hermetic builds. [PathEnv] captures those once, or takes them from the
+//! caller, and resolves against the snapshot only.
+//!
+//! ```
+//! # #[cfg(unix)] {
+//! use dirge::{AbsPathBuf, env::PathEnv};
+//!
+//! let abs = |s: &str| AbsPathBuf::new(s).unwrap();
+//! let env = PathEnv::new(abs("/work")).with_home(abs("/home/test"));
+//! assert_eq!(env.absolutize("~/notes.txt").unwrap(), abs("/home/test/notes.txt"));
+//! assert_eq!(env.absolutize("src/../lib.rs").unwrap(), abs("/work/src/../lib.rs"));
+//! # }
+//! ```
+
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{AbsPath, AbsPathBuf};
+
+/// The current directory, home directory, `PATH` and per-user directories to resolve against.
+///
+/// Build one with [PathEnv::capture] in production and [PathEnv::new] in tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathEnv {
+    cwd: AbsPathBuf,
+    home: Option<AbsPathBuf>,
+    path: Vec<AbsPathBuf>,
+    config_dir: Option<AbsPathBuf>,
+    cache_dir: Option<AbsPathBuf>,
+    data_dir: Option<AbsPathBuf>,
+}
+
+impl PathEnv {
+    /// An environment with only a current directory: no home, an empty `PATH` and no per-user
+    /// directories.
+    pub fn new<P: AsRef<AbsPath>>(cwd: P) -> Self {
+        PathEnv {
+            cwd: cwd.as_ref().to_owned(),
+            home: None,
+            path: Vec::new(),
+            config_dir: None,
+            cache_dir: None,
+            data_dir: None,
+        }
+    }
+
+    /// Snapshots the current process.
+    ///
+    /// The home directory comes from `HOME`, or `USERPROFILE` on Windows. The per-user
+    /// directories follow the platform's conventions: the XDG base directory variables with
+    /// their defaults under the home directory, `~/Library` on macOS, and `APPDATA` and
+    /// `LOCALAPPDATA` on Windows. Relative entries, which those conventions say to ignore, are
+    /// dropped.
+    pub fn capture() -> io::Result<Self> {
+        let var = |name: &str| {
+            std::env::var_os(name)
+                .map(PathBuf::from)
+                .filter(|p| p.is_absolute())
+                .and_then(|p| AbsPathBuf::new(p).ok())
+        };
+
+        let mut env = PathEnv::new(AbsPathBuf::new(std::env::current_dir()?)?);
+        env.home = var(if cfg!(windows) { "USERPROFILE" } else { "HOME" });
+        env.path = std::env::var_os("PATH")
+            .map(|p| {
+                std::env::split_paths(&p)
+                    .filter(|p| p.is_absolute())
+                    .filter_map(|p| AbsPathBuf::new(p).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let home = env.home.clone();
+        let under_home = |rel: &str| home.as_ref().and_then(|h| h.try_join(rel).ok());
+        if cfg!(windows) {
+            env.config_dir = var("APPDATA");
+            env.data_dir = var("APPDATA");
+            env.cache_dir = var("LOCALAPPDATA");
+        } else if cfg!(target_os = "macos") {
+            env.config_dir = under_home("Library/Application Support");
+            env.data_dir = under_home("Library/Application Support");
+            env.cache_dir = under_home("Library/Caches");
+        } else {
+            env.config_dir = var("XDG_CONFIG_HOME").or_else(|| under_home(".config"));
+            env.data_dir = var("XDG_DATA_HOME").or_else(|| under_home(".local/share"));
+            env.cache_dir = var("XDG_CACHE_HOME").or_else(|| under_home(".cache"));
+        }
+        Ok(env)
+    }
+
+    pub fn with_cwd<P: AsRef<AbsPath>>(mut self, cwd: P) -> Self {
+        self.cwd = cwd.as_ref().to_owned();
+        self
+    }
+
+    pub fn with_home<P: AsRef<AbsPath>>(mut self, home: P) -> Self {
+        self.home = Some(home.as_ref().to_owned());
+        self
+    }
+
+    /// Replaces the directories searched by [PathEnv::find_executable].
+    pub fn with_path<I, P>(mut self, dirs: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<AbsPath>,
+    {
+        self.path = dirs.into_iter().map(|d| d.as_ref().to_owned()).collect();
+        self
+    }
+
+    pub fn with_config_dir<P: AsRef<AbsPath>>(mut self, dir: P) -> Self {
+        self.config_dir = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    pub fn with_cache_dir<P: AsRef<AbsPath>>(mut self, dir: P) -> Self {
+        self.cache_dir = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    pub fn with_data_dir<P: AsRef<AbsPath>>(mut self, dir: P) -> Self {
+        self.data_dir = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    pub fn cwd(&self) -> &AbsPath {
+        &self.cwd
+    }
+
+    pub fn home(&self) -> Option<&AbsPath> {
+        self.home.as_deref()
+    }
+
+    pub fn path(&self) -> &[AbsPathBuf] {
+        &self.path
+    }
+
+    pub fn config_dir(&self) -> Option<&AbsPath> {
+        self.config_dir.as_deref()
+    }
+
+    pub fn cache_dir(&self) -> Option<&AbsPath> {
+        self.cache_dir.as_deref()
+    }
+
+    pub fn data_dir(&self) -> Option<&AbsPath> {
+        self.data_dir.as_deref()
+    }
+
+    /// Replaces a leading `~` component with the home directory.
+    ///
+    /// Other paths, including `~user/...`, are returned unchanged. Fails with
+    /// [io::ErrorKind::NotFound] if the path starts with `~` and there is no home directory.
+    pub fn expand_user<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        let path = path.as_ref();
+        let mut components = path.components();
+        match components.next() {
+            Some(Component::Normal(first)) if first == "~" => {
+                let home = self.home.as_ref().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, "no home directory to expand `~`")
+                })?;
+                Ok(home.as_path().join(components.as_path()))
+            }
+            _ => Ok(path.to_path_buf()),
+        }
+    }
+
+    /// Expands `~` like [PathEnv::expand_user], then resolves a relative result against the
+    /// current directory. The path is not normalized.
+    pub fn absolutize<P: AsRef<Path>>(&self, path: P) -> io::Result<AbsPathBuf> {
+        let path = self.expand_user(path)?;
+        if path.as_os_str().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot make an empty path absolute",
+            ));
+        }
+        if path.is_absolute() {
+            AbsPathBuf::new(path)
+        } else {
+            AbsPathBuf::new(self.cwd.as_path().join(path))
+        }
+    }
+
+    /// Finds `name` in the snapshot's `PATH`, trying `PATHEXT` extensions on Windows.
+    ///
+    /// A name with more than one component is resolved with [PathEnv::absolutize] instead.
+    /// Fails with [io::ErrorKind::NotFound] naming the program.
+    pub fn find_executable<S: AsRef<OsStr>>(&self, name: S) -> io::Result<AbsPathBuf> {
+        let name = name.as_ref();
+        if Path::new(name).components().count() > 1 || Path::new(name).is_absolute() {
+            return self.absolutize(name);
+        }
+        for dir in &self.path {
+            for candidate in crate::process::candidates(dir, name) {
+                if crate::process::is_executable(&candidate) {
+                    return AbsPathBuf::new(candidate);
+                }
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{}: program not found in PATH", Path::new(name).display()),
+        ))
+    }
+}
@@ -0,0 +1,160 @@
+//! NUL-terminated, platform-native path representations for calling C libraries.
+//!
+//! ```
+//! use dirge::{AbsPathBuf, ffi::AsRawPath};
+//!
+//! let db = AbsPathBuf::new("app.sqlite").unwrap();
+//! let raw = db.to_raw_path().unwrap();
+//! // Pass `raw.as_ptr()` to e.g. `sqlite3_open`; it stays valid while `raw` lives.
+//! # let _ = raw.as_ptr();
+//! ```
+
+use std::{fmt, io, ops::Deref, path::Path};
+
+use crate::{AbsPath, AbsPathBuf, NormPath, NormPathBuf, RelPath, RelPathBuf};
+
+/// The character type of a [RawPath]: `c_char` on Unix and `u16` on Windows.
+#[cfg(not(windows))]
+pub type RawChar = std::ffi::c_char;
+/// The character type of a [RawPath]: `c_char` on Unix and `u16` on Windows.
+#[cfg(windows)]
+pub type RawChar = u16;
+
+/// A path as the platform's C APIs take it: bytes on Unix and UTF-16 on Windows, followed by
+/// a NUL terminator.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RawPath {
+    #[cfg(not(windows))]
+    inner: std::ffi::CString,
+    #[cfg(windows)]
+    inner: Vec<u16>,
+}
+
+impl RawPath {
+    /// Converts `path`, failing with [io::ErrorKind::InvalidInput] if it contains a NUL.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<RawPath> {
+        let path = path.as_ref();
+        let nul = || {
+            crate::fs::with_path(
+                io::Error::new(io::ErrorKind::InvalidInput, "path contains a NUL character"),
+                path,
+            )
+        };
+
+        #[cfg(unix)]
+        let inner = {
+            use std::os::unix::ffi::OsStrExt;
+            std::ffi::CString::new(path.as_os_str().as_bytes()).map_err(|_| nul())?
+        };
+        #[cfg(windows)]
+        let inner = {
+            use std::os::windows::ffi::OsStrExt;
+            let mut wide: Vec<u16> = path.as_os_str().encode_wide().collect();
+            if wide.contains(&0) {
+                return Err(nul());
+            }
+            wide.push(0);
+            wide
+        };
+        #[cfg(not(any(unix, windows)))]
+        let inner = {
+            let text = path.to_str().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "path is not valid UTF-8")
+            })?;
+            std::ffi::CString::new(text).map_err(|_| nul())?
+        };
+
+        Ok(RawPath { inner })
+    }
+
+    /// A pointer to the NUL-terminated representation, valid for as long as `self` is.
+    pub fn as_ptr(&self) -> *const RawChar {
+        self.inner.as_ptr()
+    }
+
+    #[cfg(not(windows))]
+    pub fn as_c_str(&self) -> &std::ffi::CStr {
+        &self.inner
+    }
+
+    /// The UTF-16 code units, including the terminating NUL.
+    #[cfg(windows)]
+    pub fn as_wide_with_nul(&self) -> &[u16] {
+        &self.inner
+    }
+}
+
+impl fmt::Debug for RawPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+/// Converts dirge's path types into a [RawPath].
+pub trait AsRawPath {
+    fn to_raw_path(&self) -> io::Result<RawPath>;
+}
+
+macro_rules! impl_as_raw_path {
+    ($($ty:ty),*) => {
+        $(
+            impl AsRawPath for $ty {
+                fn to_raw_path(&self) -> io::Result<RawPath> {
+                    RawPath::new(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_as_raw_path!(
+    AbsPath,
+    AbsPathBuf,
+    RelPath,
+    RelPathBuf,
+    NormPath,
+    NormPathBuf
+);
+
+/// An owned path stored together with its [RawPath], so repeated FFI calls convert once.
+///
+/// dirge's owned types have the same layout as [std::path::PathBuf] and cannot hold the
+/// converted form themselves; wrap them in this instead where a path is handed to C often.
+///
+/// ```
+/// use dirge::{AbsPathBuf, ffi::WithRawPath};
+///
+/// let db = WithRawPath::new(AbsPathBuf::new("app.sqlite").unwrap()).unwrap();
+/// assert_eq!(db.file_name().unwrap(), "app.sqlite");
+/// # let _ = db.raw().as_ptr();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WithRawPath<P> {
+    path: P,
+    raw: RawPath,
+}
+
+impl<P: AsRawPath> WithRawPath<P> {
+    pub fn new(path: P) -> io::Result<Self> {
+        let raw = path.to_raw_path()?;
+        Ok(WithRawPath { path, raw })
+    }
+}
+
+impl<P> WithRawPath<P> {
+    pub fn raw(&self) -> &RawPath {
+        &self.raw
+    }
+
+    pub fn into_inner(self) -> P {
+        self.path
+    }
+}
+
+impl<P> Deref for WithRawPath<P> {
+    type Target = P;
+
+    fn deref(&self) -> &P {
+        &self.path
+    }
+}
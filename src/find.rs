@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use crate::{AbsPath, AbsPathBuf};
+
+impl AbsPath {
+    /// Looks for `name` in this directory and then in each of its ancestors.
+    ///
+    /// Returns the path of the first match, e.g. the nearest `Cargo.toml` or `.git`. A `name`
+    /// that [AbsPath::try_join] rejects is never found.
+    ///
+    /// ```no_run
+    /// use dirge::AbsPathBuf;
+    ///
+    /// let cwd = AbsPathBuf::new(".").unwrap();
+    /// let manifest = cwd.find_up("Cargo.toml");
+    /// ```
+    pub fn find_up<P: AsRef<Path>>(&self, name: P) -> Option<AbsPathBuf> {
+        self.find_up_any(&[name])
+    }
+
+    /// Like [AbsPath::find_up], but looks for several names at once.
+    ///
+    /// The nearest directory containing any of `names` wins; within one directory, earlier names
+    /// take precedence over later ones.
+    pub fn find_up_any<P: AsRef<Path>>(&self, names: &[P]) -> Option<AbsPathBuf> {
+        self.find_up_within(names, usize::MAX)
+    }
+
+    /// Like [AbsPath::find_up_any], but examines at most `limit` directories, starting with
+    /// this one.
+    pub fn find_up_within<P: AsRef<Path>>(&self, names: &[P], limit: usize) -> Option<AbsPathBuf> {
+        self.ancestors().take(limit).find_map(|dir| {
+            names.iter().find_map(|name| {
+                let candidate = dir.try_join(name).ok()?;
+                candidate.try_exists().unwrap_or(false).then_some(candidate)
+            })
+        })
+    }
+}
@@ -0,0 +1,1237 @@
+//! File system helpers that take and return dirge's path types.
+
+use std::{
+    cell::Cell,
+    cmp::Ordering,
+    collections::BTreeMap,
+    ffi::{OsStr, OsString},
+    fs::{File, OpenOptions},
+    hash::{BuildHasher, RandomState},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{
+        Mutex, MutexGuard,
+        atomic::{self, AtomicU64},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    AbsPath, AbsPathBuf, RelPath, RelPathBuf,
+    walk::{Budget, Limits},
+};
+
+/// Rewraps `err` so its message names the path it concerns, keeping the error kind.
+pub(crate) fn with_path(err: io::Error, path: &Path) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {err}", path.display()))
+}
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(1);
+const MAX_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Blocks until `path` exists, polling with exponential backoff.
+///
+/// Fails with [io::ErrorKind::TimedOut] if the path has not appeared within `timeout`. Service
+/// supervisors can use this to wait for a socket or pid file.
+///
+/// ```no_run
+/// use dirge::AbsPathBuf;
+/// use std::time::Duration;
+///
+/// let socket = AbsPathBuf::new("/run/app/app.sock").unwrap();
+/// dirge::fs::wait_for(&socket, Duration::from_secs(5)).unwrap();
+/// ```
+pub fn wait_for<P: AsRef<AbsPath>>(path: P, timeout: Duration) -> io::Result<()> {
+    poll(path.as_ref(), timeout, true)
+}
+
+/// Blocks until `path` no longer exists, polling with exponential backoff.
+///
+/// Fails with [io::ErrorKind::TimedOut] if the path is still present after `timeout`.
+pub fn wait_for_removal<P: AsRef<AbsPath>>(path: P, timeout: Duration) -> io::Result<()> {
+    poll(path.as_ref(), timeout, false)
+}
+
+fn poll(path: &AbsPath, timeout: Duration, exists: bool) -> io::Result<()> {
+    let deadline = Instant::now() + timeout;
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        if path.try_exists()? == exists {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            let what = if exists { "appear" } else { "be removed" };
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("timed out waiting for {} to {what}", path.display()),
+            ));
+        }
+
+        thread::sleep(backoff.min(deadline - now));
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Creates `dir` and any missing parents with restrictive permissions, refusing unsafe locations.
+///
+/// Daemons creating runtime or state directories can use this instead of [std::fs::create_dir_all].
+/// It fails with [io::ErrorKind::PermissionDenied] if:
+///
+/// - any existing component of `dir` is a symlink,
+/// - any existing ancestor is group- or world-writable without the sticky bit (so `/tmp` is
+///   accepted, since other users cannot replace entries in it),
+/// - `dir` already exists but is not owned by the current user or is group- or world-writable.
+///
+/// Missing directories are created with `mode`, which makes creation and permission setting a
+/// single step. On platforms other than Unix `mode` and the ownership checks are ignored, but
+/// symlinked components are still rejected.
+pub fn ensure_dir_secure<P: AsRef<AbsPath>>(dir: P, mode: u32) -> io::Result<()> {
+    let path: &Path = dir.as_ref().as_ref();
+    let mut created_final = false;
+
+    for ancestor in path.ancestors().collect::<Vec<_>>().into_iter().rev() {
+        let is_final = ancestor == path;
+        match ancestor.symlink_metadata() {
+            Ok(metadata) => {
+                if metadata.file_type().is_symlink() {
+                    return Err(insecure(ancestor, "is a symlink"));
+                }
+                if !metadata.is_dir() {
+                    return Err(with_path(
+                        io::Error::from(io::ErrorKind::NotADirectory),
+                        ancestor,
+                    ));
+                }
+                #[cfg(unix)]
+                if !is_final {
+                    use std::os::unix::fs::PermissionsExt;
+
+                    let mode = metadata.permissions().mode();
+                    if mode & 0o022 != 0 && mode & 0o1000 == 0 {
+                        return Err(insecure(ancestor, "is group- or world-writable"));
+                    }
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let mut builder = std::fs::DirBuilder::new();
+
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::DirBuilderExt;
+
+                    builder.mode(mode);
+                }
+                #[cfg(not(unix))]
+                let _ = mode;
+
+                builder
+                    .create(ancestor)
+                    .map_err(|e| with_path(e, ancestor))?;
+                created_final = is_final;
+            }
+            Err(e) => return Err(with_path(e, ancestor)),
+        }
+    }
+
+    #[cfg(unix)]
+    if !created_final {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = path.symlink_metadata().map_err(|e| with_path(e, path))?;
+        if metadata.uid() != current_uid()? {
+            return Err(insecure(path, "is not owned by the current user"));
+        }
+        if metadata.mode() & 0o022 != 0 {
+            return Err(insecure(path, "is group- or world-writable"));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = created_final;
+
+    Ok(())
+}
+
+fn insecure(path: &Path, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("{}: {reason}", path.display()),
+    )
+}
+
+/// A content hash algorithm for [hash_tree].
+///
+/// Implemented for closures taking `&mut dyn Read`, so any hasher (e.g. from the `digest`
+/// family of crates) can be plugged in without dirge depending on it.
+pub trait HashAlgorithm {
+    type Digest;
+
+    fn hash(&self, reader: &mut dyn Read) -> io::Result<Self::Digest>;
+}
+
+impl<D, F> HashAlgorithm for F
+where
+    F: Fn(&mut dyn Read) -> io::Result<D>,
+{
+    type Digest = D;
+
+    fn hash(&self, reader: &mut dyn Read) -> io::Result<D> {
+        self(reader)
+    }
+}
+
+/// Hashes the contents of every regular file below `root`, keyed by its path relative to `root`.
+///
+/// The tree is traversed with [AbsPath::walk], so the result is the same on every run.
+/// Symlinks and special files are skipped. Keys contain no `.` or `..` components.
+///
+/// ```no_run
+/// use dirge::AbsPathBuf;
+/// use std::io::{self, Read};
+///
+/// let root = AbsPathBuf::new("/srv/site").unwrap();
+/// let sizes = dirge::fs::hash_tree(&root, |r: &mut dyn Read| io::copy(r, &mut io::sink()))
+///     .unwrap();
+/// ```
+pub fn hash_tree<P, A>(root: P, algo: A) -> io::Result<BTreeMap<RelPathBuf, A::Digest>>
+where
+    P: AsRef<AbsPath>,
+    A: HashAlgorithm,
+{
+    let mut digests = BTreeMap::new();
+    for entry in root.as_ref().walk() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let digest = File::open(entry.path())
+            .and_then(|mut file| algo.hash(&mut file))
+            .map_err(|err| with_path(err, entry.path()))?;
+        digests.insert(entry.relative_path().to_owned(), digest);
+    }
+    Ok(digests)
+}
+
+/// The order of entries returned by [read_dir_sorted].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SortOrder {
+    /// Byte-wise order of the file names, independent of locale and platform collation.
+    #[default]
+    Bytes,
+    /// Like [SortOrder::Bytes], except that runs of ASCII digits compare by numeric value, so
+    /// `file2` sorts before `file10`. Equal numbers with fewer leading zeros come first.
+    Natural,
+    /// For showing names to people: like [SortOrder::Natural], but ignoring case, so
+    /// `b.txt` sorts between `A.txt` and `C.txt`. Names equal apart from case fall back to
+    /// [SortOrder::Bytes], so the order is still deterministic.
+    ///
+    /// Case is folded with Unicode's locale-independent lowercase mapping, and names that are
+    /// not valid UTF-8 are compared lossily. For a locale's collation rules, pass a collator
+    /// to [read_dir_sorted_by] instead.
+    Human,
+}
+
+impl SortOrder {
+    /// Compares two file names in this order.
+    ///
+    /// ```
+    /// use dirge::fs::SortOrder;
+    /// use std::ffi::OsStr;
+    ///
+    /// let mut names = ["b10", "B2", "a"].map(OsStr::new);
+    /// names.sort_by(|a, b| SortOrder::Bytes.compare(a, b));
+    /// assert_eq!(names, ["B2", "a", "b10"]);
+    /// names.sort_by(|a, b| SortOrder::Human.compare(a, b));
+    /// assert_eq!(names, ["a", "B2", "b10"]);
+    /// ```
+    pub fn compare(self, a: &OsStr, b: &OsStr) -> Ordering {
+        let (a, b) = (a.as_encoded_bytes(), b.as_encoded_bytes());
+        match self {
+            SortOrder::Bytes => a.cmp(b),
+            SortOrder::Natural => natural_cmp(a, b),
+            SortOrder::Human => {
+                let fold = |name: &[u8]| String::from_utf8_lossy(name).to_lowercase();
+                natural_cmp(fold(a).as_bytes(), fold(b).as_bytes()).then_with(|| a.cmp(b))
+            }
+        }
+    }
+}
+
+/// Lists the entries of `dir` in a deterministic order.
+///
+/// `.` and `..` are never included. Errors name the directory.
+///
+/// ```no_run
+/// use dirge::{AbsPathBuf, fs::SortOrder};
+///
+/// let dir = AbsPathBuf::new("/var/log").unwrap();
+/// for path in dirge::fs::read_dir_sorted(&dir, SortOrder::Natural).unwrap() {
+///     println!("{}", path.display());
+/// }
+/// ```
+pub fn read_dir_sorted<P: AsRef<AbsPath>>(dir: P, order: SortOrder) -> io::Result<Vec<AbsPathBuf>> {
+    read_dir_sorted_by(dir, |a, b| order.compare(a, b))
+}
+
+/// Like [read_dir_sorted], ordering file names with `compare`.
+///
+/// This is the hook for locale-aware collation, e.g. with an ICU collator, which dirge does not
+/// depend on itself. Under the `utf8-strict` feature, a name that is not valid UTF-8 fails the
+/// whole listing with [io::ErrorKind::InvalidData], as [AbsPath::walk] does.
+pub fn read_dir_sorted_by<P, F>(dir: P, mut compare: F) -> io::Result<Vec<AbsPathBuf>>
+where
+    P: AsRef<AbsPath>,
+    F: FnMut(&OsStr, &OsStr) -> Ordering,
+{
+    let dir = dir.as_ref();
+    let mut names = std::fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| {
+                    let name = entry?.file_name();
+                    #[cfg(feature = "utf8-strict")]
+                    crate::utf8::check_os(Path::new(&name))?;
+                    Ok(name)
+                })
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .map_err(|err| with_path(err, dir))?;
+
+    names.sort_by(|a, b| compare(a, b));
+    Ok(names
+        .into_iter()
+        .map(|name| dir.join_entry(&name))
+        .collect())
+}
+
+fn natural_cmp(mut a: &[u8], mut b: &[u8]) -> Ordering {
+    loop {
+        match (a.first(), b.first()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (num_a, rest_a) =
+                    a.split_at(a.iter().take_while(|c| c.is_ascii_digit()).count());
+                let (num_b, rest_b) =
+                    b.split_at(b.iter().take_while(|c| c.is_ascii_digit()).count());
+                let trim = |n: &[u8]| -> usize { n.iter().take_while(|&&c| c == b'0').count() };
+                let (value_a, value_b) = (&num_a[trim(num_a)..], &num_b[trim(num_b)..]);
+                let ordering = value_a
+                    .len()
+                    .cmp(&value_b.len())
+                    .then_with(|| value_a.cmp(value_b))
+                    .then_with(|| num_a.len().cmp(&num_b.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                (a, b) = (rest_a, rest_b);
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(y);
+                }
+                (a, b) = (&a[1..], &b[1..]);
+            }
+        }
+    }
+}
+
+/// Returns the effective user id of the current process.
+///
+/// Without `libc`, this reads the owner of `/proc/self` where available and otherwise the owner
+/// of a freshly created file in the temporary directory.
+#[cfg(unix)]
+pub(crate) fn current_uid() -> io::Result<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    if let Ok(metadata) = std::fs::metadata("/proc/self") {
+        return Ok(metadata.uid());
+    }
+
+    let probe = std::env::temp_dir().join(format!(
+        ".dirge-uid-probe-{}-{:?}",
+        std::process::id(),
+        thread::current().id()
+    ));
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)?;
+    let uid = file.metadata().map(|m| m.uid());
+    let _ = std::fs::remove_file(&probe);
+    uid
+}
+
+impl AbsPath {
+    /// Checks that files can be created in this directory, by creating and removing one.
+    ///
+    /// Installers can call this before starting a long copy. Permission bits alone are not
+    /// enough, since read-only mounts, ACLs and quotas also matter. Fails with
+    /// [io::ErrorKind::InvalidInput] if the path is not a directory; errors name the path.
+    pub fn check_writable(&self) -> io::Result<()> {
+        let metadata = self.metadata().map_err(|err| with_path(err, self))?;
+        if !metadata.is_dir() {
+            return Err(with_path(
+                io::Error::new(io::ErrorKind::InvalidInput, "not a directory"),
+                self,
+            ));
+        }
+
+        let (probe, _) = self
+            .unique_child_with(".dirge-write-probe-", "", |candidate| {
+                OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(candidate)
+            })
+            .map_err(|err| with_path(err, self))?;
+        std::fs::remove_file(&probe).map_err(|err| with_path(err, &probe))
+    }
+
+    /// Returns a path in this directory that is very unlikely to exist yet, without touching
+    /// the file system.
+    ///
+    /// The name is `prefix`, then the current time and 64 random bits in hex, then `.` and `ext`
+    /// unless `ext` is empty. Staging files, backups and temporary siblings for atomic writes
+    /// all need such names; prefer [AbsPath::unique_child_with], which also creates the file.
+    ///
+    /// Panics if `prefix` or `ext` contains a path separator, or if [AbsPath::try_join] rejects
+    /// the resulting name, such as a `prefix` that is a Windows drive like `C:`.
+    ///
+    /// ```
+    /// use dirge::AbsPathBuf;
+    ///
+    /// let dir = AbsPathBuf::new("/var/lib/app").unwrap();
+    /// let staging = dir.unique_child(".config.", "tmp");
+    /// assert_eq!(staging.parent(), Some(&*dir));
+    /// assert!(staging.to_str().unwrap().ends_with(".tmp"));
+    /// assert_ne!(staging, dir.unique_child(".config.", "tmp"));
+    /// ```
+    pub fn unique_child<S: AsRef<OsStr>>(&self, prefix: S, ext: &str) -> AbsPathBuf {
+        let prefix = prefix.as_ref();
+        let is_separator = |c: u8| std::path::is_separator(char::from(c));
+        assert!(
+            !prefix.as_encoded_bytes().iter().any(|&c| is_separator(c))
+                && !ext.bytes().any(is_separator),
+            "unique_child: prefix and extension must not contain path separators"
+        );
+
+        let millis = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let mut name = prefix.to_owned();
+        name.push(format!("{millis:x}-{:016x}", random_u64()));
+        if !ext.is_empty() {
+            name.push(".");
+            name.push(ext);
+        }
+        self.try_join(name)
+            .unwrap_or_else(|err| panic!("unique_child: {err}"))
+    }
+
+    /// Picks a name like [AbsPath::unique_child] and calls `create` with it, retrying with a
+    /// new name while `create` fails with [io::ErrorKind::AlreadyExists].
+    ///
+    /// `create` should make the file or directory in a way that fails if it exists, such as
+    /// [OpenOptions::create_new] or [std::fs::create_dir], so the returned path is known to be
+    /// fresh. Returns the path and what `create` returned. Gives up after 16 attempts; other
+    /// errors are returned at once.
+    ///
+    /// ```no_run
+    /// use dirge::AbsPathBuf;
+    /// use std::fs::OpenOptions;
+    ///
+    /// let dir = AbsPathBuf::new("/var/lib/app").unwrap();
+    /// let (path, file) = dir
+    ///     .unique_child_with("backup-", "json", |candidate| {
+    ///         OpenOptions::new().write(true).create_new(true).open(candidate)
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn unique_child_with<S, T, F>(
+        &self,
+        prefix: S,
+        ext: &str,
+        mut create: F,
+    ) -> io::Result<(AbsPathBuf, T)>
+    where
+        S: AsRef<OsStr>,
+        F: FnMut(&AbsPath) -> io::Result<T>,
+    {
+        let prefix = prefix.as_ref();
+        for _ in 0..UNIQUE_CHILD_ATTEMPTS {
+            let candidate = self.unique_child(prefix, ext);
+            match create(&candidate) {
+                Ok(created) => return Ok((candidate, created)),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Err(with_path(
+            io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "no unused name found for a new entry",
+            ),
+            self,
+        ))
+    }
+
+    /// Starts building the options to open this path with.
+    ///
+    /// ```no_run
+    /// use dirge::AbsPathBuf;
+    ///
+    /// let key = AbsPathBuf::new("/var/lib/app/secrets/key").unwrap();
+    /// let file = key
+    ///     .open_with()
+    ///     .write(true)
+    ///     .create_new(true)
+    ///     .create_parents(true)
+    ///     .mode(0o600)
+    ///     .open()
+    ///     .unwrap();
+    /// ```
+    pub fn open_with(&self) -> OpenOptionsBuilder<'_> {
+        OpenOptionsBuilder {
+            path: self,
+            options: OpenOptions::new(),
+            create_parents: false,
+            mode: None,
+        }
+    }
+
+    /// Canonicalizes the longest existing prefix of the path and appends the rest lexically.
+    ///
+    /// Like Python's `Path.resolve(strict=False)`: symlinks are resolved as far as the path
+    /// exists, and `.`/`..` in the missing remainder are applied without touching the file
+    /// system. Useful for files that are about to be created. The result is normalized.
+    pub fn canonicalize_lenient(&self) -> io::Result<AbsPathBuf> {
+        let components: Vec<_> = self.components().collect();
+        for existing in (1..=components.len()).rev() {
+            let prefix: PathBuf = components[..existing].iter().collect();
+            match prefix.canonicalize() {
+                Ok(mut resolved) => {
+                    #[cfg(feature = "utf8-strict")]
+                    crate::utf8::check_os(&resolved)?;
+                    resolved.extend(&components[existing..]);
+                    return Ok(AbsPathBuf::from_path_buf_unchecked(crate::normalize(
+                        resolved,
+                    )));
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(with_path(err, &prefix)),
+            }
+        }
+        Err(with_path(
+            io::Error::new(io::ErrorKind::NotFound, "no part of the path exists"),
+            self,
+        ))
+    }
+}
+
+const UNIQUE_CHILD_ATTEMPTS: usize = 16;
+
+/// 64 bits that differ between calls, threads and processes, without a random number crate.
+fn random_u64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    // Every `RandomState` is seeded differently, from keys the OS randomizes per process.
+    RandomState::new().hash_one((
+        COUNTER.fetch_add(1, atomic::Ordering::Relaxed),
+        std::process::id(),
+        thread::current().id(),
+        Instant::now(),
+    ))
+}
+
+/// Typed wrapper around [OpenOptions], created by [AbsPath::open_with].
+///
+/// Errors returned by [OpenOptionsBuilder::open] mention the path being opened.
+#[derive(Debug, Clone)]
+pub struct OpenOptionsBuilder<'a> {
+    path: &'a AbsPath,
+    options: OpenOptions,
+    create_parents: bool,
+    mode: Option<u32>,
+}
+
+impl OpenOptionsBuilder<'_> {
+    /// See [OpenOptions::read].
+    pub fn read(&mut self, read: bool) -> &mut Self {
+        self.options.read(read);
+        self
+    }
+
+    /// See [OpenOptions::write].
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.options.write(write);
+        self
+    }
+
+    /// See [OpenOptions::append].
+    pub fn append(&mut self, append: bool) -> &mut Self {
+        self.options.append(append);
+        self
+    }
+
+    /// See [OpenOptions::truncate].
+    pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+        self.options.truncate(truncate);
+        self
+    }
+
+    /// See [OpenOptions::create].
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.options.create(create);
+        self
+    }
+
+    /// See [OpenOptions::create_new].
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.options.create_new(create_new);
+        self
+    }
+
+    /// Creates missing parent directories before opening.
+    pub fn create_parents(&mut self, create_parents: bool) -> &mut Self {
+        self.create_parents = create_parents;
+        self
+    }
+
+    /// Sets the permission bits a newly created file gets, subject to the umask.
+    ///
+    /// Ignored on platforms other than Unix.
+    pub fn mode(&mut self, mode: u32) -> &mut Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Opens the file with the configured options.
+    pub fn open(&self) -> io::Result<File> {
+        let path: &Path = self.path;
+
+        if self.create_parents
+            && let Some(parent) = path.parent()
+        {
+            std::fs::create_dir_all(parent).map_err(|e| with_path(e, parent))?;
+        }
+
+        let mut options = self.options.clone();
+
+        #[cfg(unix)]
+        if let Some(mode) = self.mode {
+            use std::os::unix::fs::OpenOptionsExt;
+
+            options.mode(mode);
+        }
+
+        options.open(path).map_err(|e| with_path(e, path))
+    }
+}
+
+/// Held by every [CurrentDirGuard]; holds the error of a guard that could not restore the
+/// directory on drop, for the next [CurrentDirGuard::change_to] to report.
+static CURRENT_DIR_LOCK: Mutex<Option<io::Error>> = Mutex::new(None);
+
+thread_local! {
+    static HOLDS_CURRENT_DIR: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Changes the process's current directory until dropped, then changes it back.
+///
+/// Guards are serialized by a process-wide lock, so threads that each change directory through
+/// a guard never observe one another's. Code that calls [std::env::set_current_dir] directly
+/// bypasses the lock. Taking a second guard on a thread that already holds one would deadlock,
+/// so it panics instead.
+///
+/// Dropping the guard cannot report a failure to restore the previous directory, for example
+/// because it was removed, so the next [CurrentDirGuard::change_to] fails with that error
+/// instead. Call [CurrentDirGuard::restore] to handle it where it happens.
+///
+/// ```no_run
+/// use dirge::{AbsPathBuf, fs::CurrentDirGuard};
+///
+/// let fixture = AbsPathBuf::new("/tmp/fixture").unwrap();
+/// let _guard = CurrentDirGuard::change_to(&fixture).unwrap();
+/// // Legacy code resolving paths against the current directory runs here.
+/// ```
+pub struct CurrentDirGuard {
+    previous: AbsPathBuf,
+    restored: bool,
+    lock: MutexGuard<'static, Option<io::Error>>,
+}
+
+impl CurrentDirGuard {
+    /// Changes to `dir`. Fails without changing directory if the last guard dropped could not
+    /// restore its previous directory; the error names that directory, and is reported once.
+    pub fn change_to<P: AsRef<AbsPath>>(dir: P) -> io::Result<CurrentDirGuard> {
+        assert!(
+            !HOLDS_CURRENT_DIR.get(),
+            "CurrentDirGuard::change_to called while this thread already holds a guard"
+        );
+        // A thread that panicked while holding a guard still restored the directory on drop,
+        // or left the error below, so the poison is benign.
+        let mut lock = CURRENT_DIR_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(err) = lock.take() {
+            return Err(io::Error::new(
+                err.kind(),
+                format!("a dropped CurrentDirGuard failed to restore the current directory: {err}"),
+            ));
+        }
+        let previous = AbsPathBuf::new(std::env::current_dir()?)?;
+        let dir = dir.as_ref();
+        std::env::set_current_dir(dir).map_err(|e| with_path(e, dir))?;
+        HOLDS_CURRENT_DIR.set(true);
+        Ok(CurrentDirGuard {
+            previous,
+            restored: false,
+            lock,
+        })
+    }
+
+    /// The directory that will be restored on drop.
+    pub fn previous(&self) -> &AbsPath {
+        &self.previous
+    }
+
+    /// Changes back to the previous directory and releases the guard, returning the error that
+    /// dropping it would leave for the next [CurrentDirGuard::change_to].
+    pub fn restore(mut self) -> io::Result<()> {
+        self.restored = true;
+        std::env::set_current_dir(&self.previous).map_err(|e| with_path(e, &self.previous))
+    }
+}
+
+impl Drop for CurrentDirGuard {
+    fn drop(&mut self) {
+        HOLDS_CURRENT_DIR.set(false);
+        if self.restored {
+            return;
+        }
+        if let Err(e) = std::env::set_current_dir(&self.previous) {
+            *self.lock = Some(with_path(e, &self.previous));
+        }
+    }
+}
+
+/// What the filesystem hosting a directory was observed to support; see [probe].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct FsCapabilities {
+    /// Whether `A.txt` and `a.txt` name different files.
+    pub case_sensitive: bool,
+    /// Whether symbolic links can be created.
+    pub symlinks_supported: bool,
+    /// Whether hard links can be created.
+    pub hard_links_supported: bool,
+    /// The longest file name, in bytes, that could be created. Capped at [MAX_PROBED_NAME_LEN].
+    pub max_name_len: usize,
+}
+
+/// The longest file name [probe] tries to create.
+pub const MAX_PROBED_NAME_LEN: usize = 4096;
+
+/// Empirically tests the filesystem hosting `dir` by creating, inspecting and removing files in
+/// a scratch directory inside it.
+///
+/// Per-OS defaults are often wrong: macOS volumes can be case-sensitive, Linux can mount
+/// case-insensitive filesystems, and Windows only allows symlinks with developer mode. Sync
+/// tools can use the result to, for example, warn about `A.txt`/`a.txt` collisions. `dir` must
+/// be writable; errors name the path.
+///
+/// ```no_run
+/// use dirge::AbsPathBuf;
+///
+/// let caps = dirge::fs::probe(AbsPathBuf::new("/mnt/share").unwrap()).unwrap();
+/// if !caps.case_sensitive {
+///     eprintln!("warning: names differing only in case will collide");
+/// }
+/// ```
+pub fn probe<P: AsRef<AbsPath>>(dir: P) -> io::Result<FsCapabilities> {
+    let dir = dir.as_ref();
+    let (scratch, ()) = dir.unique_child_with(".dirge-probe-", "", |path| {
+        std::fs::create_dir(path).map_err(|err| with_path(err, path))
+    })?;
+    let result = probe_in(&scratch);
+    let _ = std::fs::remove_dir_all(&scratch);
+    result.map_err(|err| with_path(err, dir))
+}
+
+fn probe_in(scratch: &AbsPath) -> io::Result<FsCapabilities> {
+    let upper = scratch.try_join("PROBE")?;
+    std::fs::write(&upper, "")?;
+    let case_sensitive = std::fs::symlink_metadata(scratch.try_join("probe")?).is_err();
+
+    let link = scratch.try_join("link")?;
+    #[cfg(unix)]
+    let symlinks_supported = std::os::unix::fs::symlink(&upper, &link).is_ok();
+    #[cfg(windows)]
+    let symlinks_supported = std::os::windows::fs::symlink_file(&upper, &link).is_ok();
+    #[cfg(not(any(unix, windows)))]
+    let symlinks_supported = false;
+
+    let hard_links_supported = std::fs::hard_link(&upper, scratch.try_join("hard")?).is_ok();
+
+    // Binary search for the longest name that can be created; `lo` is always known to work.
+    let (mut lo, mut hi) = (1, MAX_PROBED_NAME_LEN);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        let name = scratch.try_join("n".repeat(mid))?;
+        if std::fs::write(&name, "").is_ok() {
+            let _ = std::fs::remove_file(&name);
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+
+    Ok(FsCapabilities {
+        case_sensitive,
+        symlinks_supported,
+        hard_links_supported,
+        max_name_len: lo,
+    })
+}
+
+/// How long [replace_file] and [replace_dir] keep retrying a rename another process is
+/// blocking.
+const SHARING_RETRY_LIMIT: Duration = Duration::from_secs(2);
+
+/// Moves the file `src` to `dst`, replacing any file already there.
+///
+/// On Unix this is `rename(2)`: atomic, so other processes see either the old `dst` or the new
+/// one and never a missing or partial file. On Windows it is `MoveFileExW` with
+/// `MOVEFILE_REPLACE_EXISTING`, which is atomic on NTFS for files on the same volume but, unlike
+/// `ReplaceFileW`, does not carry over the old file's ACLs or attributes.
+///
+/// Virus scanners and indexers briefly open new files on Windows, which makes the rename fail
+/// with a sharing violation; such failures are retried with backoff for up to two seconds. If
+/// `src` and `dst` are on different filesystems, `src` is copied to a temporary file next to
+/// `dst`, which is then renamed into place, and `src` is removed, so `dst` is still replaced
+/// atomically.
+///
+/// Neither the file nor its directory is synced; callers that need the replacement to survive a
+/// crash should do that themselves. Fails if `src` is not a file or `dst` is a directory;
+/// errors name the path concerned.
+///
+/// ```no_run
+/// use dirge::AbsPathBuf;
+///
+/// let staged = AbsPathBuf::new("/opt/app/.staging/app").unwrap();
+/// let installed = AbsPathBuf::new("/opt/app/bin/app").unwrap();
+/// dirge::fs::replace_file(&staged, &installed).unwrap();
+/// ```
+pub fn replace_file<P, Q>(src: P, dst: Q) -> io::Result<()>
+where
+    P: AsRef<AbsPath>,
+    Q: AsRef<AbsPath>,
+{
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    let metadata = src.symlink_metadata().map_err(|e| with_path(e, src))?;
+    if metadata.is_dir() {
+        return Err(with_path(io::Error::from(io::ErrorKind::IsADirectory), src));
+    }
+    match dst.symlink_metadata() {
+        Ok(m) if m.is_dir() => {
+            return Err(with_path(io::Error::from(io::ErrorKind::IsADirectory), dst));
+        }
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(with_path(e, dst)),
+    }
+
+    match rename_retrying(src, dst) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices && metadata.is_file() => {
+            let staged = sibling(dst, "replace");
+            let copied = std::fs::copy(src, &staged)
+                .map_err(|e| with_path(e, src))
+                .and_then(|_| rename_retrying(&staged, dst).map_err(|e| with_path(e, dst)));
+            if let Err(e) = copied {
+                let _ = std::fs::remove_file(&staged);
+                return Err(e);
+            }
+            std::fs::remove_file(src).map_err(|e| with_path(e, src))
+        }
+        result => result.map_err(|e| with_path(e, dst)),
+    }
+}
+
+/// Moves the directory `src` to `dst`, replacing any directory already there with it.
+///
+/// Neither Unix nor Windows can atomically rename a directory over a non-empty one, so an
+/// existing `dst` is first renamed aside, `src` is renamed into its place, and the old tree is
+/// then removed. Between the two renames `dst` briefly does not exist. If the second rename
+/// fails the old directory is moved back. Should that also fail, the error says so and names
+/// the path the old directory was left at, so `dst` is never lost silently. A failure to remove
+/// the old tree is reported, but `dst` has been replaced by then.
+///
+/// Renames are retried on Windows sharing violations as in [replace_file]. There is no
+/// fallback for `src` and `dst` on different filesystems, which fails with
+/// [io::ErrorKind::CrossesDevices]. Fails if either path exists but is not a directory; errors
+/// name the path concerned.
+pub fn replace_dir<P, Q>(src: P, dst: Q) -> io::Result<()>
+where
+    P: AsRef<AbsPath>,
+    Q: AsRef<AbsPath>,
+{
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    let metadata = src.symlink_metadata().map_err(|e| with_path(e, src))?;
+    if !metadata.is_dir() {
+        return Err(with_path(
+            io::Error::from(io::ErrorKind::NotADirectory),
+            src,
+        ));
+    }
+    match dst.symlink_metadata() {
+        Ok(m) if !m.is_dir() => {
+            return Err(with_path(
+                io::Error::from(io::ErrorKind::NotADirectory),
+                dst,
+            ));
+        }
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return rename_retrying(src, dst).map_err(|e| with_path(e, dst));
+        }
+        Err(e) => return Err(with_path(e, dst)),
+    }
+
+    let old = sibling(dst, "old");
+    rename_retrying(dst, &old).map_err(|e| with_path(e, dst))?;
+    if let Err(e) = rename_retrying(src, dst) {
+        if let Err(rollback) = rename_retrying(&old, dst) {
+            return Err(io::Error::new(
+                e.kind(),
+                format!(
+                    "{}: {e}; moving the old directory back failed ({rollback}), it was left at {}",
+                    dst.display(),
+                    old.display(),
+                ),
+            ));
+        }
+        return Err(with_path(e, dst));
+    }
+    std::fs::remove_dir_all(&old).map_err(|e| with_path(e, &old))
+}
+
+/// A fresh name in the directory containing `path`, for staging a replacement of it.
+fn sibling(path: &AbsPath, what: &str) -> AbsPathBuf {
+    let mut prefix = OsString::from(".");
+    prefix.push(path.file_name().unwrap_or_default());
+    prefix.push(format!(".dirge-{what}-"));
+    path.parent().unwrap_or(path).unique_child(prefix, "")
+}
+
+fn rename_retrying(src: &Path, dst: &Path) -> io::Result<()> {
+    let deadline = Instant::now() + SHARING_RETRY_LIMIT;
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        match std::fs::rename(src, dst) {
+            Err(e) if is_sharing_violation(&e) && Instant::now() < deadline => {
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether `err` is the transient failure Windows reports while another process, typically a
+/// virus scanner, has the file open.
+fn is_sharing_violation(err: &io::Error) -> bool {
+    // ERROR_ACCESS_DENIED, ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION.
+    cfg!(windows) && matches!(err.raw_os_error(), Some(5 | 32 | 33))
+}
+
+/// A virtual directory layout: which source path should appear at each relative location.
+///
+/// Locations are normalized lexically, so `a/./b` and `a/b` are the same entry. See
+/// [materialize].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathMapping {
+    entries: BTreeMap<RelPathBuf, AbsPathBuf>,
+}
+
+impl PathMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Maps `at` to `source`, returning the source it replaces.
+    ///
+    /// Fails with [io::ErrorKind::InvalidInput] if `at` is empty, `.`, or climbs above the
+    /// root with `..`.
+    pub fn insert<R, S>(&mut self, at: R, source: S) -> io::Result<Option<AbsPathBuf>>
+    where
+        R: AsRef<RelPath>,
+        S: AsRef<AbsPath>,
+    {
+        let at = at.as_ref();
+        if crate::rel::escapes_root(at) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}: location leaves the layout", at.display()),
+            ));
+        }
+        let at = crate::normalize(at);
+        if at.as_os_str().is_empty() || at.as_os_str() == "." {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "location names the root of the layout",
+            ));
+        }
+        // Normalizing a relative path that does not escape keeps it relative.
+        let at = RelPathBuf::from_path_buf_unchecked(at);
+        Ok(self.entries.insert(at, source.as_ref().to_owned()))
+    }
+
+    pub fn get<R: AsRef<RelPath>>(&self, at: R) -> Option<&AbsPath> {
+        let at = RelPathBuf::from_path_buf_unchecked(crate::normalize(at.as_ref()));
+        self.entries.get(&at).map(|source| &**source)
+    }
+
+    /// Iterates over the entries in component order.
+    pub fn iter(&self) -> impl Iterator<Item = (&RelPath, &AbsPath)> {
+        self.entries.iter().map(|(at, source)| (&**at, &**source))
+    }
+}
+
+/// How [materialize] puts each source in place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MaterializeStrategy {
+    /// Hard-links files, falling back to a symlink where that fails, e.g. across file systems.
+    /// Directories are symlinked.
+    #[default]
+    Link,
+    /// Copies files and, recursively, directories. Symlinks inside copied directories are
+    /// recreated, not followed.
+    Copy,
+    /// Like [MaterializeStrategy::Copy], sharing data blocks with the source where the file
+    /// system supports it. Standard library copies already clone where they can (`clonefile`
+    /// on macOS, `copy_file_range` on Linux), so this currently behaves exactly like `Copy`;
+    /// it states the intent and leaves room for a dedicated clone call.
+    Reflink,
+}
+
+/// What [materialize] did with each entry of the mapping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MaterializeReport {
+    /// Locations that were created.
+    pub created: Vec<RelPathBuf>,
+    /// Locations that already held the source, through a link or because it is the same file.
+    pub unchanged: Vec<RelPathBuf>,
+    /// Locations left untouched because something else is already there, a parent directory in
+    /// the destination is a symlink, or another entry of the mapping contains them.
+    pub conflicts: Vec<RelPathBuf>,
+    /// Locations whose source does not exist, including sources that are dangling symlinks.
+    pub dangling: Vec<RelPathBuf>,
+}
+
+impl MaterializeReport {
+    /// Whether every entry is now in place.
+    pub fn is_complete(&self) -> bool {
+        self.conflicts.is_empty() && self.dangling.is_empty()
+    }
+}
+
+/// Realizes `mapping` below `dst`, creating parent directories as needed.
+///
+/// Entries are processed in component order. Existing files are never overwritten or removed:
+/// occupied locations and missing sources are reported instead, so the call can be repeated
+/// after fixing them, and a second run over an unchanged tree reports everything as unchanged
+/// (for linked entries). An entry below another one, such as `a/b` when `a` is mapped too, is a
+/// conflict, since creating it would write into the source of `a`. Other I/O errors stop the
+/// run; they name the path concerned.
+///
+/// ```no_run
+/// use dirge::{AbsPathBuf, RelPathBuf, fs::{self, MaterializeStrategy, PathMapping}};
+///
+/// let store = AbsPathBuf::new("/var/cache/pkgs").unwrap();
+/// let mut layout = PathMapping::new();
+/// layout
+///     .insert(
+///         RelPathBuf::new("node_modules/left-pad").unwrap(),
+///         store.try_join("left-pad-1.3.0").unwrap(),
+///     )
+///     .unwrap();
+/// let dst = AbsPathBuf::new("/src/app").unwrap();
+/// let report = fs::materialize(&layout, &dst, MaterializeStrategy::Link).unwrap();
+/// for conflict in &report.conflicts {
+///     eprintln!("{} is in the way", conflict.display());
+/// }
+/// ```
+pub fn materialize<P: AsRef<AbsPath>>(
+    mapping: &PathMapping,
+    dst: P,
+    strategy: MaterializeStrategy,
+) -> io::Result<MaterializeReport> {
+    materialize_with_limits(mapping, dst, strategy, Limits::default())
+}
+
+/// Like [materialize], bounding the work with `limits`, for mappings whose sources are not
+/// trusted.
+///
+/// Every location created counts as an entry, as does everything copied below it; the bytes
+/// are those of the regular files copied, and the depth is counted within copied directories.
+/// The timeout runs from the call. Exceeding a limit stops the run with the
+/// [LimitExceeded](crate::walk::LimitExceeded) error a walk would report, leaving what was
+/// already created in place.
+/// [Limits::same_file_system] keeps copies of directories from descending into other file
+/// systems mounted inside them.
+pub fn materialize_with_limits<P: AsRef<AbsPath>>(
+    mapping: &PathMapping,
+    dst: P,
+    strategy: MaterializeStrategy,
+    limits: Limits,
+) -> io::Result<MaterializeReport> {
+    let dst = dst.as_ref();
+    let mut report = MaterializeReport::default();
+    let mut budget = Budget::new(limits);
+
+    for (at, source) in mapping.iter() {
+        let nested = at
+            .ancestors()
+            .skip(1)
+            .filter(|a| !a.as_os_str().is_empty())
+            .any(|a| {
+                mapping.entries.contains_key(a)
+                    || dst.join(a).symlink_metadata().is_ok_and(|m| m.is_symlink())
+            });
+        if nested {
+            report.conflicts.push(at.to_owned());
+            continue;
+        }
+
+        let metadata = match source.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                report.dangling.push(at.to_owned());
+                continue;
+            }
+            Err(e) => return Err(with_path(e, source)),
+        };
+
+        let target = dst.join(at);
+        match target.symlink_metadata() {
+            Ok(_) => {
+                if same_file(source, &target)? {
+                    report.unchanged.push(at.to_owned());
+                } else {
+                    report.conflicts.push(at.to_owned());
+                }
+                continue;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(with_path(e, &target)),
+        }
+
+        let copies_file = strategy != MaterializeStrategy::Link && metadata.is_file();
+        let size = if copies_file { metadata.len() } else { 0 };
+        budget.charge(source, 0, size)?;
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| with_path(e, parent))?;
+        }
+        match strategy {
+            MaterializeStrategy::Link => {
+                if !metadata.is_file() || std::fs::hard_link(source, &target).is_err() {
+                    symlink(source, &target, metadata.is_dir())?;
+                }
+            }
+            MaterializeStrategy::Copy | MaterializeStrategy::Reflink => {
+                if metadata.is_dir() {
+                    copy_tree(source, &target, &mut budget)?;
+                } else {
+                    std::fs::copy(source, &target).map_err(|e| with_path(e, &target))?;
+                }
+            }
+        }
+        report.created.push(at.to_owned());
+    }
+    Ok(report)
+}
+
+/// Whether `a` and `b` resolve to the same file. A dangling `b` is not the same file.
+fn same_file(a: &Path, b: &Path) -> io::Result<bool> {
+    let (a_meta, b_meta) = match (a.metadata(), b.metadata()) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(e), _) => return Err(with_path(e, a)),
+        (_, Err(_)) => return Ok(false),
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        Ok(a_meta.dev() == b_meta.dev() && a_meta.ino() == b_meta.ino())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (a_meta, b_meta);
+        Ok(a.canonicalize().ok() == b.canonicalize().ok())
+    }
+}
+
+pub(crate) fn symlink(original: &Path, link: &Path, is_dir: bool) -> io::Result<()> {
+    #[cfg(unix)]
+    let result = {
+        let _ = is_dir;
+        std::os::unix::fs::symlink(original, link)
+    };
+    #[cfg(windows)]
+    let result = if is_dir {
+        std::os::windows::fs::symlink_dir(original, link)
+    } else {
+        std::os::windows::fs::symlink_file(original, link)
+    };
+    #[cfg(not(any(unix, windows)))]
+    let result = {
+        let _ = (original, is_dir);
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    };
+    result.map_err(|e| with_path(e, link))
+}
+
+fn copy_tree(source: &AbsPath, target: &AbsPath, budget: &mut Budget) -> io::Result<()> {
+    std::fs::create_dir(target).map_err(|e| with_path(e, target))?;
+    let walk = source.walk();
+    let walk = if budget.same_file_system() {
+        walk.same_file_system()
+    } else {
+        walk
+    };
+    for entry in walk {
+        let entry = entry?;
+        let size = if budget.counts_bytes() && entry.is_file() {
+            entry.metadata()?.len()
+        } else {
+            0
+        };
+        budget.charge(entry.path(), entry.depth(), size)?;
+        let to = target.join(entry.relative_path());
+        if entry.is_dir() {
+            std::fs::create_dir(&to).map_err(|e| with_path(e, &to))?;
+        } else if entry.is_symlink() {
+            let original =
+                std::fs::read_link(entry.path()).map_err(|e| with_path(e, entry.path()))?;
+            let is_dir = entry.path().is_dir();
+            symlink(&original, &to, is_dir)?;
+        } else {
+            std::fs::copy(entry.path(), &to).map_err(|e| with_path(e, &to))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,305 @@
+//! Telling file arguments apart from glob patterns.
+//!
+//! CLI tools that accept "a file or a glob" all need the same classifier. [Input::parse] is
+//! it: an argument is a glob if it contains an unescaped `*` or `?`, or a `[` with a matching
+//! `]`. Wildcards are escaped by wrapping them in brackets (`[*]`, `[?]`, `[[]`) and, outside
+//! Windows where `\` is a separator, with a backslash (`\*`). An argument whose only special
+//! characters are escaped is a literal path with the escapes removed.
+//!
+//! ```
+//! use dirge::{RelPathBuf, glob::Input};
+//!
+//! let Input::Glob(pattern) = Input::parse("src/**/*.rs").unwrap() else { panic!() };
+//! assert!(pattern.matches(RelPathBuf::new("src/a/b/lib.rs").unwrap()));
+//! assert!(!pattern.matches(RelPathBuf::new("tests/it.rs").unwrap()));
+//!
+//! assert_eq!(
+//!     Input::parse("notes[*].txt").unwrap(),
+//!     Input::Literal(RelPathBuf::new("notes*.txt").unwrap())
+//! );
+//! ```
+
+use std::{
+    ffi::OsStr,
+    fmt, io,
+    path::{Component, PathBuf},
+};
+
+use crate::{RelPath, RelPathBuf};
+
+/// A command-line argument naming either one path or a set of paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Literal(RelPathBuf),
+    Glob(GlobPattern),
+}
+
+impl Input {
+    /// Classifies `arg` as described in the [module docs](self).
+    ///
+    /// Fails with [io::ErrorKind::InvalidInput] if `arg` is absolute, or is a glob that is
+    /// not valid UTF-8 or not a valid [GlobPattern]. Non-UTF-8 arguments without wildcards are
+    /// literals.
+    pub fn parse<S: AsRef<OsStr>>(arg: S) -> io::Result<Input> {
+        let arg = arg.as_ref();
+        let Some(text) = arg.to_str() else {
+            return RelPathBuf::new(arg).map(Input::Literal);
+        };
+        let tokens = lex(text, false)?;
+        if tokens.iter().any(Token::is_wildcard) {
+            GlobPattern::new(text).map(Input::Glob)
+        } else {
+            let literal: String = tokens
+                .iter()
+                .map(|t| match t {
+                    Token::Sep => '/',
+                    t => literal_char(t),
+                })
+                .collect();
+            RelPathBuf::new(literal).map(Input::Literal)
+        }
+    }
+}
+
+/// A relative glob pattern matched component by component.
+///
+/// `*` matches any run of characters within one component, `?` one character, `[abc]`,
+/// `[a-z]` and `[!a-z]` one character from a class, and a whole component `**` any number of
+/// components. `.` components in matched paths are ignored.
+#[derive(Clone, PartialEq, Eq)]
+pub struct GlobPattern {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    AnyDirs,
+    Tokens(Vec<Token>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Lit(char),
+    Sep,
+    Any,
+    One,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Token {
+    fn is_wildcard(&self) -> bool {
+        match self {
+            Token::Any | Token::One => true,
+            // A one-character class is just an escape.
+            Token::Class { negated, ranges } => {
+                *negated || ranges.len() != 1 || ranges[0].0 != ranges[0].1
+            }
+            _ => false,
+        }
+    }
+}
+
+impl GlobPattern {
+    /// Parses `pattern`. Fails with [io::ErrorKind::InvalidInput] if it is absolute, has an
+    /// unclosed `[`, or uses `**` as part of a longer component.
+    pub fn new(pattern: &str) -> io::Result<GlobPattern> {
+        if pattern.starts_with('/') || (cfg!(windows) && RelPathBuf::new(pattern).is_err()) {
+            return Err(invalid("glob pattern must be relative"));
+        }
+
+        let mut segments = Vec::new();
+        for part in lex(pattern, true)?.split(|t| *t == Token::Sep) {
+            match part {
+                [] => {}
+                [Token::Lit('.')] => {}
+                [Token::Any, Token::Any] => segments.push(Segment::AnyDirs),
+                _ if part.windows(2).any(|w| w == [Token::Any, Token::Any]) => {
+                    return Err(invalid("`**` must be a whole path component"));
+                }
+                _ => segments.push(Segment::Tokens(part.to_vec())),
+            }
+        }
+        Ok(GlobPattern {
+            source: pattern.to_owned(),
+            segments,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns whether `path` matches the whole pattern. Components that are not valid UTF-8
+    /// never match wildcards.
+    pub fn matches<P: AsRef<RelPath>>(&self, path: P) -> bool {
+        let names: Vec<Option<Vec<char>>> = path
+            .as_ref()
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .map(|c| c.as_os_str().to_str().map(|s| s.chars().collect()))
+            .collect();
+        match_segments(&self.segments, &names)
+    }
+
+    /// The leading components that contain no wildcards, i.e. the directory a search for
+    /// matches can start from.
+    pub fn literal_prefix(&self) -> RelPathBuf {
+        let mut prefix = PathBuf::new();
+        for segment in &self.segments {
+            let Segment::Tokens(tokens) = segment else {
+                break;
+            };
+            if tokens.iter().any(Token::is_wildcard) {
+                break;
+            }
+            let name: String = tokens.iter().map(literal_char).collect();
+            prefix.push(name);
+        }
+        // Each name is a single component without a root.
+        RelPathBuf::from_path_buf_unchecked(prefix)
+    }
+}
+
+impl fmt::Debug for GlobPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GlobPattern").field(&self.source).finish()
+    }
+}
+
+impl fmt::Display for GlobPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg.to_owned())
+}
+
+fn literal_char(token: &Token) -> char {
+    match token {
+        Token::Lit(c) => *c,
+        Token::Class { ranges, .. } => ranges[0].0,
+        _ => unreachable!("not a literal"),
+    }
+}
+
+/// Splits `text` into tokens. An unclosed `[` is an error when `strict`, and a literal
+/// otherwise.
+fn lex(text: &str, strict: bool) -> io::Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        tokens.push(match c {
+            '*' => Token::Any,
+            '?' => Token::One,
+            '/' => Token::Sep,
+            '\\' if cfg!(windows) => Token::Sep,
+            '\\' if i < chars.len() => {
+                i += 1;
+                Token::Lit(chars[i - 1])
+            }
+            '[' => match lex_class(&chars[i..]) {
+                Some((token, len)) => {
+                    i += len;
+                    token
+                }
+                None if strict => return Err(invalid("unclosed `[` in glob pattern")),
+                None => Token::Lit('['),
+            },
+            c => Token::Lit(c),
+        });
+    }
+    Ok(tokens)
+}
+
+/// Parses a class after its `[`, returning it and how many characters it used.
+fn lex_class(chars: &[char]) -> Option<(Token, usize)> {
+    let negated = chars.first() == Some(&'!');
+    let mut i = usize::from(negated);
+    let mut ranges = Vec::new();
+    // A `]` right after the opening bracket is a member, not the end.
+    let mut first = true;
+    loop {
+        let c = *chars.get(i)?;
+        if c == ']' && !first {
+            return Some((Token::Class { negated, ranges }, i + 1));
+        }
+        first = false;
+        if chars.get(i + 1) == Some(&'-') && chars.get(i + 2).is_some_and(|&e| e != ']') {
+            ranges.push((c, chars[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+    }
+}
+
+fn match_segments(segments: &[Segment], names: &[Option<Vec<char>>]) -> bool {
+    match_wildcard(
+        segments,
+        names,
+        |segment| matches!(segment, Segment::AnyDirs),
+        |segment, name| match (segment, name) {
+            (Segment::Tokens(tokens), Some(chars)) => match_tokens(tokens, chars),
+            _ => false,
+        },
+    )
+}
+
+fn match_tokens(tokens: &[Token], name: &[char]) -> bool {
+    match_wildcard(
+        tokens,
+        name,
+        |token| matches!(token, Token::Any),
+        |token, &c| match token {
+            Token::Lit(l) => *l == c,
+            Token::One => true,
+            Token::Class { negated, ranges } => {
+                ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != *negated
+            }
+            Token::Any | Token::Sep => unreachable!(),
+        },
+    )
+}
+
+/// Matches `items` against `pattern`, where a `star` element stands for any run of items and
+/// every other element must match exactly one item.
+///
+/// On a mismatch only the most recent star is retried, one item longer: an earlier star could
+/// only absorb items the later one can absorb too. That keeps matching at O(pattern × items)
+/// instead of exponential in the number of stars.
+fn match_wildcard<P, T>(
+    pattern: &[P],
+    items: &[T],
+    star: impl Fn(&P) -> bool,
+    matches_one: impl Fn(&P, &T) -> bool,
+) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // The pattern index after the most recent star, and the item index it has absorbed up to.
+    let mut retry = None;
+    while i < items.len() {
+        if p < pattern.len() && star(&pattern[p]) {
+            p += 1;
+            retry = Some((p, i));
+        } else if p < pattern.len() && matches_one(&pattern[p], &items[i]) {
+            p += 1;
+            i += 1;
+        } else if let Some((after_star, absorbed)) = retry {
+            p = after_star;
+            i = absorbed + 1;
+            retry = Some((after_star, i));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(star)
+}
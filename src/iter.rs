@@ -0,0 +1,163 @@
+//! Adapters for streams of absolute paths.
+//!
+//! [PathIteratorExt] adds the steps most file-processing pipelines share to any iterator of
+//! [AbsPathBuf]s, such as the paths from a [walk](AbsPath::walk):
+//!
+//! ```no_run
+//! use dirge::{AbsPathBuf, iter::PathIteratorExt, map::PathSet};
+//!
+//! let root = AbsPathBuf::new("/src/project").unwrap();
+//! let excluded: PathSet<AbsPathBuf> = [root.try_join("target").unwrap()].into();
+//! let sources = root
+//!     .walk()
+//!     .filter_map(Result::ok)
+//!     .map(|entry| entry.into_path())
+//!     .exclude(&excluded)
+//!     .filter_extensions(["rs"])
+//!     .dedup_by_identity()
+//!     .relative_to(&root);
+//! for source in sources {
+//!     println!("{source:?}");
+//! }
+//! ```
+
+use std::{collections::HashSet, ffi::OsString};
+
+use crate::{
+    AbsPath, AbsPathBuf, RelPathBuf,
+    map::{KeyNormalizer, PathSet},
+};
+
+/// Adapters over iterators of [AbsPathBuf]s; see the [module docs](self).
+pub trait PathIteratorExt: Iterator<Item = AbsPathBuf> + Sized {
+    /// Yields each path relative to `root`, dropping paths that are not at or below it.
+    fn relative_to<P: AsRef<AbsPath>>(self, root: P) -> RelativeTo<Self> {
+        RelativeTo {
+            iter: self,
+            root: root.as_ref().to_owned(),
+        }
+    }
+
+    /// Keeps only paths whose extension is one of `extensions`, given without the dot.
+    /// Extensions are compared exactly, so `RS` does not match `rs`.
+    fn filter_extensions<I>(self, extensions: I) -> FilterExtensions<Self>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        FilterExtensions {
+            iter: self,
+            extensions: extensions.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Drops paths that are in `excluded` or lie below a path that is, so excluding a
+    /// directory excludes everything in it. Paths are compared with the set's normalizer.
+    fn exclude<N: KeyNormalizer>(self, excluded: &PathSet<AbsPathBuf, N>) -> Exclude<'_, Self, N> {
+        Exclude {
+            iter: self,
+            excluded,
+        }
+    }
+
+    /// Drops paths naming a file already yielded, e.g. through a symlink or hard link.
+    ///
+    /// Files are identified by device and inode on Unix and by canonical path elsewhere, which
+    /// costs one `stat` or `canonicalize` per path. Paths that cannot be inspected, such as
+    /// dangling symlinks, are passed through.
+    fn dedup_by_identity(self) -> DedupByIdentity<Self> {
+        DedupByIdentity {
+            iter: self,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = AbsPathBuf>> PathIteratorExt for I {}
+
+/// Iterator returned by [PathIteratorExt::relative_to].
+#[derive(Debug, Clone)]
+pub struct RelativeTo<I> {
+    iter: I,
+    root: AbsPathBuf,
+}
+
+impl<I: Iterator<Item = AbsPathBuf>> Iterator for RelativeTo<I> {
+    type Item = RelPathBuf;
+
+    fn next(&mut self) -> Option<RelPathBuf> {
+        self.iter
+            .find_map(|path| Some(path.strip_prefix(&self.root).ok()?.to_owned()))
+    }
+}
+
+/// Iterator returned by [PathIteratorExt::filter_extensions].
+#[derive(Debug, Clone)]
+pub struct FilterExtensions<I> {
+    iter: I,
+    extensions: Vec<OsString>,
+}
+
+impl<I: Iterator<Item = AbsPathBuf>> Iterator for FilterExtensions<I> {
+    type Item = AbsPathBuf;
+
+    fn next(&mut self) -> Option<AbsPathBuf> {
+        let extensions = &self.extensions;
+        self.iter.find(|path| {
+            path.extension()
+                .is_some_and(|e| extensions.iter().any(|x| x == e))
+        })
+    }
+}
+
+/// Iterator returned by [PathIteratorExt::exclude].
+pub struct Exclude<'a, I, N> {
+    iter: I,
+    excluded: &'a PathSet<AbsPathBuf, N>,
+}
+
+impl<I: Iterator<Item = AbsPathBuf>, N: KeyNormalizer> Iterator for Exclude<'_, I, N> {
+    type Item = AbsPathBuf;
+
+    fn next(&mut self) -> Option<AbsPathBuf> {
+        let excluded = self.excluded;
+        self.iter
+            .find(|path| !path.ancestors().any(|ancestor| excluded.contains(ancestor)))
+    }
+}
+
+/// Iterator returned by [PathIteratorExt::dedup_by_identity].
+#[derive(Debug, Clone)]
+pub struct DedupByIdentity<I> {
+    iter: I,
+    seen: HashSet<Identity>,
+}
+
+#[cfg(unix)]
+type Identity = (u64, u64);
+#[cfg(not(unix))]
+type Identity = AbsPathBuf;
+
+fn identity(path: &AbsPath) -> Option<Identity> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = path.metadata().ok()?;
+        Some((metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        path.canonicalize().ok()
+    }
+}
+
+impl<I: Iterator<Item = AbsPathBuf>> Iterator for DedupByIdentity<I> {
+    type Item = AbsPathBuf;
+
+    fn next(&mut self) -> Option<AbsPathBuf> {
+        let seen = &mut self.seen;
+        self.iter
+            .find(|path| identity(path).is_none_or(|id| seen.insert(id)))
+    }
+}
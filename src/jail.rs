@@ -0,0 +1,155 @@
+//! A read-only view of a directory tree for code that must not modify it.
+//!
+//! ```no_run
+//! use dirge::{AbsPathBuf, RelPathBuf, jail::ReadOnlyJail};
+//! use std::io::Read;
+//!
+//! let jail = ReadOnlyJail::new(AbsPathBuf::new("/srv/plugins/assets").unwrap()).unwrap();
+//! let mut text = String::new();
+//! jail.open(RelPathBuf::new("readme.txt").unwrap())
+//!     .unwrap()
+//!     .read_to_string(&mut text)
+//!     .unwrap();
+//! assert!(jail.open(RelPathBuf::new("../secrets").unwrap()).is_err());
+//! ```
+
+use std::{
+    fs::{File, Metadata},
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use crate::{AbsPath, AbsPathBuf, RelPath, RelPathBuf};
+
+/// Read access to the files below one directory.
+///
+/// Every method takes a path relative to the root and only reads: there is no method that
+/// writes, and the files it opens are [ReadOnlyFile]s, which expose no way to modify the file
+/// or get at the underlying [File]. A plugin handed a `ReadOnlyJail` is therefore read-only by
+/// construction rather than by a runtime policy check.
+///
+/// Paths that climb out of the root with `..`, or that resolve outside it through a symlink,
+/// are rejected with [io::ErrorKind::PermissionDenied]. The symlink check resolves the path
+/// and then opens it, so a tree that is being modified concurrently can race it; the jail is
+/// meant for trees the untrusted code cannot write to.
+#[derive(Debug, Clone)]
+pub struct ReadOnlyJail {
+    root: AbsPathBuf,
+}
+
+impl ReadOnlyJail {
+    /// Creates a jail rooted at `root`, which must be an existing directory.
+    pub fn new<P: AsRef<AbsPath>>(root: P) -> io::Result<Self> {
+        let root = root.as_ref().canonicalize()?;
+        if !root.is_dir() {
+            return Err(crate::fs::with_path(
+                io::Error::new(io::ErrorKind::InvalidInput, "not a directory"),
+                &root,
+            ));
+        }
+        Ok(ReadOnlyJail { root })
+    }
+
+    /// The canonical root directory.
+    pub fn root(&self) -> &AbsPath {
+        &self.root
+    }
+
+    /// Opens a file for reading.
+    pub fn open<P: AsRef<RelPath>>(&self, path: P) -> io::Result<ReadOnlyFile> {
+        let path = self.resolve(path.as_ref())?;
+        File::open(&path)
+            .map(ReadOnlyFile)
+            .map_err(|err| crate::fs::with_path(err, &path))
+    }
+
+    /// Reads a whole file.
+    pub fn read<P: AsRef<RelPath>>(&self, path: P) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.open(path)?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Reads a whole file as UTF-8.
+    pub fn read_to_string<P: AsRef<RelPath>>(&self, path: P) -> io::Result<String> {
+        let mut text = String::new();
+        self.open(path)?.read_to_string(&mut text)?;
+        Ok(text)
+    }
+
+    /// Lists a directory, returning the entries as normalized paths relative to the root,
+    /// sorted.
+    pub fn read_dir<P: AsRef<RelPath>>(&self, path: P) -> io::Result<Vec<RelPathBuf>> {
+        let rel = path.as_ref();
+        let dir = self.resolve(rel)?;
+        let mut entries = std::fs::read_dir(&dir)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| {
+                        let name = entry?.file_name();
+                        // `rel` does not escape the root, so normalizing keeps it relative.
+                        let path = crate::normalize(rel.join(RelPath::new(&name)?));
+                        Ok(RelPathBuf::from_path_buf_unchecked(path))
+                    })
+                    .collect::<io::Result<Vec<_>>>()
+            })
+            .map_err(|err| crate::fs::with_path(err, &dir))?;
+        entries.sort();
+        Ok(entries)
+    }
+
+    /// Returns the metadata of `path`, following symlinks that stay inside the jail.
+    pub fn metadata<P: AsRef<RelPath>>(&self, path: P) -> io::Result<Metadata> {
+        let path = self.resolve(path.as_ref())?;
+        std::fs::metadata(&path).map_err(|err| crate::fs::with_path(err, &path))
+    }
+
+    /// Returns whether `path` exists inside the jail.
+    pub fn exists<P: AsRef<RelPath>>(&self, path: P) -> bool {
+        self.resolve(path.as_ref()).is_ok()
+    }
+
+    fn resolve(&self, rel: &RelPath) -> io::Result<AbsPathBuf> {
+        let outside = || {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{}: path leaves the jail", rel.display()),
+            )
+        };
+        if crate::rel::escapes_root(rel) {
+            return Err(outside());
+        }
+        // On Windows a RelPath can still carry a root or drive, as in `\x` or `D:x`.
+        let resolved = self
+            .root
+            .try_join(rel)
+            .map_err(|_| outside())?
+            .canonicalize()?;
+        if resolved.starts_with(&self.root) {
+            Ok(resolved)
+        } else {
+            Err(outside())
+        }
+    }
+}
+
+/// A file opened by [ReadOnlyJail], which can be read and seeked but not written.
+#[derive(Debug)]
+pub struct ReadOnlyFile(File);
+
+impl ReadOnlyFile {
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.0.metadata()
+    }
+}
+
+impl Read for ReadOnlyFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Seek for ReadOnlyFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
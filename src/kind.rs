@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use crate::{AbsPath, AbsPathBuf, NormPath, NormPathBuf, RelPath, RelPathBuf};
+
+/// The guarantee a dirge path type makes about its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Absolute,
+    Relative,
+    Normalized,
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Implemented by all of dirge's path types, so one generic function can accept any of them.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+///
+/// ```
+/// use dirge::{AbsPathBuf, Kind, PathKind, RelPathBuf};
+///
+/// fn describe<P: PathKind + ?Sized>(p: &P) -> String {
+///     format!("{:?} {}", p.kind(), p.as_path().display())
+/// }
+///
+/// let rel = RelPathBuf::new("src/lib.rs").unwrap();
+/// assert_eq!(describe(&*rel), "Relative src/lib.rs");
+/// assert_eq!(describe(&rel), "Relative src/lib.rs");
+/// ```
+pub trait PathKind: sealed::Sealed + AsRef<Path> {
+    /// The owned buffer type carrying the same guarantee.
+    type Owned: PathKind;
+
+    /// The guarantee this type makes.
+    const KIND: Kind;
+
+    fn as_path(&self) -> &Path {
+        self.as_ref()
+    }
+
+    fn to_owned_buf(&self) -> Self::Owned;
+
+    fn kind(&self) -> Kind {
+        Self::KIND
+    }
+}
+
+macro_rules! impl_path_kind {
+    ($slice:ty, $buf:ty, $kind:expr) => {
+        impl sealed::Sealed for $slice {}
+        impl sealed::Sealed for $buf {}
+
+        impl PathKind for $slice {
+            type Owned = $buf;
+            const KIND: Kind = $kind;
+
+            fn to_owned_buf(&self) -> Self::Owned {
+                self.to_owned()
+            }
+        }
+
+        impl PathKind for $buf {
+            type Owned = $buf;
+            const KIND: Kind = $kind;
+
+            fn to_owned_buf(&self) -> Self::Owned {
+                self.clone()
+            }
+        }
+    };
+}
+
+impl_path_kind!(AbsPath, AbsPathBuf, Kind::Absolute);
+impl_path_kind!(RelPath, RelPathBuf, Kind::Relative);
+impl_path_kind!(NormPath, NormPathBuf, Kind::Normalized);
@@ -74,13 +74,163 @@
 //! - Enhance correctness through specific types.
 //! - Be conducive to re-exporting.
 //! - Be portable.
+//!
+//! ## Ordering
+//! All types implement [Ord] consistently with [std::path::Path]: paths are compared component
+//! by component, so the order never depends on the platform's separator. For example `a/b` sorts
+//! before `a-b` everywhere, which keeps `BTreeMap`s keyed by [RelPathBuf] stable across
+//! platforms.
+//!
+//! ## Typed methods
+//! The types deref to [std::path::Path], but the `Path` methods that would drop the invariant
+//! are shadowed: `join`, `parent`, `with_file_name`, `with_extension` and `canonicalize` return
+//! dirge types where the invariant allows. `NormPath::join` is deprecated in favour of
+//! [NormPath::join_norm], which keeps the result normalized.
+//!
+//! The typed `join`, `push` and `join_norm` take a [RelPath], which cannot have a root or a
+//! Windows drive, so they always extend the path and never panic. For input that has not been
+//! checked yet, `try_join` and `try_push` fail instead of letting an absolute argument replace
+//! the path as [std::path::Path::join] does.
+//!
+//! ## Debug output
+//! `{:?}` formats every type exactly like [std::path::Path], escapes included, so nothing is
+//! lost. The alternate form `{:#?}` instead shows the path as [std::path::Path::display] would,
+//! in quotes, which reads better in logs (especially with Windows backslashes).
+//!
+//! ## Cargo features
+//!
+//! - `serde`: `Serialize`/`Deserialize` for the path types.
+//! - `derive`: the [DirgePath] derive for domain-specific newtypes.
+//! - `utf8-strict`: every constructor additionally rejects non-UTF-8 paths, and `as_str()`
+//!   becomes available on all types. Operations that cannot report an error, such as
+//!   [AbsPathBuf::push] and [ToNormPathBuf::to_norm_path_buf], panic on non-UTF-8 input
+//!   instead.
+//! - `check-invariants`: debug builds assert that every [NormPathBuf] is a fixed point of
+//!   [normalize], to catch normalizer bugs early.
+//! - `cargo-locate`: [cargo::workspace_root] and [cargo::target_dir], which run `cargo`.
+//! - `testing`: the `testing` module, a differential check of [normalize] against the
+//!   filesystem to run in downstream CI.
+//!
+//! ## Unsafe code
+//!
+//! `unsafe` is denied everywhere except one private module of layout casts between the path
+//! types and the std types they wrap, which backs the zero-copy slice, `Vec`, `Arc` and `Box`
+//! conversions. The crate does not bind platform APIs itself: where a feature would need FFI,
+//! such as free-space queries, `ReplaceFileW` or clone ioctls, it uses what std provides and
+//! documents the difference, or is left to crates that do.
 
 #![deny(unsafe_code)]
 
 mod abs;
+pub mod algo;
+mod any;
+mod audit;
+pub mod bulk;
+pub mod cache;
+pub mod cargo;
+mod cast;
+mod cmp;
+pub mod diff;
+pub mod encode;
+pub mod env;
+pub mod ffi;
+mod find;
+pub mod fs;
+pub mod glob;
+pub mod iter;
+pub mod jail;
+mod kind;
+pub mod map;
+mod meta;
+mod mount;
+mod nfc;
 mod norm;
+pub mod prelude;
+pub mod process;
+pub mod project;
+pub mod redact;
 mod rel;
+pub mod router;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod trace;
+mod utf8;
+pub mod validate;
+pub mod walk;
+pub mod win;
+pub mod wire;
+
+pub use abs::{AbsAncestors, AbsPath, AbsPathBuf, ToAbsPathBuf};
+pub use any::{AnyPath, PathValue};
+pub use audit::{AuditFinding, AuditIssue};
+pub use kind::{Kind, PathKind};
+pub use meta::{PathMetadata, SymlinkTarget};
+pub use norm::{NormAncestors, NormPath, NormPathBuf, ToNormPathBuf, is_normalized, normalize};
+pub use rel::{Breadcrumbs, RelAncestors, RelPath, RelPathBuf, ToRelPathBuf};
+pub use utf8::{EscapedDisplay, LossyPolicy, NonUtf8Error};
+
+/// Derives a domain-specific newtype on top of one of dirge's owned path types.
+///
+/// The derive is applied to the owned type and declares its borrowed counterpart, so
+/// downstream crates can layer their own semantics over dirge's invariants without
+/// writing the `Deref`/`Borrow`/`ToOwned` plumbing by hand.
+///
+/// Attributes, all inside `#[dirge(...)]`:
+///
+/// - `borrowed = Name` (required): the borrowed type to declare.
+/// - `validate = path::to::fn`: a `fn(&Inner) -> io::Result<()>` run by every constructor.
+/// - `serde`: also implement `Serialize` for both types and a validating `Deserialize`
+///   for the owned type. Requires the `serde` feature.
+///
+/// The derive implements `Debug` for both types, so don't derive it as well.
+///
+/// The borrowed type derives [RefCastCustom](ref_cast::RefCastCustom) with a private cast, so
+/// the only ways to obtain one are `from_inner` and `Deref` on the owned type, both of which
+/// have run the validator. No `unsafe` code is written in your crate, but the expansion names
+/// the `ref_cast` crate: add `ref-cast = "1"` to your dependencies alongside dirge.
+///
+/// ```
+/// use dirge::{DirgePath, RelPath, RelPathBuf};
+/// use std::io;
+///
+/// fn under_src(p: &RelPath) -> io::Result<()> {
+///     if p.starts_with("src") {
+///         Ok(())
+///     } else {
+///         Err(io::Error::new(io::ErrorKind::InvalidInput, "not under src/"))
+///     }
+/// }
+///
+/// #[derive(DirgePath, Clone, PartialEq, Eq)]
+/// #[dirge(borrowed = SourceRelPath, validate = under_src)]
+/// pub struct SourceRelPathBuf(RelPathBuf);
+///
+/// let main = SourceRelPathBuf::new("src/main.rs").unwrap();
+/// let borrowed: &SourceRelPath = &main;
+/// assert_eq!(borrowed.file_name().unwrap(), "main.rs");
+///
+/// assert!(SourceRelPathBuf::new("tests/it.rs").is_err());
+/// ```
+///
+/// The cast itself is not reachable, so a `&SourceRelPath` can't skip the validator:
+///
+/// ```compile_fail
+/// use dirge::{DirgePath, RelPath, RelPathBuf};
+/// use ref_cast::RefCast;
+///
+/// #[derive(DirgePath, Clone, PartialEq, Eq)]
+/// #[dirge(borrowed = SourceRelPath)]
+/// pub struct SourceRelPathBuf(RelPathBuf);
+///
+/// let tests = RelPath::new("tests/it.rs").unwrap();
+/// let _ = SourceRelPath::ref_cast(tests);
+/// ```
+#[cfg(feature = "derive")]
+pub use dirge_derive::DirgePath;
 
-pub use abs::{AbsPath, AbsPathBuf, ToAbsPathBuf};
-pub use norm::{NormPath, NormPathBuf, ToNormPathBuf};
-pub use rel::{RelPath, RelPathBuf, ToRelPathBuf};
+#[doc(hidden)]
+pub mod __private {
+    pub use ref_cast;
+    #[cfg(feature = "serde")]
+    pub use serde;
+}
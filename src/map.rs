@@ -0,0 +1,321 @@
+//! A map keyed by paths that compares keys through a pluggable normalization.
+//!
+//! ```
+//! use dirge::{RelPathBuf, map::PathMap};
+//!
+//! let mut assets: PathMap<RelPathBuf, u32> = PathMap::new();
+//! assets.insert(RelPathBuf::new("Textures/Grass.PNG").unwrap(), 1);
+//!
+//! assert_eq!(assets.get("textures/grass.png"), Some(&1));
+//! let (key, _) = assets.iter().next().unwrap();
+//! assert_eq!(key.to_string_lossy(), "Textures/Grass.PNG");
+//! ```
+
+use std::{
+    collections::{BTreeMap, btree_map},
+    fmt,
+    path::{Component, Path},
+};
+
+use crate::EscapedDisplay;
+
+/// Computes the comparison key of a path for [PathMap] and [PathSet].
+///
+/// Paths with equal keys are the same entry. The built-in policies are [Separators] (exact
+/// apart from the separator spelling), [AsciiCaseFold] and [CaseFold]. Implemented for
+/// closures, so other schemes such as Unicode NFC can be plugged in.
+pub trait KeyNormalizer {
+    fn normalize_key(&self, path: &Path) -> String;
+}
+
+impl<F: Fn(&Path) -> String> KeyNormalizer for F {
+    fn normalize_key(&self, path: &Path) -> String {
+        self(path)
+    }
+}
+
+/// Compares paths component-wise with `/` as the only separator, so `a\b` and `a/b` are the
+/// same key on Windows. Non-UTF-8 bytes are escaped, never merged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Separators;
+
+impl KeyNormalizer for Separators {
+    fn normalize_key(&self, path: &Path) -> String {
+        let segments: Vec<String> = path
+            .components()
+            .map(|c| match c {
+                Component::RootDir => String::new(),
+                c => EscapedDisplay(Path::new(c.as_os_str())).to_string(),
+            })
+            .collect();
+        segments.join("/")
+    }
+}
+
+/// Like [Separators], and additionally ignores case (Unicode lowercase mapping).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CaseFold;
+
+impl KeyNormalizer for CaseFold {
+    fn normalize_key(&self, path: &Path) -> String {
+        Separators.normalize_key(path).to_lowercase()
+    }
+}
+
+/// Like [Separators], and additionally ignores ASCII case only, as FAT and Windows' own
+/// upcase table do for the characters most paths use. Cheaper than [CaseFold].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsciiCaseFold;
+
+impl KeyNormalizer for AsciiCaseFold {
+    fn normalize_key(&self, path: &Path) -> String {
+        let mut key = Separators.normalize_key(path);
+        key.make_ascii_lowercase();
+        key
+    }
+}
+
+/// A map from paths to values that looks keys up by their normalized form while keeping each
+/// key as it was first inserted, for iteration and display.
+///
+/// Iteration is ordered by normalized key. By default keys are compared case-insensitively;
+/// see [KeyNormalizer] for other choices.
+#[derive(Clone)]
+pub struct PathMap<K, V, N = CaseFold> {
+    entries: BTreeMap<String, (K, V)>,
+    normalizer: N,
+}
+
+impl<K, V, N: Default> Default for PathMap<K, V, N> {
+    fn default() -> Self {
+        Self::with_normalizer(N::default())
+    }
+}
+
+impl<K, V, N: Default> PathMap<K, V, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K, V, N> PathMap<K, V, N> {
+    pub fn with_normalizer(normalizer: N) -> Self {
+        PathMap {
+            entries: BTreeMap::new(),
+            normalizer,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries with their originally inserted keys.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter(self.entries.values())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K: AsRef<Path>, V, N: KeyNormalizer> PathMap<K, V, N> {
+    /// Inserts `value` under `key`, returning the previous value for an equivalent key.
+    ///
+    /// As with [std::collections::HashMap], an existing entry keeps its original key.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self
+            .entries
+            .entry(self.normalizer.normalize_key(key.as_ref()))
+        {
+            btree_map::Entry::Occupied(mut entry) => {
+                Some(std::mem::replace(&mut entry.get_mut().1, value))
+            }
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&V> {
+        self.get_key_value(path).map(|(_, v)| v)
+    }
+
+    /// Returns the entry for `path` together with its originally inserted key.
+    pub fn get_key_value<P: AsRef<Path>>(&self, path: P) -> Option<(&K, &V)> {
+        let (k, v) = self.entries.get(&self.key(path))?;
+        Some((k, v))
+    }
+
+    pub fn get_mut<P: AsRef<Path>>(&mut self, path: P) -> Option<&mut V> {
+        let key = self.key(path);
+        self.entries.get_mut(&key).map(|(_, v)| v)
+    }
+
+    pub fn contains_key<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.entries.contains_key(&self.key(path))
+    }
+
+    /// Removes the entry for `path`, returning its original key and value.
+    pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> Option<(K, V)> {
+        let key = self.key(path);
+        self.entries.remove(&key)
+    }
+
+    fn key<P: AsRef<Path>>(&self, path: P) -> String {
+        self.normalizer.normalize_key(path.as_ref())
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, N> fmt::Debug for PathMap<K, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: AsRef<Path>, V, N: KeyNormalizer + Default> FromIterator<(K, V)> for PathMap<K, V, N> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: AsRef<Path>, V, N: KeyNormalizer> Extend<(K, V)> for PathMap<K, V, N> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+/// Iterator returned by [PathMap::iter].
+pub struct Iter<'a, K, V>(btree_map::Values<'a, String, (K, V)>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, v)| (k, v))
+    }
+}
+
+impl<'a, K, V, N> IntoIterator for &'a PathMap<K, V, N> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A set of paths compared through a [KeyNormalizer], keeping each path as first inserted.
+///
+/// ```
+/// use dirge::map::{PathSet, Separators};
+/// use std::path::PathBuf;
+///
+/// let mut exact: PathSet<PathBuf, Separators> = PathSet::new();
+/// exact.insert(PathBuf::from("README.md"));
+/// assert!(!exact.contains("readme.md"));
+///
+/// let insensitive: PathSet<PathBuf> = ["README.md", "readme.md"].map(PathBuf::from).into();
+/// assert_eq!(insensitive.len(), 1);
+/// ```
+#[derive(Clone)]
+pub struct PathSet<K, N = CaseFold> {
+    map: PathMap<K, (), N>,
+}
+
+impl<K, N: Default> Default for PathSet<K, N> {
+    fn default() -> Self {
+        Self::with_normalizer(N::default())
+    }
+}
+
+impl<K, N: Default> PathSet<K, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K, N> PathSet<K, N> {
+    pub fn with_normalizer(normalizer: N) -> Self {
+        PathSet {
+            map: PathMap::with_normalizer(normalizer),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterates over the originally inserted paths, ordered by normalized key.
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.map.keys()
+    }
+}
+
+impl<K: AsRef<Path>, N: KeyNormalizer> PathSet<K, N> {
+    /// Adds `path`, returning `false` if an equivalent path was already present. The existing
+    /// path is kept.
+    pub fn insert(&mut self, path: K) -> bool {
+        self.map.insert(path, ()).is_none()
+    }
+
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.map.contains_key(path)
+    }
+
+    /// Returns the originally inserted path equivalent to `path`.
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&K> {
+        self.map.get_key_value(path).map(|(k, _)| k)
+    }
+
+    /// Removes the path equivalent to `path`, returning it as originally inserted.
+    pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> Option<K> {
+        self.map.remove(path).map(|(k, _)| k)
+    }
+}
+
+impl<K: fmt::Debug, N> fmt::Debug for PathSet<K, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<K: AsRef<Path>, N: KeyNormalizer + Default> FromIterator<K> for PathSet<K, N> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<K: AsRef<Path>, N: KeyNormalizer> Extend<K> for PathSet<K, N> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for k in iter {
+            self.insert(k);
+        }
+    }
+}
+
+impl<K: AsRef<Path>, N: KeyNormalizer + Default, const M: usize> From<[K; M]> for PathSet<K, N> {
+    fn from(paths: [K; M]) -> Self {
+        paths.into_iter().collect()
+    }
+}
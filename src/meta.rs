@@ -0,0 +1,117 @@
+use std::{
+    fs::{self, FileType},
+    io,
+    ops::Deref,
+    path::Path,
+};
+
+use crate::{AbsPath, AbsPathBuf, PathValue};
+
+impl AbsPath {
+    /// Like [Path::metadata], following symlinks, with errors that name the path.
+    pub fn metadata_typed(&self) -> io::Result<PathMetadata> {
+        let metadata = self
+            .metadata()
+            .map_err(|err| crate::fs::with_path(err, self))?;
+        Ok(PathMetadata {
+            metadata,
+            target: None,
+        })
+    }
+
+    /// Like [Path::symlink_metadata]; if the path is a symlink its target is read as well and
+    /// is available from [PathMetadata::symlink_target].
+    pub fn symlink_metadata_typed(&self) -> io::Result<PathMetadata> {
+        let metadata = self
+            .symlink_metadata()
+            .map_err(|err| crate::fs::with_path(err, self))?;
+        let target = if metadata.is_symlink() {
+            let target = self
+                .read_link()
+                .and_then(PathValue::new)
+                .map_err(|err| crate::fs::with_path(err, self))?;
+            Some(SymlinkTarget {
+                link: self.to_owned(),
+                target,
+            })
+        } else {
+            None
+        };
+        Ok(PathMetadata { metadata, target })
+    }
+}
+
+/// [fs::Metadata] together with the typed target of a symlink.
+///
+/// Created by [AbsPath::metadata_typed] and [AbsPath::symlink_metadata_typed]. Derefs to
+/// [fs::Metadata].
+#[derive(Debug, Clone)]
+pub struct PathMetadata {
+    metadata: fs::Metadata,
+    target: Option<SymlinkTarget>,
+}
+
+impl PathMetadata {
+    pub fn file_type(&self) -> FileType {
+        self.metadata.file_type()
+    }
+
+    /// The target of the symlink, if this is the metadata of a symlink itself.
+    pub fn symlink_target(&self) -> Option<&SymlinkTarget> {
+        self.target.as_ref()
+    }
+
+    pub fn into_metadata(self) -> fs::Metadata {
+        self.metadata
+    }
+}
+
+impl Deref for PathMetadata {
+    type Target = fs::Metadata;
+
+    fn deref(&self) -> &Self::Target {
+        &self.metadata
+    }
+}
+
+/// Where a symlink points, classified without touching the file system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymlinkTarget {
+    link: AbsPathBuf,
+    target: PathValue,
+}
+
+impl SymlinkTarget {
+    /// The target as stored in the link: [PathValue::Abs] or [PathValue::Rel].
+    pub fn target(&self) -> &PathValue {
+        &self.target
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        matches!(self.target, PathValue::Abs(_))
+    }
+
+    /// The absolute, normalized path the link points to.
+    ///
+    /// A relative target is resolved against the directory containing the link. Resolution is
+    /// lexical, so symlinks along the way, including the target itself, are not followed.
+    pub fn resolve(&self) -> AbsPathBuf {
+        let joined = match &self.target {
+            PathValue::Rel(rel) => match self.link.parent() {
+                Some(dir) => Path::join(dir, rel),
+                None => rel.to_path_buf(),
+            },
+            other => other.as_path().to_path_buf(),
+        };
+        AbsPathBuf::from_path_buf_unchecked(crate::normalize(joined))
+    }
+
+    /// Reports whether the link points inside `root`, judged lexically by [Self::resolve].
+    ///
+    /// `root` is normalized before comparing, and a link pointing at `root` itself counts as
+    /// inside. Tools extracting or copying a tree can use this to refuse links that escape it.
+    pub fn is_within<P: AsRef<AbsPath>>(&self, root: P) -> bool {
+        let root = crate::normalize(root.as_ref());
+        self.resolve().starts_with(root)
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    io,
+    path::{Component, Prefix},
+};
+
+use crate::{AbsPath, AbsPathBuf};
+
+impl AbsPath {
+    /// Returns `true` if the path is a Windows UNC path such as `\\server\share\file`.
+    ///
+    /// This is purely lexical and always `false` on platforms without path prefixes.
+    pub fn is_unc(&self) -> bool {
+        matches!(
+            self.components().next(),
+            Some(Component::Prefix(p)) if matches!(p.kind(), Prefix::UNC(..) | Prefix::VerbatimUNC(..))
+        )
+    }
+
+    /// Reports whether the path lives on a network file system (NFS, SMB, ...).
+    ///
+    /// On Linux the mount table in `/proc/self/mountinfo` is consulted, so the path must exist.
+    /// On Windows UNC paths are reported as network paths; detecting mapped drive letters is
+    /// not supported and fails with [io::ErrorKind::Unsupported], as do other platforms.
+    pub fn is_network_drive(&self) -> io::Result<bool> {
+        if self.is_unc() {
+            return Ok(true);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let canonical = self.canonicalize()?;
+            let mountinfo = std::fs::read_to_string("/proc/self/mountinfo")?;
+            let fs_type = linux::fs_type(&mountinfo, &canonical).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "no mount contains this path")
+            })?;
+            Ok(linux::is_network_fs(&fs_type))
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "network drive detection is not supported on this platform",
+            ))
+        }
+    }
+
+    /// Returns the root of the file system (mount point or volume) containing the path.
+    ///
+    /// The path is canonicalized first, so it must exist. On Unix this is the topmost ancestor
+    /// on the same device; bind mounts of the same device are not told apart. On Windows this is
+    /// the root of the path's prefix, e.g. `C:\` or `\\server\share\`.
+    pub fn mount_point(&self) -> io::Result<AbsPathBuf> {
+        let canonical = self.canonicalize()?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            let dev = canonical.metadata()?.dev();
+            let mut mount_point = canonical.as_path();
+            while let Some(parent) = mount_point.parent() {
+                if parent.metadata()?.dev() != dev {
+                    break;
+                }
+                mount_point = parent;
+            }
+            Ok(AbsPathBuf::from_path_buf_unchecked(
+                mount_point.to_path_buf(),
+            ))
+        }
+
+        #[cfg(not(unix))]
+        {
+            let root: std::path::PathBuf = canonical
+                .components()
+                .take_while(|c| matches!(c, Component::Prefix(_) | Component::RootDir))
+                .collect();
+            Ok(AbsPathBuf::from_path_buf_unchecked(root))
+        }
+    }
+
+    /// Reports whether `self` and `other` live on the same file system.
+    ///
+    /// Both paths must exist. Tools that walk or copy trees can use this to avoid descending
+    /// into `/proc`, network mounts or other volumes.
+    pub fn same_file_system<P: AsRef<AbsPath>>(&self, other: P) -> io::Result<bool> {
+        let other = other.as_ref();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            Ok(self.metadata()?.dev() == other.metadata()?.dev())
+        }
+
+        #[cfg(not(unix))]
+        {
+            Ok(self.mount_point()? == other.mount_point()?)
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::path::{Path, PathBuf};
+
+    const NETWORK_FS_TYPES: &[&str] = &[
+        "9p",
+        "afs",
+        "ceph",
+        "cifs",
+        "coda",
+        "davfs",
+        "fuse.sshfs",
+        "glusterfs",
+        "lustre",
+        "ncpfs",
+        "nfs",
+        "nfs4",
+        "smb3",
+        "smbfs",
+    ];
+
+    pub(super) fn is_network_fs(fs_type: &str) -> bool {
+        NETWORK_FS_TYPES.contains(&fs_type)
+    }
+
+    /// Finds the file system type of the innermost mount containing `path`.
+    pub(super) fn fs_type(mountinfo: &str, path: &Path) -> Option<String> {
+        mountinfo
+            .lines()
+            .filter_map(parse_line)
+            .filter(|(mount_point, _)| path.starts_with(mount_point))
+            .max_by_key(|(mount_point, _)| mount_point.components().count())
+            .map(|(_, fs_type)| fs_type)
+    }
+
+    /// Extracts the mount point (field 5) and file system type (first field after `-`).
+    fn parse_line(line: &str) -> Option<(PathBuf, String)> {
+        let mut fields = line.split(' ');
+        let mount_point = fields.nth(4)?;
+        let fs_type = fields.skip_while(|f| *f != "-").nth(1)?;
+        Some((PathBuf::from(unescape(mount_point)), fs_type.to_owned()))
+    }
+
+    /// Undoes the octal escaping (`\040` for space, ...) applied to mount points.
+    fn unescape(field: &str) -> String {
+        let mut out = String::with_capacity(field.len());
+        let mut rest = field;
+        while let Some(i) = rest.find('\\') {
+            out.push_str(&rest[..i]);
+            let escaped = rest
+                .get(i + 1..i + 4)
+                .and_then(|o| u8::from_str_radix(o, 8).ok());
+            match escaped {
+                Some(byte) => {
+                    out.push(char::from(byte));
+                    rest = &rest[i + 4..];
+                }
+                None => {
+                    out.push('\\');
+                    rest = &rest[i + 1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const MOUNTINFO: &str = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+40 22 0:35 / /mnt/share rw,relatime shared:20 - nfs4 server:/export rw
+41 40 0:36 / /mnt/share/local\\040disk rw - ext4 /dev/sdb1 rw
+";
+
+        #[test]
+        fn innermost_mount_wins() {
+            let fs = |p: &str| fs_type(MOUNTINFO, Path::new(p));
+            assert_eq!(fs("/home/user").as_deref(), Some("ext4"));
+            assert_eq!(fs("/mnt/share/data").as_deref(), Some("nfs4"));
+            assert_eq!(fs("/mnt/share/local disk/x").as_deref(), Some("ext4"));
+        }
+
+        #[test]
+        fn prefix_is_component_wise() {
+            assert_eq!(
+                fs_type(MOUNTINFO, Path::new("/mnt/shared")).as_deref(),
+                Some("ext4")
+            );
+        }
+
+        #[test]
+        fn network_types() {
+            assert!(is_network_fs("nfs4"));
+            assert!(is_network_fs("cifs"));
+            assert!(!is_network_fs("ext4"));
+            assert!(!is_network_fs("tmpfs"));
+        }
+    }
+}
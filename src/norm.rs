@@ -1,43 +1,146 @@
 use std::{
-    borrow::Borrow,
+    borrow::{Borrow, Cow},
+    ffi::OsStr,
     io,
     ops::Deref,
-    path::{Component, Path, PathBuf},
+    path::{Component, Path, PathBuf, StripPrefixError},
+    sync::Arc,
 };
 
 use std::fmt;
 
 use ref_cast::RefCast;
 
+use crate::{AbsPath, AbsPathBuf, EscapedDisplay, LossyPolicy, NonUtf8Error, RelPath, cast};
+
 /// Equivalent to [PathBuf], but guaranteed to be normalized.
 ///
 /// A normalized path has no `.` or `..` components and uses canonical separators.
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 #[repr(transparent)]
 pub struct NormPathBuf(PathBuf);
 
 /// Equivalent to [Path], but guaranteed to be normalized.
 ///
 /// A normalized path has no `.` or `..` components and uses canonical separators.
-#[derive(RefCast, PartialEq, Eq)]
+#[derive(RefCast, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct NormPath(Path);
 
+/// Iterator returned by [NormPath::ancestors].
+#[derive(Debug, Clone)]
+pub struct NormAncestors<'a>(std::path::Ancestors<'a>);
+
+impl<'a> Iterator for NormAncestors<'a> {
+    type Item = &'a NormPath;
+
+    fn next(&mut self) -> Option<&'a NormPath> {
+        self.0.next().map(NormPath::ref_cast)
+    }
+}
+
+impl std::iter::FusedIterator for NormAncestors<'_> {}
+
 impl fmt::Debug for NormPath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self.0, f)
+        crate::utf8::debug(&self.0, f)
     }
 }
 
 impl fmt::Debug for NormPathBuf {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self.0, f)
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Displays the path like [Path::display], replacing non-UTF-8 content with U+FFFD. Use
+/// [NormPath::display_escaped] to keep such bytes distinguishable.
+///
+/// For a path that is valid UTF-8, parsing the output with [FromStr](std::str::FromStr) gives
+/// back the same path, byte for byte, trailing separator included. Otherwise the output is
+/// lossy: it parses, if at all, to a different path.
+impl fmt::Display for NormPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0.display(), f)
+    }
+}
+
+impl fmt::Display for NormPathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+/// Parses with the same rules as [NormPathBuf::new]. The inverse of `Display` for paths that
+/// are valid UTF-8.
+impl std::str::FromStr for NormPathBuf {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        NormPathBuf::new(s)
+    }
+}
+
+/// Normalizes like [NormPathBuf::new], reusing the buffer if the path is already normalized.
+impl TryFrom<PathBuf> for NormPathBuf {
+    type Error = io::Error;
+
+    fn try_from(p: PathBuf) -> io::Result<Self> {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(&p)?;
+        if is_normalized(&p) {
+            Ok(NormPathBuf(p))
+        } else {
+            Ok(NormPathBuf::from_path_buf_unchecked(normalize(p)))
+        }
+    }
+}
+
+impl TryFrom<&Path> for NormPathBuf {
+    type Error = io::Error;
+
+    fn try_from(p: &Path) -> io::Result<Self> {
+        NormPathBuf::new(p)
+    }
+}
+
+impl TryFrom<String> for NormPathBuf {
+    type Error = io::Error;
+
+    fn try_from(s: String) -> io::Result<Self> {
+        NormPathBuf::try_from(PathBuf::from(s))
+    }
+}
+
+impl TryFrom<std::ffi::OsString> for NormPathBuf {
+    type Error = io::Error;
+
+    fn try_from(s: std::ffi::OsString) -> io::Result<Self> {
+        NormPathBuf::try_from(PathBuf::from(s))
     }
 }
 
 impl NormPathBuf {
+    /// Normalizes `p`, reporting unacceptable input as an error.
+    ///
+    /// Normalization itself cannot fail, so this only returns an error under the `utf8-strict`
+    /// feature. Use [ToNormPathBuf::to_norm_path_buf] where that feature is not a concern.
     pub fn new<P: AsRef<Path>>(p: P) -> io::Result<Self> {
-        p.as_ref().to_norm_path_buf()
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(p.as_ref())?;
+        Ok(NormPathBuf::from_path_buf_unchecked(normalize(p)))
+    }
+
+    /// Wraps `p` without checking the invariant; callers must have established it.
+    ///
+    /// Under the `check-invariants` feature, debug builds assert that `p` is already normalized.
+    pub(crate) fn from_path_buf_unchecked(p: PathBuf) -> Self {
+        #[cfg(feature = "check-invariants")]
+        debug_assert!(
+            is_canonical_form(&p),
+            "{p:?} is not a fixed point of normalization"
+        );
+        NormPathBuf(p)
     }
 }
 
@@ -53,43 +156,167 @@ impl AsRef<Path> for NormPath {
     }
 }
 
+impl AsRef<OsStr> for NormPathBuf {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl AsRef<OsStr> for NormPath {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl AsRef<NormPath> for NormPathBuf {
+    fn as_ref(&self) -> &NormPath {
+        self
+    }
+}
+
+impl AsRef<NormPath> for NormPath {
+    fn as_ref(&self) -> &NormPath {
+        self
+    }
+}
+
+impl From<&NormPath> for Arc<NormPath> {
+    fn from(p: &NormPath) -> Self {
+        NormPath::from_arc_unchecked(Arc::from(&p.0))
+    }
+}
+
+impl From<NormPathBuf> for Arc<NormPath> {
+    fn from(p: NormPathBuf) -> Self {
+        Arc::from(&*p)
+    }
+}
+
+impl From<NormPathBuf> for PathBuf {
+    fn from(p: NormPathBuf) -> Self {
+        p.0
+    }
+}
+
+impl From<NormPathBuf> for Box<NormPath> {
+    fn from(p: NormPathBuf) -> Self {
+        NormPath::from_box_unchecked(p.0.into_boxed_path())
+    }
+}
+
+impl From<&NormPath> for Box<NormPath> {
+    fn from(p: &NormPath) -> Self {
+        Box::from(p.to_owned())
+    }
+}
+
 pub trait ToNormPathBuf: AsRef<Path> {
-    fn to_norm_path_buf(&self) -> io::Result<NormPathBuf>;
+    /// Normalizes the path lexically, which cannot fail.
+    ///
+    /// Panics under the `utf8-strict` feature if the path is not valid UTF-8; use
+    /// [NormPathBuf::new] to handle that case as an error.
+    fn to_norm_path_buf(&self) -> NormPathBuf;
 }
 
-fn normalize_path(path: &Path) -> PathBuf {
+/// Normalizes `path` lexically, exactly as [NormPathBuf] does.
+///
+/// `.` components are dropped and each `..` removes the preceding normal component. A `..`
+/// with nothing left to remove is kept in a relative path and dropped directly after the root,
+/// since the parent of `/` is `/`. An empty result becomes `.`. The output is always a fixed
+/// point: normalizing it again returns it unchanged.
+///
+/// ```
+/// use std::path::Path;
+///
+/// assert_eq!(dirge::normalize("a/./b/../c"), Path::new("a/c"));
+/// assert_eq!(dirge::normalize("../../x"), Path::new("../../x"));
+/// assert_eq!(dirge::normalize("/../x"), Path::new("/x"));
+/// ```
+pub fn normalize<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
     let mut normalized = PathBuf::new();
+    push_normalized(&mut normalized, path);
 
+    // If the path is now empty and the original wasn't, use "."
+    if normalized.as_os_str().is_empty() && !path.as_os_str().is_empty() {
+        normalized.push(".");
+    }
+
+    normalized
+}
+
+/// Appends `path` to the already normalized `normalized`, keeping it normalized.
+fn push_normalized(normalized: &mut PathBuf, path: &Path) {
     for component in path.components() {
         match component {
-            Component::CurDir => {
-                // Skip "." components
-            }
-            Component::ParentDir => {
-                // Handle ".." by popping the last component if possible
-                if !normalized.pop() {
-                    // If we can't pop (empty path), keep the ".."
-                    normalized.push("..");
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
                 }
-            }
-            _ => {
-                normalized.push(component);
-            }
+                Some(Component::RootDir) => {}
+                _ => normalized.push(".."),
+            },
+            c => normalized.push(c),
         }
     }
+}
 
-    // If the path is now empty and the original wasn't, use "."
-    if normalized.as_os_str().is_empty() && !path.as_os_str().is_empty() {
-        normalized.push(".");
+/// Returns whether `path` is already in the form [normalize] produces, without allocating.
+///
+/// ```
+/// assert!(dirge::is_normalized("a/b"));
+/// assert!(dirge::is_normalized("../a"));
+/// assert!(!dirge::is_normalized("a/../b"));
+/// assert!(!dirge::is_normalized("./a"));
+/// assert!(!dirge::is_normalized("a//b"));
+/// ```
+pub fn is_normalized<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    let bytes = path.as_os_str().as_encoded_bytes();
+    let sep = std::path::MAIN_SEPARATOR_STR.as_bytes();
+
+    // Walk the components, checking each is allowed and that the bytes are exactly what
+    // `PathBuf::push` would have produced for them.
+    let mut pos = 0;
+    let mut prev = None;
+    for component in path.components() {
+        let allowed = match component {
+            Component::CurDir => bytes == b".",
+            Component::ParentDir => matches!(
+                prev,
+                None | Some(Component::ParentDir | Component::Prefix(_))
+            ),
+            _ => true,
+        };
+        if !allowed {
+            return false;
+        }
+        if matches!(prev, Some(Component::Normal(_) | Component::ParentDir)) {
+            if !bytes[pos..].starts_with(sep) {
+                return false;
+            }
+            pos += sep.len();
+        }
+        let text = component.as_os_str().as_encoded_bytes();
+        if !bytes[pos..].starts_with(text) {
+            return false;
+        }
+        pos += text.len();
+        prev = Some(component);
     }
+    pos == bytes.len()
+}
 
-    normalized
+pub(crate) fn is_canonical_form(path: &Path) -> bool {
+    normalize(path).as_os_str() == path.as_os_str()
 }
 
 impl<P: AsRef<Path>> ToNormPathBuf for P {
-    fn to_norm_path_buf(&self) -> io::Result<NormPathBuf> {
-        let normalized = normalize_path(self.as_ref());
-        Ok(NormPathBuf(normalized))
+    fn to_norm_path_buf(&self) -> NormPathBuf {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::assert(self.as_ref());
+        NormPathBuf::from_path_buf_unchecked(normalize(self))
     }
 }
 
@@ -131,6 +358,242 @@ impl NormPathBuf {
     pub fn capacity(&self) -> usize {
         self.0.capacity()
     }
+
+    /// Consumes the path, returning the inner [PathBuf] without copying it.
+    pub fn into_path_buf(self) -> PathBuf {
+        self.0
+    }
+
+    /// Like [PathBuf::set_extension], keeping the path normalized.
+    ///
+    /// Panics under the `utf8-strict` feature if `extension` is not valid UTF-8.
+    pub fn set_extension<S: AsRef<OsStr>>(&mut self, extension: S) -> bool {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::assert(Path::new(&extension));
+        let changed = self.0.set_extension(extension);
+        // An extension containing separators can add `.` or `..` components.
+        if changed && !is_normalized(&self.0) {
+            self.0 = normalize(&self.0);
+        }
+        changed
+    }
+
+    /// Like [PathBuf::set_file_name], validating `file_name` as [NormPath::with_file_name] does
+    /// and leaving the path unchanged if it is rejected.
+    pub fn set_file_name<S: AsRef<OsStr>>(&mut self, file_name: S) -> io::Result<()> {
+        let file_name = crate::rel::check_segment(file_name.as_ref())?;
+        *self = self.replace_file_name(file_name);
+        Ok(())
+    }
+
+    /// Like [NormPathBuf::new], taking over the string's buffer when it is owned.
+    pub fn from_utf8_cow(s: Cow<'_, str>) -> io::Result<Self> {
+        match s {
+            Cow::Borrowed(s) => NormPathBuf::new(s),
+            Cow::Owned(s) => NormPathBuf::try_from(s),
+        }
+    }
+
+    /// Views a slice of paths as [PathBuf]s, without copying.
+    pub fn cast_slice(paths: &[NormPathBuf]) -> &[PathBuf] {
+        cast::inner_slice(paths)
+    }
+
+    /// Converts a vector of paths into [PathBuf]s, reusing its allocation.
+    pub fn into_path_buf_vec(paths: Vec<NormPathBuf>) -> Vec<PathBuf> {
+        cast::into_inner_vec(paths)
+    }
+
+    /// Views a slice of [PathBuf]s as this type, without copying, if every path satisfies its
+    /// invariant. Otherwise fails with the error for the first path that does not.
+    pub fn try_cast_slice(paths: &[PathBuf]) -> io::Result<&[NormPathBuf]> {
+        for path in paths {
+            NormPath::try_new(path)?;
+        }
+        Ok(cast::slice_unchecked(paths))
+    }
+
+    /// Converts a vector of [PathBuf]s into this type, reusing its allocation, if every path
+    /// satisfies its invariant. Otherwise fails with the error for the first path that does not.
+    pub fn try_from_path_buf_vec(paths: Vec<PathBuf>) -> io::Result<Vec<NormPathBuf>> {
+        for path in &paths {
+            NormPath::try_new(path)?;
+        }
+        Ok(cast::vec_unchecked(paths))
+    }
+}
+
+impl NormPath {
+    /// Views `p` as a [NormPath] without copying it, if it is already normalized.
+    ///
+    /// Fails with [io::ErrorKind::InvalidInput] otherwise; see [is_normalized].
+    ///
+    /// ```
+    /// use dirge::NormPath;
+    ///
+    /// assert!(NormPath::try_new("docs/guide.md").is_ok());
+    /// assert!(NormPath::try_new("docs/../guide.md").is_err());
+    /// ```
+    pub fn try_new<P: AsRef<Path> + ?Sized>(p: &P) -> io::Result<&NormPath> {
+        let path = p.as_ref();
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(path)?;
+        if is_normalized(path) {
+            Ok(NormPath::ref_cast(path))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path is not normalized",
+            ))
+        }
+    }
+
+    /// Like [Path::parent]; the parent of a normalized path is normalized.
+    pub fn parent(&self) -> Option<&NormPath> {
+        self.0.parent().map(NormPath::ref_cast)
+    }
+
+    /// Like [Path::ancestors]: `self`, then each parent in turn. Every ancestor of a normalized
+    /// path is normalized.
+    pub fn ancestors(&self) -> NormAncestors<'_> {
+        NormAncestors(self.0.ancestors())
+    }
+
+    /// Like [Path::strip_prefix], but the remainder is typed as relative. Stripping an empty
+    /// `base` from an absolute path fails, as the remainder would be the whole path.
+    pub fn strip_prefix<P: AsRef<Path>>(&self, base: P) -> Result<&RelPath, StripPrefixError> {
+        let rest = self.0.strip_prefix(base)?;
+        if rest.is_relative() {
+            Ok(RelPath::from_path_unchecked(rest))
+        } else {
+            // StripPrefixError has no public constructor; `self` is not empty here, so this
+            // always fails.
+            Err(Path::new("").strip_prefix(&self.0).unwrap_err())
+        }
+    }
+
+    /// Like [Path::with_file_name], but fails with [io::ErrorKind::InvalidInput] unless
+    /// `file_name` is a single normal component: not empty, `.` or `..`, and without separators.
+    /// Under the `utf8-strict` feature it must also be valid UTF-8.
+    ///
+    /// The result stays normalized, so the file name of `.` is replaced by just the name.
+    pub fn with_file_name<S: AsRef<OsStr>>(&self, file_name: S) -> io::Result<NormPathBuf> {
+        let file_name = crate::rel::check_segment(file_name.as_ref())?;
+        Ok(self.replace_file_name(file_name))
+    }
+
+    /// [Path::with_file_name], normalizing the result; `file_name` may have several components.
+    fn replace_file_name(&self, file_name: &OsStr) -> NormPathBuf {
+        self.0.with_file_name(file_name).to_norm_path_buf()
+    }
+
+    /// Like [Path::with_extension], normalizing the result.
+    ///
+    /// Panics under the `utf8-strict` feature if `extension` is not valid UTF-8.
+    pub fn with_extension<S: AsRef<OsStr>>(&self, extension: S) -> NormPathBuf {
+        self.0.with_extension(extension).to_norm_path_buf()
+    }
+
+    /// Like [Path::canonicalize]; a relative path is resolved against the current directory.
+    pub fn canonicalize(&self) -> io::Result<AbsPathBuf> {
+        AbsPath::from_path_unchecked(&std::path::absolute(&self.0)?).canonicalize()
+    }
+
+    /// Superseded by [NormPath::join_norm], which keeps the result normalized.
+    #[deprecated(note = "use `join_norm`, which keeps the result normalized")]
+    pub fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.0.join(path)
+    }
+
+    /// Joins `path` onto `self`, resolving its `.` and `..` as they are appended so the result
+    /// stays normalized.
+    ///
+    /// Prefer this to [Path::join] through `Deref`, which returns a plain, possibly
+    /// non-normalized [PathBuf]. Unlike with [Path::join], `path` is a [RelPath] and so always
+    /// extends `self`; use [NormPath::try_join] for a path that has not been checked yet.
+    ///
+    /// ```
+    /// use dirge::{NormPathBuf, RelPath};
+    ///
+    /// let base = NormPathBuf::new("site/blog").unwrap();
+    /// let rel = RelPath::new("../static/./a.css").unwrap();
+    /// assert_eq!(base.join_norm(rel), NormPathBuf::new("site/static/a.css").unwrap());
+    /// ```
+    pub fn join_norm<P: AsRef<RelPath>>(&self, path: P) -> NormPathBuf {
+        self.join_normalized(path.as_ref())
+    }
+
+    fn join_normalized(&self, path: &Path) -> NormPathBuf {
+        let mut joined = if self.0.as_os_str() == "." {
+            PathBuf::new()
+        } else {
+            self.0.to_path_buf()
+        };
+        push_normalized(&mut joined, path);
+        if joined.as_os_str().is_empty() {
+            joined.push(".");
+        }
+        NormPathBuf::from_path_buf_unchecked(joined)
+    }
+
+    /// Like [NormPath::join_norm], for a `path` that is not yet known to be a [RelPath]. Fails
+    /// with [io::ErrorKind::InvalidInput] if it has a root or, on Windows, a prefix such as
+    /// `D:`, and under the `utf8-strict` feature if it is not valid UTF-8. `..` in `path` is
+    /// still resolved against `self`.
+    ///
+    /// ```
+    /// use dirge::NormPathBuf;
+    ///
+    /// let base = NormPathBuf::new("site/blog").unwrap();
+    /// assert_eq!(base.try_join("../static/./a.css").unwrap(), "site/static/a.css");
+    /// # #[cfg(unix)]
+    /// assert!(base.try_join("/etc").is_err());
+    /// ```
+    pub fn try_join<P: AsRef<Path>>(&self, path: P) -> io::Result<NormPathBuf> {
+        let path = path.as_ref();
+        crate::rel::check_joinable(path)?;
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(path)?;
+        Ok(self.join_normalized(path))
+    }
+
+    /// Returns `true` if the path is byte-for-byte what [normalize] produces for it.
+    ///
+    /// Always `true` for a correctly constructed `NormPath`; useful as a sanity check in tests
+    /// and for paths obtained from elsewhere.
+    pub fn is_canonical_form(&self) -> bool {
+        is_canonical_form(&self.0)
+    }
+
+    /// Converts the path to a [String], handling non-UTF-8 content according to `policy`.
+    pub fn to_string_policy(&self, policy: LossyPolicy) -> io::Result<String> {
+        crate::utf8::to_string_policy(&self.0, policy)
+    }
+
+    /// Returns an object that displays the path with non-UTF-8 bytes escaped as `\xNN`.
+    ///
+    /// See [LossyPolicy::Escape].
+    pub fn display_escaped(&self) -> EscapedDisplay<'_> {
+        EscapedDisplay(&self.0)
+    }
+
+    /// Views the path as a string, failing with the raw bytes if it is not valid UTF-8.
+    ///
+    /// Never allocates, and unlike [Path::to_string_lossy] never loses information; the result
+    /// converts back with [NormPathBuf::from_utf8_cow].
+    pub fn to_cow_str(&self) -> Result<Cow<'_, str>, NonUtf8Error> {
+        crate::utf8::to_cow_str(&self.0)
+    }
+}
+
+#[cfg(feature = "utf8-strict")]
+impl NormPath {
+    /// Returns the path as a string slice.
+    ///
+    /// Only available under the `utf8-strict` feature, which guarantees the path is valid UTF-8.
+    pub fn as_str(&self) -> &str {
+        crate::utf8::as_str(&self.0)
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -139,7 +602,7 @@ impl serde::Serialize for NormPathBuf {
     where
         S: serde::Serializer,
     {
-        self.0.serialize(serializer)
+        crate::win::serialize_path(&self.0, serializer)
     }
 }
 
@@ -150,8 +613,34 @@ impl<'de> serde::Deserialize<'de> for NormPathBuf {
         D: serde::Deserializer<'de>,
     {
         let path_buf = PathBuf::deserialize(deserializer)?;
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(&path_buf).map_err(serde::de::Error::custom)?;
         // Always normalize during deserialization
-        Ok(NormPathBuf(normalize_path(&path_buf)))
+        Ok(NormPathBuf::from_path_buf_unchecked(normalize(&path_buf)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl NormPathBuf {
+    /// Deserializes a path that must already be normalized, instead of normalizing it.
+    ///
+    /// The default [Deserialize](serde::Deserialize) impl quietly turns `a/../b` into `b`; use
+    /// this with `#[serde(deserialize_with = "NormPathBuf::deserialize_strict")]` to reject such
+    /// input, e.g. when reading manifests that a normalizing writer produced.
+    pub fn deserialize_strict<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::Deserialize;
+
+        let path_buf = PathBuf::deserialize(deserializer)?;
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(&path_buf).map_err(serde::de::Error::custom)?;
+        if is_canonical_form(&path_buf) {
+            Ok(NormPathBuf::from_path_buf_unchecked(path_buf))
+        } else {
+            Err(serde::de::Error::custom("path is not normalized"))
+        }
     }
 }
 
@@ -161,14 +650,38 @@ impl serde::Serialize for NormPath {
     where
         S: serde::Serializer,
     {
-        self.0.serialize(serializer)
+        crate::win::serialize_path(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for &'a NormPath
+where
+    'de: 'a,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let path = <&Path>::deserialize(deserializer)?;
+        NormPath::try_new(path).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Box<NormPath> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        NormPathBuf::deserialize(deserializer).map(Box::from)
     }
 }
 
 #[cfg(all(test, feature = "serde"))]
 mod serde_tests {
     use super::*;
-    use serde_test::{Token, assert_de_tokens, assert_tokens};
+    use serde_test::{Token, assert_de_tokens, assert_de_tokens_error, assert_tokens};
 
     #[test]
     fn test_norm_path_buf_serialize() {
@@ -188,6 +701,31 @@ mod serde_tests {
         assert_de_tokens(&expected, &[Token::Str("path/./to/../file.txt")]);
     }
 
+    #[derive(serde::Deserialize, PartialEq, Debug)]
+    struct Strict(#[serde(deserialize_with = "NormPathBuf::deserialize_strict")] NormPathBuf);
+
+    #[test]
+    fn test_norm_path_buf_deserialize_strict() {
+        let expected = Strict(NormPathBuf::new("path/file.txt").unwrap());
+        assert_de_tokens(
+            &expected,
+            &[
+                Token::NewtypeStruct { name: "Strict" },
+                Token::Str("path/file.txt"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_norm_path_buf_deserialize_strict_rejects() {
+        for input in ["path/../file.txt", "./file.txt", "path//file.txt", "path/"] {
+            assert_de_tokens_error::<Strict>(
+                &[Token::NewtypeStruct { name: "Strict" }, Token::Str(input)],
+                "path is not normalized",
+            );
+        }
+    }
+
     #[test]
     fn test_norm_path_serialize() {
         let path_buf = NormPathBuf::new("path/to/file.txt").unwrap();
@@ -195,4 +733,22 @@ mod serde_tests {
         use serde_test::{Token, assert_ser_tokens};
         assert_ser_tokens(&norm_path, &[Token::Str("path/to/file.txt")]);
     }
+
+    #[test]
+    fn test_norm_path_deserialize_borrowed() {
+        let path_buf = NormPathBuf::new("path/to/file.txt").unwrap();
+        let norm_path: &NormPath = &path_buf;
+        assert_tokens(&norm_path, &[Token::BorrowedStr("path/to/file.txt")]);
+        // A borrowed path cannot be normalized in place, so it must already be normal.
+        assert_de_tokens_error::<&NormPath>(
+            &[Token::BorrowedStr("path/../file.txt")],
+            "path is not normalized",
+        );
+    }
+
+    #[test]
+    fn test_boxed_norm_path_normalizes() {
+        let expected: Box<NormPath> = NormPathBuf::new("path/file.txt").unwrap().into();
+        assert_de_tokens(&expected, &[Token::Str("path/./file.txt")]);
+    }
 }
@@ -0,0 +1,13 @@
+//! Convenient glob import of dirge's types and conversion traits.
+//!
+//! ```
+//! use dirge::prelude::*;
+//!
+//! let rel = "src/lib.rs".to_rel_path_buf().unwrap();
+//! let norm = "a/./b".to_norm_path_buf();
+//! ```
+
+pub use crate::{
+    AbsPath, AbsPathBuf, AnyPath, NormPath, NormPathBuf, PathKind, RelPath, RelPathBuf,
+    ToAbsPathBuf, ToNormPathBuf, ToRelPathBuf,
+};
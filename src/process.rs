@@ -0,0 +1,147 @@
+//! Spawning processes with dirge's path types.
+//!
+//! ```no_run
+//! use dirge::{AbsPathBuf, RelPathBuf, process::{ArgStyle, CommandExt}};
+//! use std::process::Command;
+//!
+//! let repo = AbsPathBuf::new("/src/app").unwrap();
+//! let mut cmd = Command::new("git");
+//! cmd.current_dir_abs(&repo)
+//!     .arg("add")
+//!     .path_arg(RelPathBuf::new("-weird-name.txt").unwrap(), ArgStyle::Native);
+//! eprintln!("running {}", cmd.resolve_program().unwrap().display());
+//! cmd.status().unwrap();
+//! ```
+
+use std::{
+    ffi::{OsStr, OsString},
+    io,
+    path::{Component, Path, PathBuf},
+    process::Command,
+};
+
+use crate::{AbsPath, AbsPathBuf};
+
+/// How [CommandExt::path_arg] spells a path for the program receiving it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ArgStyle {
+    /// For native programs: the platform's own separator.
+    #[default]
+    Native,
+    /// For shell scripts and other tools that expect `/`, such as `sh` on Windows.
+    Script,
+}
+
+/// Extensions to [Command] that take dirge's path types.
+pub trait CommandExt {
+    /// Sets the working directory of the child, like [Command::current_dir].
+    fn current_dir_abs<P: AsRef<AbsPath>>(&mut self, dir: P) -> &mut Self;
+
+    /// Appends `path` as an argument, spelled for `style`.
+    ///
+    /// A relative path starting with `-` is prefixed with `./`, so the program cannot mistake
+    /// it for an option. Quoting is left to [Command], which already does it per platform.
+    fn path_arg<P: AsRef<Path>>(&mut self, path: P, style: ArgStyle) -> &mut Self;
+
+    /// The program that will run, as an absolute path, for logging.
+    ///
+    /// A program name containing a separator is resolved against the child's working directory;
+    /// a bare name is searched for in `PATH`, honouring a `PATH` set on the command. On Windows,
+    /// the extensions in `PATHEXT` are tried too. Fails with [io::ErrorKind::NotFound] naming
+    /// the program if nothing matches.
+    fn resolve_program(&self) -> io::Result<AbsPathBuf>;
+}
+
+impl CommandExt for Command {
+    fn current_dir_abs<P: AsRef<AbsPath>>(&mut self, dir: P) -> &mut Self {
+        self.current_dir(dir.as_ref())
+    }
+
+    fn path_arg<P: AsRef<Path>>(&mut self, path: P, style: ArgStyle) -> &mut Self {
+        self.arg(spell(path.as_ref(), style))
+    }
+
+    fn resolve_program(&self) -> io::Result<AbsPathBuf> {
+        let program = Path::new(self.get_program());
+        if program.components().count() > 1 || program.is_absolute() {
+            let base = match self.get_current_dir() {
+                Some(dir) => std::path::absolute(dir)?,
+                None => std::env::current_dir()?,
+            };
+            return AbsPathBuf::new(base.join(program));
+        }
+
+        let path = self
+            .get_envs()
+            .find(|(k, _)| *k == "PATH")
+            .map(|(_, v)| v.map(OsStr::to_owned))
+            .unwrap_or_else(|| std::env::var_os("PATH"))
+            .unwrap_or_default();
+        for dir in std::env::split_paths(&path).filter(|d| d.is_absolute()) {
+            for candidate in candidates(&dir, program.as_os_str()) {
+                if is_executable(&candidate) {
+                    return AbsPathBuf::new(candidate);
+                }
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{}: program not found in PATH", program.display()),
+        ))
+    }
+}
+
+fn spell(path: &Path, style: ArgStyle) -> OsString {
+    let mut out = OsString::new();
+    if let Some(Component::Normal(first)) = path.components().next()
+        && first.as_encoded_bytes().starts_with(b"-")
+    {
+        out.push(".");
+        out.push(separator(style));
+    }
+
+    // Backslashes are ordinary file name characters outside Windows, so only rewrite there,
+    // and only paths that are valid UTF-8.
+    match path.to_str() {
+        Some(s) if cfg!(windows) => out.push(s.replace(['/', '\\'], separator(style))),
+        _ => out.push(path.as_os_str()),
+    }
+    out
+}
+
+fn separator(style: ArgStyle) -> &'static str {
+    match style {
+        ArgStyle::Native => std::path::MAIN_SEPARATOR_STR,
+        ArgStyle::Script => "/",
+    }
+}
+
+#[cfg(windows)]
+pub(crate) fn candidates(dir: &Path, program: &OsStr) -> Vec<PathBuf> {
+    let mut out = vec![dir.join(program)];
+    if Path::new(program).extension().is_none() {
+        let exts = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".into());
+        for ext in exts.split(';').filter(|e| !e.is_empty()) {
+            let mut name = program.to_owned();
+            name.push(ext);
+            out.push(dir.join(name));
+        }
+    }
+    out
+}
+
+#[cfg(not(windows))]
+pub(crate) fn candidates(dir: &Path, program: &OsStr) -> Vec<PathBuf> {
+    vec![dir.join(program)]
+}
+
+#[cfg(unix)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
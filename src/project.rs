@@ -0,0 +1,101 @@
+//! Locating the root directory of a project.
+
+use std::{fmt, ops::Deref, path::Path};
+
+use crate::{AbsPath, AbsPathBuf};
+
+/// Something whose presence in a directory identifies it as a project root.
+pub enum Marker<'a> {
+    /// A file with this name, e.g. `Cargo.toml`.
+    File(&'a str),
+    /// A directory with this name, e.g. `.git`.
+    Dir(&'a str),
+    /// An arbitrary check performed on each candidate directory.
+    Predicate(&'a dyn Fn(&AbsPath) -> bool),
+}
+
+impl Marker<'_> {
+    fn matches(&self, dir: &AbsPath) -> bool {
+        match self {
+            Marker::File(name) => dir.try_join(name).is_ok_and(|p| p.is_file()),
+            Marker::Dir(name) => dir.try_join(name).is_ok_and(|p| p.is_dir()),
+            Marker::Predicate(f) => f(dir),
+        }
+    }
+}
+
+impl fmt::Debug for Marker<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Marker::File(name) => f.debug_tuple("File").field(name).finish(),
+            Marker::Dir(name) => f.debug_tuple("Dir").field(name).finish(),
+            Marker::Predicate(_) => f.write_str("Predicate(..)"),
+        }
+    }
+}
+
+/// The root directory of a project, as found by [detect_root].
+#[derive(PartialEq, Eq, Clone)]
+pub struct ProjectRoot(AbsPathBuf);
+
+impl ProjectRoot {
+    pub fn into_abs_path_buf(self) -> AbsPathBuf {
+        self.0
+    }
+}
+
+impl fmt::Debug for ProjectRoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl Deref for ProjectRoot {
+    type Target = AbsPath;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<AbsPath> for ProjectRoot {
+    fn as_ref(&self) -> &AbsPath {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for ProjectRoot {
+    fn as_ref(&self) -> &Path {
+        self.0.as_ref()
+    }
+}
+
+/// Finds the nearest directory, starting at `start` and moving up through its ancestors, that
+/// contains one of `markers`.
+///
+/// Returns the root together with the marker that matched. Within one directory, earlier markers
+/// take precedence over later ones; see also [AbsPath::find_up].
+///
+/// ```no_run
+/// use dirge::{
+///     AbsPathBuf,
+///     project::{Marker, detect_root},
+/// };
+///
+/// let cwd = AbsPathBuf::new(".").unwrap();
+/// let markers = [Marker::File("Cargo.toml"), Marker::Dir(".git")];
+/// if let Some((root, marker)) = detect_root(&cwd, &markers) {
+///     println!("{root:?} identified by {marker:?}");
+/// }
+/// ```
+pub fn detect_root<'m, 'a, P: AsRef<AbsPath>>(
+    start: P,
+    markers: &'m [Marker<'a>],
+) -> Option<(ProjectRoot, &'m Marker<'a>)> {
+    start.as_ref().ancestors().find_map(|dir| {
+        markers
+            .iter()
+            .find(|marker| marker.matches(dir))
+            .map(|marker| (ProjectRoot(dir.to_owned()), marker))
+    })
+}
@@ -0,0 +1,158 @@
+//! Displaying paths without leaking who they belong to.
+//!
+//! ```
+//! use dirge::redact::RedactPolicy;
+//! use std::path::Path;
+//!
+//! let policy = RedactPolicy::new().home("/home/alice").collapse(true);
+//! assert_eq!(
+//!     policy.redact(Path::new("/home/alice/secret/x")).to_string(),
+//!     "~/…/x"
+//! );
+//!
+//! let policy = RedactPolicy::new().prefix("/home/alice/secret", "<redacted>");
+//! assert_eq!(
+//!     policy.redact(Path::new("/home/alice/secret/x")).to_string(),
+//!     "<redacted>/x"
+//! );
+//! ```
+
+use std::{
+    fmt,
+    path::{Component, MAIN_SEPARATOR, Path, PathBuf},
+};
+
+const USER_DIRS: [&str; 2] = ["home", "Users"];
+
+/// Which parts of a path [Redact] hides.
+///
+/// Policies are cheap to build once and reuse for every path that gets logged.
+#[derive(Debug, Clone, Default)]
+pub struct RedactPolicy {
+    prefixes: Vec<(PathBuf, String)>,
+    mask_users: bool,
+    collapse: bool,
+}
+
+impl RedactPolicy {
+    /// A policy that redacts nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shows `home` and everything below it relative to `~`.
+    pub fn home<P: AsRef<Path>>(self, home: P) -> Self {
+        self.prefix(home, "~")
+    }
+
+    /// Like [RedactPolicy::home], with the current user's home directory from `HOME`, or
+    /// `USERPROFILE` on Windows. Does nothing if neither is set.
+    pub fn home_from_env(self) -> Self {
+        let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+        match std::env::var_os(var) {
+            Some(home) if !home.is_empty() => self.home(home),
+            _ => self,
+        }
+    }
+
+    /// Replaces `prefix` with `replacement`. When several prefixes match, the longest wins.
+    pub fn prefix<P: AsRef<Path>, S: Into<String>>(mut self, prefix: P, replacement: S) -> Self {
+        self.prefixes
+            .push((prefix.as_ref().to_path_buf(), replacement.into()));
+        self
+    }
+
+    /// Shows the user name in `/home/<name>` and `/Users/<name>` as `<user>`, for paths that no
+    /// configured prefix matched.
+    pub fn mask_users(mut self, yes: bool) -> Self {
+        self.mask_users = yes;
+        self
+    }
+
+    /// After a redacted prefix, shows only the file name, with `…` standing in for the
+    /// directories in between.
+    pub fn collapse(mut self, yes: bool) -> Self {
+        self.collapse = yes;
+        self
+    }
+
+    /// Displays `path` according to this policy.
+    pub fn redact<'a>(&'a self, path: &'a Path) -> Redact<'a> {
+        Redact { path, policy: self }
+    }
+
+    fn longest_prefix<'a>(&'a self, path: &'a Path) -> Option<(&'a str, &'a Path)> {
+        self.prefixes
+            .iter()
+            .filter_map(|(prefix, replacement)| {
+                let rest = path.strip_prefix(prefix).ok()?;
+                Some((prefix.components().count(), replacement.as_str(), rest))
+            })
+            .max_by_key(|(len, ..)| *len)
+            .map(|(_, replacement, rest)| (replacement, rest))
+    }
+}
+
+/// Displays a path with the parts selected by a [RedactPolicy] masked.
+///
+/// Created by [RedactPolicy::redact]. Paths are written with the platform's separator.
+#[derive(Clone, Copy)]
+pub struct Redact<'a> {
+    path: &'a Path,
+    policy: &'a RedactPolicy,
+}
+
+impl Redact<'_> {
+    fn user_dir_len(&self) -> Option<usize> {
+        let mut components = self.path.components();
+        if !matches!(components.next(), Some(Component::RootDir))
+            && !matches!(components.next(), Some(Component::RootDir))
+        {
+            return None;
+        }
+        let dir = components.next()?.as_os_str();
+        USER_DIRS
+            .iter()
+            .any(|d| dir == *d)
+            .then(|| self.path.components().count() - components.count())
+    }
+}
+
+impl fmt::Display for Redact<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((replacement, rest)) = self.policy.longest_prefix(self.path) {
+            f.write_str(replacement)?;
+            let count = rest.components().count();
+            if self.policy.collapse && count > 1 {
+                write!(f, "{MAIN_SEPARATOR}…")?;
+                let name = rest.components().next_back().unwrap();
+                return write!(f, "{MAIN_SEPARATOR}{}", Path::new(&name).display());
+            }
+            for c in rest.components() {
+                write!(f, "{MAIN_SEPARATOR}{}", Path::new(&c).display())?;
+            }
+            return Ok(());
+        }
+
+        if self.policy.mask_users
+            && let Some(len) = self.user_dir_len()
+            && self.path.components().count() > len
+        {
+            let head: PathBuf = self.path.components().take(len).collect();
+            let tail: PathBuf = self.path.components().skip(len + 1).collect();
+            write!(f, "{}{MAIN_SEPARATOR}<user>", head.display())?;
+            if !tail.as_os_str().is_empty() {
+                write!(f, "{MAIN_SEPARATOR}{}", tail.display())?;
+            }
+            return Ok(());
+        }
+
+        write!(f, "{}", self.path.display())
+    }
+}
+
+impl fmt::Debug for Redact<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{self}\"")
+    }
+}
@@ -1,40 +1,356 @@
 use std::{
-    borrow::Borrow,
+    borrow::{Borrow, Cow},
+    ffi::OsStr,
     io,
     ops::Deref,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
 };
 
 use std::fmt;
 
 use ref_cast::RefCast;
 
+use crate::{AbsPath, AbsPathBuf, EscapedDisplay, LossyPolicy, NonUtf8Error, cast};
+
 /// Equivalent to [PathBuf], but guaranteed to be relative.
-#[derive(PartialEq, Eq, Clone, Hash)]
+///
+/// Relative here also rules out the Windows forms that [Path::is_relative] accepts but that
+/// [Path::join] lets replace the base: a root without a drive, such as `\x`, and a drive
+/// without a root, such as `D:x`. Joining a `RelPath` onto another path therefore always
+/// extends it.
+///
+/// Serialization always uses `/` as the separator, so a `RelPathBuf` (or a map keyed by one)
+/// serializes identically on every platform.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 #[repr(transparent)]
 pub struct RelPathBuf(PathBuf);
 
-/// Equivalent to [Path], but guaranteed to be relative.
-#[derive(RefCast, PartialEq, Eq)]
+/// Equivalent to [Path], but guaranteed to be relative, in the sense of [RelPathBuf].
+#[derive(RefCast, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct RelPath(Path);
 
+/// Iterator returned by [RelPath::ancestors].
+#[derive(Debug, Clone)]
+pub struct RelAncestors<'a>(std::path::Ancestors<'a>);
+
+impl<'a> Iterator for RelAncestors<'a> {
+    type Item = &'a RelPath;
+
+    fn next(&mut self) -> Option<&'a RelPath> {
+        self.0.next().map(RelPath::ref_cast)
+    }
+}
+
+impl std::iter::FusedIterator for RelAncestors<'_> {}
+
 impl fmt::Debug for RelPath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self.0, f)
+        crate::utf8::debug(&self.0, f)
     }
 }
 
 impl fmt::Debug for RelPathBuf {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self.0, f)
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Displays the path like [Path::display], replacing non-UTF-8 content with U+FFFD. Use
+/// [RelPath::display_escaped] to keep such bytes distinguishable.
+///
+/// For a path that is valid UTF-8, parsing the output with [FromStr](std::str::FromStr) gives
+/// back the same path, byte for byte, trailing separator included. Otherwise the output is
+/// lossy: it parses, if at all, to a different path.
+impl fmt::Display for RelPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0.display(), f)
+    }
+}
+
+impl fmt::Display for RelPathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+/// Parses with the same rules as [RelPathBuf::new]. The inverse of `Display` for paths that
+/// are valid UTF-8.
+impl std::str::FromStr for RelPathBuf {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        RelPathBuf::new(s)
+    }
+}
+
+/// Checks the path like [RelPathBuf::new], reusing the buffer.
+impl TryFrom<PathBuf> for RelPathBuf {
+    type Error = io::Error;
+
+    fn try_from(p: PathBuf) -> io::Result<Self> {
+        RelPath::new(&p)?;
+        Ok(RelPathBuf(p))
+    }
+}
+
+impl TryFrom<&Path> for RelPathBuf {
+    type Error = io::Error;
+
+    fn try_from(p: &Path) -> io::Result<Self> {
+        RelPathBuf::new(p)
+    }
+}
+
+impl TryFrom<String> for RelPathBuf {
+    type Error = io::Error;
+
+    fn try_from(s: String) -> io::Result<Self> {
+        RelPathBuf::try_from(PathBuf::from(s))
+    }
+}
+
+impl TryFrom<std::ffi::OsString> for RelPathBuf {
+    type Error = io::Error;
+
+    fn try_from(s: std::ffi::OsString) -> io::Result<Self> {
+        RelPathBuf::try_from(PathBuf::from(s))
     }
 }
 
 impl RelPathBuf {
+    /// Wraps `p` if it is relative.
+    ///
+    /// The empty path counts as relative and is accepted; use [RelPathBuf::new_non_empty] where
+    /// an empty path would be a mistake.
     pub fn new<P: AsRef<Path>>(p: P) -> io::Result<Self> {
         p.as_ref().to_rel_path_buf()
     }
+
+    /// Like [RelPathBuf::new], but also rejects the empty path with
+    /// [io::ErrorKind::InvalidInput].
+    ///
+    /// ```
+    /// use dirge::RelPathBuf;
+    ///
+    /// assert!(RelPathBuf::new("").is_ok());
+    /// assert!(RelPathBuf::new_non_empty("").is_err());
+    /// assert!(RelPathBuf::new_non_empty(".").is_ok());
+    /// ```
+    pub fn new_non_empty<P: AsRef<Path>>(p: P) -> io::Result<Self> {
+        let path = p.as_ref();
+        if path.as_os_str().is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "path is empty"));
+        }
+        path.to_rel_path_buf()
+    }
+
+    /// The empty relative path, for the cases that really want it. Joining onto it is a no-op.
+    pub fn empty() -> Self {
+        RelPathBuf(PathBuf::new())
+    }
+
+    /// Replaces the component at `index` with the file name `name`.
+    ///
+    /// Indices count [Path::components], so `.` and `..` occupy a slot. `name` must be a
+    /// single normal component: not empty, `.` or `..`, and without separators. Errors with
+    /// [io::ErrorKind::InvalidInput] leave the path unchanged.
+    ///
+    /// ```
+    /// use dirge::RelPathBuf;
+    ///
+    /// let mut rel = RelPathBuf::new("src/old_mod/lib.rs").unwrap();
+    /// rel.replace_segment(1, "new_mod").unwrap();
+    /// assert_eq!(rel, RelPathBuf::new("src/new_mod/lib.rs").unwrap());
+    /// ```
+    pub fn replace_segment<S: AsRef<OsStr>>(&mut self, index: usize, name: S) -> io::Result<()> {
+        let name = check_segment(name.as_ref())?;
+        self.edit_segments(index, false, |out, old| {
+            out.push(name);
+            old.is_some()
+        })
+    }
+
+    /// Inserts the file name `name` so it becomes the component at `index`, which may equal the
+    /// number of components to append. See [RelPathBuf::replace_segment] for the rules.
+    pub fn insert_segment<S: AsRef<OsStr>>(&mut self, index: usize, name: S) -> io::Result<()> {
+        let name = check_segment(name.as_ref())?;
+        self.edit_segments(index, true, |out, old| {
+            out.push(name);
+            if let Some(old) = old {
+                out.push(old);
+            }
+            true
+        })
+    }
+
+    /// Removes the component at `index`.
+    pub fn remove_segment(&mut self, index: usize) -> io::Result<()> {
+        self.edit_segments(index, false, |_, old| old.is_some())
+    }
+
+    /// Rebuilds the path in one pass, letting `edit` write the component at `index` (`None`
+    /// past the end, when `allow_end` permits it). `edit` returns whether `index` was valid.
+    fn edit_segments<F>(&mut self, index: usize, allow_end: bool, edit: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut PathBuf, Option<&OsStr>) -> bool,
+    {
+        let mut out = PathBuf::with_capacity(self.0.as_os_str().len());
+        let mut edit = Some(edit);
+        let mut count = 0;
+        for (i, c) in self.0.components().enumerate() {
+            if i == index {
+                edit.take().unwrap()(&mut out, Some(c.as_os_str()));
+            } else {
+                out.push(c);
+            }
+            count = i + 1;
+        }
+
+        let valid = match edit {
+            None => true,
+            Some(edit) => allow_end && index == count && edit(&mut out, None),
+        };
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("segment index {index} out of range for a path of {count} components"),
+            ));
+        }
+        self.0 = out;
+        Ok(())
+    }
+
+    /// Wraps `p` without checking the invariant; callers must have established it.
+    pub(crate) fn from_path_buf_unchecked(p: PathBuf) -> Self {
+        RelPathBuf(p)
+    }
+
+    /// Parses the textual form produced by [RelPath::to_portable_string].
+    ///
+    /// Anything that is not exactly in that form is rejected with [io::ErrorKind::InvalidInput],
+    /// including empty, `.` and `..` segments, leading, trailing or doubled `/`, `\`, and text
+    /// that is not in Unicode Normalization Form C.
+    pub fn from_portable_string(s: &str) -> io::Result<Self> {
+        if s == "." {
+            return Ok(RelPathBuf(PathBuf::from(".")));
+        }
+        if !crate::nfc::is_nfc(s) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{s:?} is not in Unicode NFC"),
+            ));
+        }
+        let mut path = PathBuf::new();
+        for segment in s.split('/') {
+            check_portable_segment(segment)?;
+            path.push(segment);
+        }
+        Ok(RelPathBuf(path))
+    }
+
+    /// Converts the path component of an HTTP request URL into a path to look up under a
+    /// document root.
+    ///
+    /// `raw` must not include a query or fragment. Each `/`-separated segment is
+    /// percent-decoded, then `.` and `..` are resolved. Fails with [io::ErrorKind::InvalidInput]
+    /// on malformed escapes, NUL, encoded separators such as `%2F`, text that is not UTF-8,
+    /// and any `..` that would climb above the root. An empty result is `.`.
+    ///
+    /// ```
+    /// use dirge::RelPathBuf;
+    ///
+    /// let rel = RelPathBuf::from_url_path("/docs/My%20File.txt").unwrap();
+    /// assert_eq!(rel, RelPathBuf::new("docs/My File.txt").unwrap());
+    /// assert!(RelPathBuf::from_url_path("/static/%2e%2e/%2e%2e/etc/passwd").is_err());
+    /// ```
+    pub fn from_url_path(raw: &str) -> io::Result<Self> {
+        let mut path = PathBuf::new();
+        for raw_segment in raw.split('/') {
+            let segment = percent_decode(raw_segment)?;
+            match segment.as_str() {
+                "" | "." => {}
+                ".." => {
+                    if !path.pop() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "URL path escapes its root",
+                        ));
+                    }
+                }
+                _ => {
+                    check_portable_segment(&segment)?;
+                    path.push(segment);
+                }
+            }
+        }
+
+        if path.as_os_str().is_empty() {
+            path.push(".");
+        }
+        Ok(RelPathBuf(path))
+    }
+}
+
+/// Decodes `%XX` escapes in one URL path segment, rejecting anything that would let the
+/// decoded text act as more than a single name.
+fn percent_decode(segment: &str) -> io::Result<String> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, msg.to_owned());
+
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut rest = segment.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+                .ok_or_else(|| invalid("malformed percent escape in URL path"))?;
+            bytes.push(hex);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+
+    if bytes.iter().any(|b| matches!(b, b'/' | b'\\' | b'\0')) {
+        return Err(invalid("URL path contains an encoded separator or NUL"));
+    }
+    String::from_utf8(bytes).map_err(|_| invalid("URL path is not valid UTF-8"))
+}
+
+/// Checks that `name` is a single normal path component.
+pub(crate) fn check_segment(name: &OsStr) -> io::Result<&OsStr> {
+    let mut components = Path::new(name).components();
+    let single = matches!(components.next(), Some(Component::Normal(c)) if c == name)
+        && components.next().is_none();
+    if !single {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{name:?} is not a single file name"),
+        ));
+    }
+    #[cfg(feature = "utf8-strict")]
+    crate::utf8::check(Path::new(name))?;
+    Ok(name)
+}
+
+/// Accepts `segment` only if it is a single, ordinary path component on this platform.
+pub(crate) fn check_portable_segment(segment: &str) -> io::Result<()> {
+    let mut components = Path::new(segment).components();
+    let single = matches!(components.next(), Some(Component::Normal(c)) if c == segment)
+        && components.next().is_none();
+    if single && !segment.contains(['\\', '\0']) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{segment:?} is not a portable path segment"),
+        ))
+    }
 }
 
 impl AsRef<Path> for RelPathBuf {
@@ -49,6 +365,60 @@ impl AsRef<Path> for RelPath {
     }
 }
 
+impl AsRef<OsStr> for RelPathBuf {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl AsRef<OsStr> for RelPath {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl AsRef<RelPath> for RelPathBuf {
+    fn as_ref(&self) -> &RelPath {
+        self
+    }
+}
+
+impl AsRef<RelPath> for RelPath {
+    fn as_ref(&self) -> &RelPath {
+        self
+    }
+}
+
+impl From<&RelPath> for Arc<RelPath> {
+    fn from(p: &RelPath) -> Self {
+        RelPath::from_arc_unchecked(Arc::from(&p.0))
+    }
+}
+
+impl From<RelPathBuf> for Arc<RelPath> {
+    fn from(p: RelPathBuf) -> Self {
+        Arc::from(&*p)
+    }
+}
+
+impl From<RelPathBuf> for PathBuf {
+    fn from(p: RelPathBuf) -> Self {
+        p.0
+    }
+}
+
+impl From<RelPathBuf> for Box<RelPath> {
+    fn from(p: RelPathBuf) -> Self {
+        RelPath::from_box_unchecked(p.0.into_boxed_path())
+    }
+}
+
+impl From<&RelPath> for Box<RelPath> {
+    fn from(p: &RelPath) -> Self {
+        Box::from(p.to_owned())
+    }
+}
+
 pub trait ToRelPathBuf: AsRef<Path> {
     fn to_rel_path_buf(&self) -> io::Result<RelPathBuf>;
 }
@@ -56,7 +426,9 @@ pub trait ToRelPathBuf: AsRef<Path> {
 impl<P: AsRef<Path>> ToRelPathBuf for P {
     fn to_rel_path_buf(&self) -> io::Result<RelPathBuf> {
         let path = self.as_ref();
-        if path.is_relative() {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(path)?;
+        if is_joinable(path) {
             Ok(RelPathBuf(path.to_path_buf()))
         } else {
             Err(io::Error::new(
@@ -67,6 +439,67 @@ impl<P: AsRef<Path>> ToRelPathBuf for P {
     }
 }
 
+/// Rejects a `path` that [Path::join] would let replace the base instead of extending it: one
+/// with a root, or on Windows a prefix such as `C:`. Exactly the paths a [RelPath] may hold pass.
+pub(crate) fn check_joinable(path: &Path) -> io::Result<()> {
+    if is_joinable(path) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{}: cannot join a path that is not relative",
+                path.display()
+            ),
+        ))
+    }
+}
+
+pub(crate) fn is_joinable(path: &Path) -> bool {
+    !path.has_root() && !matches!(path.components().next(), Some(Component::Prefix(_)))
+}
+
+/// Returns `true` if resolving `..` lexically climbs above the start of `path`.
+pub(crate) fn escapes_root(path: &Path) -> bool {
+    resolve(path).contains(&Component::ParentDir)
+}
+
+/// Iterator returned by [RelPath::breadcrumbs].
+pub struct Breadcrumbs<'a> {
+    components: std::path::Components<'a>,
+    accumulated: PathBuf,
+}
+
+impl<'a> Iterator for Breadcrumbs<'a> {
+    type Item = (RelPathBuf, &'a OsStr);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let name = loop {
+            match self.components.next()? {
+                Component::CurDir => continue,
+                c => break c.as_os_str(),
+            }
+        };
+        self.accumulated.push(name);
+        Some((RelPathBuf(self.accumulated.clone()), name))
+    }
+}
+
+/// Resolves `.` and `..` lexically, keeping any `..` that climb above the start.
+pub(crate) fn resolve(path: &Path) -> Vec<Component<'_>> {
+    let mut out = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(out.last(), Some(Component::Normal(_))) => {
+                out.pop();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 impl Deref for RelPath {
     type Target = Path;
 
@@ -105,6 +538,315 @@ impl RelPathBuf {
     pub fn capacity(&self) -> usize {
         self.0.capacity()
     }
+
+    /// Consumes the path, returning the inner [PathBuf] without copying it.
+    pub fn into_path_buf(self) -> PathBuf {
+        self.0
+    }
+
+    /// Like [PathBuf::set_extension]; the path stays relative.
+    ///
+    /// Panics under the `utf8-strict` feature if `extension` is not valid UTF-8.
+    pub fn set_extension<S: AsRef<OsStr>>(&mut self, extension: S) -> bool {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::assert(Path::new(&extension));
+        self.0.set_extension(extension)
+    }
+
+    /// Like [PathBuf::set_file_name], validating `file_name` as [RelPath::with_file_name] does
+    /// and leaving the path unchanged if it is rejected.
+    pub fn set_file_name<S: AsRef<OsStr>>(&mut self, file_name: S) -> io::Result<()> {
+        self.0.set_file_name(check_segment(file_name.as_ref())?);
+        Ok(())
+    }
+
+    /// Like [RelPathBuf::new], taking over the string's buffer when it is owned.
+    pub fn from_utf8_cow(s: Cow<'_, str>) -> io::Result<Self> {
+        match s {
+            Cow::Borrowed(s) => RelPathBuf::new(s),
+            Cow::Owned(s) => RelPathBuf::try_from(s),
+        }
+    }
+
+    /// Views a slice of paths as [PathBuf]s, without copying.
+    pub fn cast_slice(paths: &[RelPathBuf]) -> &[PathBuf] {
+        cast::inner_slice(paths)
+    }
+
+    /// Converts a vector of paths into [PathBuf]s, reusing its allocation.
+    pub fn into_path_buf_vec(paths: Vec<RelPathBuf>) -> Vec<PathBuf> {
+        cast::into_inner_vec(paths)
+    }
+
+    /// Views a slice of [PathBuf]s as this type, without copying, if every path satisfies its
+    /// invariant. Otherwise fails with the error for the first path that does not.
+    pub fn try_cast_slice(paths: &[PathBuf]) -> io::Result<&[RelPathBuf]> {
+        for path in paths {
+            RelPath::new(path)?;
+        }
+        Ok(cast::slice_unchecked(paths))
+    }
+
+    /// Converts a vector of [PathBuf]s into this type, reusing its allocation, if every path
+    /// satisfies its invariant. Otherwise fails with the error for the first path that does not.
+    pub fn try_from_path_buf_vec(paths: Vec<PathBuf>) -> io::Result<Vec<RelPathBuf>> {
+        for path in &paths {
+            RelPath::new(path)?;
+        }
+        Ok(cast::vec_unchecked(paths))
+    }
+}
+
+impl RelPath {
+    /// Views `p` as a [RelPath] without copying it, if it is relative.
+    ///
+    /// ```
+    /// use dirge::RelPath;
+    /// use std::path::Path;
+    ///
+    /// let stored = Path::new("src/main.rs");
+    /// let rel: &RelPath = RelPath::new(stored).unwrap();
+    /// assert_eq!(rel.file_name().unwrap(), "main.rs");
+    /// # #[cfg(unix)]
+    /// assert!(RelPath::new("/etc/hosts").is_err());
+    /// ```
+    pub fn new<P: AsRef<Path> + ?Sized>(p: &P) -> io::Result<&RelPath> {
+        let path = p.as_ref();
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(path)?;
+        if is_joinable(path) {
+            Ok(RelPath::ref_cast(path))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path must be relative",
+            ))
+        }
+    }
+
+    /// Views `p` as a [RelPath] without checking the invariant; callers must have established it.
+    pub(crate) fn from_path_unchecked(p: &Path) -> &RelPath {
+        RelPath::ref_cast(p)
+    }
+
+    /// Returns `true` for the empty path, which is distinct from `.`.
+    pub fn is_empty(&self) -> bool {
+        self.0.as_os_str().is_empty()
+    }
+
+    /// Creates an owned [RelPathBuf] with `path` adjoined to `self`.
+    ///
+    /// `path` is itself a [RelPath], so it always extends `self` and the result is relative.
+    /// Use [RelPath::try_join] to join a path that has not been checked yet.
+    pub fn join<P: AsRef<RelPath>>(&self, path: P) -> RelPathBuf {
+        RelPathBuf(self.0.join(&path.as_ref().0))
+    }
+
+    /// Like [RelPath::join], for a `path` that is not yet known to be a [RelPath]. Fails with
+    /// [io::ErrorKind::InvalidInput] if it has a root or, on Windows, a prefix such as `D:`, and
+    /// under the `utf8-strict` feature if it is not valid UTF-8.
+    ///
+    /// `..` components are kept, so the result can still climb above `self`.
+    pub fn try_join<P: AsRef<Path>>(&self, path: P) -> io::Result<RelPathBuf> {
+        let path = path.as_ref();
+        check_joinable(path)?;
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(path)?;
+        Ok(RelPathBuf(self.0.join(path)))
+    }
+
+    /// Like [Path::parent]; the parent of a relative path is relative.
+    pub fn parent(&self) -> Option<&RelPath> {
+        self.0.parent().map(RelPath::ref_cast)
+    }
+
+    /// Like [Path::ancestors]: `self`, then each parent in turn. Every ancestor of a relative
+    /// path is relative.
+    pub fn ancestors(&self) -> RelAncestors<'_> {
+        RelAncestors(self.0.ancestors())
+    }
+
+    /// Like [Path::with_file_name], but fails with [io::ErrorKind::InvalidInput] unless
+    /// `file_name` is a single normal component: not empty, `.` or `..`, and without separators.
+    /// Under the `utf8-strict` feature it must also be valid UTF-8.
+    pub fn with_file_name<S: AsRef<OsStr>>(&self, file_name: S) -> io::Result<RelPathBuf> {
+        let file_name = check_segment(file_name.as_ref())?;
+        Ok(RelPathBuf(self.0.with_file_name(file_name)))
+    }
+
+    /// Like [Path::with_extension].
+    ///
+    /// Panics under the `utf8-strict` feature if `extension` is not valid UTF-8.
+    pub fn with_extension<S: AsRef<OsStr>>(&self, extension: S) -> RelPathBuf {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::assert(Path::new(&extension));
+        RelPathBuf(self.0.with_extension(extension))
+    }
+
+    /// Like [Path::canonicalize], which resolves the path against the current directory.
+    pub fn canonicalize(&self) -> io::Result<AbsPathBuf> {
+        AbsPath::from_path_unchecked(&std::path::absolute(&self.0)?).canonicalize()
+    }
+
+    /// Computes the link from the file at `self` to the file at `target`, both relative to the
+    /// same root.
+    ///
+    /// The result is relative to the directory containing `self` and always uses `/` as its
+    /// separator, so it is suitable for HTML `href`s. Fails with [io::ErrorKind::InvalidInput]
+    /// if the directory containing `self` lies outside the root, since the way back in cannot be
+    /// known lexically.
+    ///
+    /// ```
+    /// use dirge::RelPathBuf;
+    ///
+    /// let page = RelPathBuf::new("blog/2024/post.html").unwrap();
+    /// let style = RelPathBuf::new("static/site.css").unwrap();
+    /// assert_eq!(page.link_to(&style).unwrap().to_str(), Some("../../static/site.css"));
+    /// ```
+    pub fn link_to<P: AsRef<RelPath>>(&self, target: P) -> io::Result<RelPathBuf> {
+        let mut from = resolve(&self.0);
+        from.pop();
+        let to = resolve(&target.as_ref().0);
+
+        if from.contains(&Component::ParentDir) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot link from a directory outside the root",
+            ));
+        }
+
+        let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+        let segments: Vec<&OsStr> = std::iter::repeat_n(OsStr::new(".."), from.len() - common)
+            .chain(to[common..].iter().map(|c| c.as_os_str()))
+            .collect();
+
+        if segments.is_empty() {
+            return Ok(RelPathBuf(PathBuf::from(".")));
+        }
+        Ok(RelPathBuf(PathBuf::from(segments.join(OsStr::new("/")))))
+    }
+
+    /// Replaces the leading `from_prefix` of the path with `to_prefix`.
+    ///
+    /// Prefixes are matched component-wise, so `src` is a prefix of `src/foo.rs` but not of
+    /// `srcs/foo.rs`. Fails with [io::ErrorKind::InvalidInput] if the path does not start with
+    /// `from_prefix`.
+    ///
+    /// ```
+    /// use dirge::RelPathBuf;
+    ///
+    /// let src = RelPathBuf::new("src/foo.rs").unwrap();
+    /// let from = RelPathBuf::new("src").unwrap();
+    /// let to = RelPathBuf::new("out").unwrap();
+    /// assert_eq!(src.rebase(&from, &to).unwrap(), RelPathBuf::new("out/foo.rs").unwrap());
+    /// ```
+    pub fn rebase<P, Q>(&self, from_prefix: P, to_prefix: Q) -> io::Result<RelPathBuf>
+    where
+        P: AsRef<RelPath>,
+        Q: AsRef<RelPath>,
+    {
+        let rest = self.0.strip_prefix(&from_prefix.as_ref().0).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path does not start with the given prefix",
+            )
+        })?;
+        Ok(RelPathBuf(to_prefix.as_ref().0.join(rest)))
+    }
+
+    /// Returns the path in dirge's portable textual form, for lockfiles and caches shared
+    /// between machines.
+    ///
+    /// The portable form is the lexically normalized path with its components joined by `/`,
+    /// or `.` for an empty path, in Unicode Normalization Form C. It contains no `.` or `..`
+    /// segments and no `\`, and a name spelled with combining accents on one machine and
+    /// precomposed on another gives the same string, so it is the same on every platform and
+    /// [RelPathBuf::from_portable_string] reads it back. Fails with
+    /// [io::ErrorKind::InvalidInput] if the path is not valid UTF-8, escapes its root, or has a
+    /// component containing `\`.
+    ///
+    /// ```
+    /// use dirge::RelPathBuf;
+    ///
+    /// let rel = RelPathBuf::new("src/./bin/../lib.rs").unwrap();
+    /// assert_eq!(rel.to_portable_string().unwrap(), "src/lib.rs");
+    /// ```
+    pub fn to_portable_string(&self) -> io::Result<String> {
+        let mut segments = Vec::new();
+        for component in resolve(&self.0) {
+            let segment = match component {
+                Component::ParentDir => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "path escapes its root",
+                    ));
+                }
+                c => c.as_os_str().to_str().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "path is not valid UTF-8")
+                })?,
+            };
+            check_portable_segment(segment)?;
+            segments.push(crate::nfc::to_nfc(segment));
+        }
+
+        if segments.is_empty() {
+            return Ok(".".to_owned());
+        }
+        Ok(segments.join("/"))
+    }
+
+    /// Iterates over each prefix of the path together with its last component.
+    ///
+    /// Yields `(a, "a")`, `(a/b, "b")`, ... which is what a clickable breadcrumb trail needs.
+    /// Each prefix is built by extending the previous one rather than re-joining from scratch.
+    ///
+    /// ```
+    /// use dirge::RelPathBuf;
+    ///
+    /// let rel = RelPathBuf::new("docs/guide/intro.md").unwrap();
+    /// let crumbs: Vec<_> = rel
+    ///     .breadcrumbs()
+    ///     .map(|(prefix, name)| (prefix.to_string_lossy().into_owned(), name.to_owned()))
+    ///     .collect();
+    /// assert_eq!(crumbs[1].0, "docs/guide");
+    /// assert_eq!(crumbs[2].1, "intro.md");
+    /// ```
+    pub fn breadcrumbs(&self) -> Breadcrumbs<'_> {
+        Breadcrumbs {
+            components: self.0.components(),
+            accumulated: PathBuf::new(),
+        }
+    }
+
+    /// Converts the path to a [String], handling non-UTF-8 content according to `policy`.
+    pub fn to_string_policy(&self, policy: LossyPolicy) -> io::Result<String> {
+        crate::utf8::to_string_policy(&self.0, policy)
+    }
+
+    /// Returns an object that displays the path with non-UTF-8 bytes escaped as `\xNN`.
+    ///
+    /// See [LossyPolicy::Escape].
+    pub fn display_escaped(&self) -> EscapedDisplay<'_> {
+        EscapedDisplay(&self.0)
+    }
+
+    /// Views the path as a string, failing with the raw bytes if it is not valid UTF-8.
+    ///
+    /// Never allocates, and unlike [Path::to_string_lossy] never loses information; the result
+    /// converts back with [RelPathBuf::from_utf8_cow].
+    pub fn to_cow_str(&self) -> Result<Cow<'_, str>, NonUtf8Error> {
+        crate::utf8::to_cow_str(&self.0)
+    }
+}
+
+#[cfg(feature = "utf8-strict")]
+impl RelPath {
+    /// Returns the path as a string slice.
+    ///
+    /// Only available under the `utf8-strict` feature, which guarantees the path is valid UTF-8.
+    pub fn as_str(&self) -> &str {
+        crate::utf8::as_str(&self.0)
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -113,7 +855,7 @@ impl serde::Serialize for RelPathBuf {
     where
         S: serde::Serializer,
     {
-        self.0.serialize(serializer)
+        (**self).serialize(serializer)
     }
 }
 
@@ -124,7 +866,9 @@ impl<'de> serde::Deserialize<'de> for RelPathBuf {
         D: serde::Deserializer<'de>,
     {
         let path_buf = PathBuf::deserialize(deserializer)?;
-        if path_buf.is_relative() {
+        #[cfg(feature = "utf8-strict")]
+        crate::utf8::check(&path_buf).map_err(serde::de::Error::custom)?;
+        if is_joinable(&path_buf) {
             Ok(RelPathBuf(path_buf))
         } else {
             Err(serde::de::Error::custom("path must be relative"))
@@ -138,7 +882,40 @@ impl serde::Serialize for RelPath {
     where
         S: serde::Serializer,
     {
-        self.0.serialize(serializer)
+        // Always use `/`, so the output doesn't depend on the platform that wrote it.
+        match self.0.to_str() {
+            #[cfg(windows)]
+            Some(s) => serializer.serialize_str(&s.replace('\\', "/")),
+            #[cfg(not(windows))]
+            Some(s) => serializer.serialize_str(s),
+            None => Err(serde::ser::Error::custom(
+                "path contains invalid UTF-8 characters",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for &'a RelPath
+where
+    'de: 'a,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let path = <&Path>::deserialize(deserializer)?;
+        RelPath::new(path).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Box<RelPath> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        RelPathBuf::deserialize(deserializer).map(Box::from)
     }
 }
 
@@ -174,4 +951,59 @@ mod serde_tests {
         use serde_test::{Token, assert_ser_tokens};
         assert_ser_tokens(&rel_path, &[Token::Str("relative/path.txt")]);
     }
+
+    #[test]
+    fn test_rel_path_deserialize_borrowed() {
+        let path_buf = RelPathBuf::new("relative/path.txt").unwrap();
+        let rel_path: &RelPath = &path_buf;
+        assert_tokens(&rel_path, &[Token::BorrowedStr("relative/path.txt")]);
+        assert_de_tokens_error::<&RelPath>(
+            &[Token::BorrowedStr("/absolute/path")],
+            "path must be relative",
+        );
+    }
+
+    #[test]
+    fn test_boxed_rel_path_map_keys() {
+        use std::collections::HashMap;
+
+        let key: Box<RelPath> = RelPathBuf::new("src/lib.rs").unwrap().into();
+        let map = HashMap::from([(key, 1u32)]);
+        assert_tokens(
+            &map,
+            &[
+                Token::Map { len: Some(1) },
+                Token::Str("src/lib.rs"),
+                Token::U32(1),
+                Token::MapEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_rel_path_buf_map_keys_are_portable() {
+        use std::collections::BTreeMap;
+
+        // Built from components so the native separator is used on each platform.
+        let key = |parts: &[&str]| RelPathBuf::new(parts.iter().collect::<PathBuf>()).unwrap();
+        let map: BTreeMap<RelPathBuf, u32> =
+            [(key(&["b"]), 3), (key(&["a-b"]), 2), (key(&["a", "b"]), 1)]
+                .into_iter()
+                .collect();
+
+        // Component order: `a/b` sorts before `a-b`, unlike a plain string comparison.
+        assert_tokens(
+            &map,
+            &[
+                Token::Map { len: Some(3) },
+                Token::Str("a/b"),
+                Token::U32(1),
+                Token::Str("a-b"),
+                Token::U32(2),
+                Token::Str("b"),
+                Token::U32(3),
+                Token::MapEnd,
+            ],
+        );
+    }
 }
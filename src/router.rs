@@ -0,0 +1,220 @@
+//! Matching relative request paths against route patterns, without traversal surprises.
+//!
+//! Patterns are `/`-separated relative paths whose segments are literals, `{name}` (exactly one
+//! segment) or, as the last segment, `{*name}` (one or more segments). Request paths are
+//! resolved lexically before matching, and any path that climbs above the root matches nothing,
+//! so a handler can safely join a capture onto its document root.
+//!
+//! ```
+//! use dirge::{RelPathBuf, router::PathRouter};
+//!
+//! let mut router = PathRouter::new();
+//! router.insert("users/{id}/avatar", "avatar").unwrap();
+//! router.insert("static/{*rest}", "static").unwrap();
+//!
+//! let (route, captures) = router.at(RelPathBuf::new("users/42/avatar").unwrap()).unwrap();
+//! assert_eq!((*route, captures.get("id")), ("avatar", Some("42")));
+//!
+//! let (_, captures) = router.at(RelPathBuf::new("static/css/../js/app.js").unwrap()).unwrap();
+//! assert_eq!(captures.get("rest"), Some("js/app.js"));
+//!
+//! assert!(router.at(RelPathBuf::new("static/../../etc/passwd").unwrap()).is_none());
+//! ```
+
+use std::{
+    fmt, io,
+    path::{Component, PathBuf},
+};
+
+use crate::{RelPath, RelPathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Param(String),
+    Rest(String),
+}
+
+impl Segment {
+    /// Lower is more specific.
+    fn rank(&self) -> u8 {
+        match self {
+            Segment::Literal(_) => 0,
+            Segment::Param(_) => 1,
+            Segment::Rest(_) => 2,
+        }
+    }
+
+    /// Whether two segments match the same inputs, regardless of capture names.
+    fn same_shape(&self, other: &Segment) -> bool {
+        match (self, other) {
+            (Segment::Literal(a), Segment::Literal(b)) => a == b,
+            (a, b) => a.rank() == b.rank(),
+        }
+    }
+}
+
+/// Maps route patterns to values of type `V`.
+///
+/// When several patterns match, the most specific wins: at the first segment where they
+/// differ, a literal beats `{name}`, which beats `{*name}`.
+pub struct PathRouter<V> {
+    routes: Vec<(Vec<Segment>, V)>,
+}
+
+impl<V> Default for PathRouter<V> {
+    fn default() -> Self {
+        PathRouter { routes: Vec::new() }
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for PathRouter<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.routes.iter().map(|(pattern, value)| (pattern, value)))
+            .finish()
+    }
+}
+
+impl<V> PathRouter<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `pattern`, failing with [io::ErrorKind::InvalidInput] if it is malformed and
+    /// with [io::ErrorKind::AlreadyExists] if an equivalent pattern is already registered.
+    ///
+    /// The empty pattern matches the root itself.
+    pub fn insert(&mut self, pattern: &str, value: V) -> io::Result<()> {
+        let segments = parse(pattern)?;
+        let conflict = self.routes.iter().any(|(existing, _)| {
+            existing.len() == segments.len()
+                && existing.iter().zip(&segments).all(|(a, b)| a.same_shape(b))
+        });
+        if conflict {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("a route equivalent to {pattern:?} is already registered"),
+            ));
+        }
+        self.routes.push((segments, value));
+        Ok(())
+    }
+
+    /// Finds the route matching `path`, with its captures.
+    ///
+    /// `.` and `..` in `path` are resolved first; a path that escapes the root, or that is not
+    /// valid UTF-8, matches nothing.
+    pub fn at<P: AsRef<RelPath>>(&self, path: P) -> Option<(&V, Captures)> {
+        let segments = crate::rel::resolve(path.as_ref())
+            .into_iter()
+            .map(|c| match c {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect::<Option<Vec<&str>>>()?;
+
+        self.routes
+            .iter()
+            .filter_map(|(pattern, value)| Some((pattern, value, capture(pattern, &segments)?)))
+            .min_by(|(a, ..), (b, ..)| a.iter().map(Segment::rank).cmp(b.iter().map(Segment::rank)))
+            .map(|(_, value, captures)| (value, captures))
+    }
+}
+
+/// The values captured by `{name}` and `{*name}` segments of a matched route.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Captures {
+    params: Vec<(String, String)>,
+    rest: Option<RelPathBuf>,
+}
+
+impl Captures {
+    /// The value captured under `name`. A `{*name}` capture is joined with `/`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The segments captured by a trailing `{*name}`, as a path that stays within its root.
+    pub fn rest(&self) -> Option<&RelPath> {
+        self.rest.as_deref()
+    }
+}
+
+fn parse(pattern: &str) -> io::Result<Vec<Segment>> {
+    let invalid = |why: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid route pattern {pattern:?}: {why}"),
+        )
+    };
+    if pattern.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let parts: Vec<&str> = pattern.split('/').collect();
+    let mut segments = Vec::with_capacity(parts.len());
+    for (i, part) in parts.iter().enumerate() {
+        let segment = match part.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+            Some(name) => match name.strip_prefix('*') {
+                Some(_) if i + 1 != parts.len() => {
+                    return Err(invalid("`{*name}` must be the last segment"));
+                }
+                Some(name) => Segment::Rest(name.to_owned()),
+                None => Segment::Param(name.to_owned()),
+            },
+            None => Segment::Literal(part.to_string()),
+        };
+        match &segment {
+            Segment::Param(name) | Segment::Rest(name) if name.is_empty() => {
+                return Err(invalid("capture names must not be empty"));
+            }
+            Segment::Literal(text)
+                if text.is_empty()
+                    || text == "."
+                    || text == ".."
+                    || text.contains(['{', '}', '\\', '\0']) =>
+            {
+                return Err(invalid("segments must be plain, non-empty names"));
+            }
+            _ => {}
+        }
+        segments.push(segment);
+    }
+    Ok(segments)
+}
+
+fn capture(pattern: &[Segment], segments: &[&str]) -> Option<Captures> {
+    let mut captures = Captures::default();
+    let mut rest = segments;
+    for segment in pattern {
+        match segment {
+            Segment::Rest(name) => {
+                if rest.is_empty() {
+                    return None;
+                }
+                captures.params.push((name.clone(), rest.join("/")));
+                captures.rest = Some(RelPathBuf::from_path_buf_unchecked(
+                    rest.iter().collect::<PathBuf>(),
+                ));
+                return Some(captures);
+            }
+            Segment::Literal(text) => {
+                let (first, tail) = rest.split_first()?;
+                if first != text {
+                    return None;
+                }
+                rest = tail;
+            }
+            Segment::Param(name) => {
+                let (first, tail) = rest.split_first()?;
+                captures.params.push((name.clone(), (*first).to_owned()));
+                rest = tail;
+            }
+        }
+    }
+    rest.is_empty().then_some(captures)
+}
@@ -0,0 +1,210 @@
+//! Differential testing of dirge's lexical normalization against the filesystem.
+//!
+//! [normalize] resolves `.` and `..` without touching the disk. That is only sound where the
+//! platform agrees with it, so security-sensitive users can run [check_lexical_fs_agreement]
+//! in their own CI to see where it does not on the filesystems they deploy to. Requires the
+//! `testing` feature.
+//!
+//! ```no_run
+//! use dirge::{AbsPathBuf, testing::{self, Cause}};
+//!
+//! let sandbox = AbsPathBuf::new(std::env::temp_dir()).unwrap();
+//! let report = testing::check_lexical_fs_agreement(&sandbox, 10_000, 42).unwrap();
+//! for d in &report.disagreements {
+//!     if d.cause == Cause::NameVariant {
+//!         eprintln!("{} names an existing entry here", d.path.display());
+//!     }
+//! }
+//! assert!(report.disagreements.iter().all(|d| d.cause != Cause::Unexplained));
+//! ```
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{AbsPath, AbsPathBuf, RelPathBuf, normalize};
+
+/// Depth of the generated directory tree; every directory above it has children `a` and `b`,
+/// and where symlinks can be created, a link `l` to the all-`b` directory one level down from
+/// the root, e.g. `a/b/l` leads to `b/b/b`.
+const DEPTH: usize = 4;
+/// Depth, within the tree, of the directory generated paths are resolved from.
+const BASE_DEPTH: usize = 2;
+const MAX_SEGMENTS: u64 = 8;
+/// Spellings that name no entry in the tree but that some filesystems take for `a` or `b`.
+const VARIANTS: [&str; 4] = ["A", "B", "a.", "b."];
+
+/// The outcome of [check_lexical_fs_agreement].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Report {
+    /// How many paths were compared.
+    pub checked: usize,
+    pub disagreements: Vec<Disagreement>,
+}
+
+/// A generated path whose lexical normalization differs from what the filesystem resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Disagreement {
+    /// The generated path, relative to the directory it was resolved from.
+    pub path: RelPathBuf,
+    /// Where [normalize] says the path leads.
+    pub lexical: AbsPathBuf,
+    /// Where the filesystem resolved it, or the error it reported.
+    pub resolved: Result<AbsPathBuf, String>,
+    /// Why the two differ.
+    pub cause: Cause,
+}
+
+/// What a [Disagreement] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Cause {
+    /// The path goes through a symlinked directory, where the filesystem resolves a later `..`
+    /// from the link's target. Lexical normalization is knowingly unsound here.
+    Symlink,
+    /// The path uses a case or trailing-dot variant of a name, and the filesystem resolved it
+    /// as that name. Lexical normalization treats the spellings as different entries, so
+    /// checks built on it can be bypassed with such a variant.
+    NameVariant,
+    /// Neither of the above: [normalize] disagrees with the filesystem on a plain directory
+    /// tree, which is a bug in dirge.
+    Unexplained,
+}
+
+/// Generates `cases` relative paths from `seed` and checks that [normalize] leads where
+/// [AbsPath::canonicalize] does, by resolving both the path and its normalized form.
+///
+/// The paths mix names, `.`, `..`, empty segments, symlinked directories and case or
+/// trailing-dot variants of names, and are resolved inside a directory tree built in a scratch
+/// directory under `sandbox`, which is removed afterwards. Each disagreement records its
+/// [Cause]. A variant counts as a disagreement whenever the filesystem resolves a path through
+/// it, since the tree holds no entry by that name. Where symlinks cannot be created, as on
+/// Windows without developer mode, the tree and the paths have none. The same seed always
+/// generates the same paths.
+pub fn check_lexical_fs_agreement<P: AsRef<AbsPath>>(
+    sandbox: P,
+    cases: usize,
+    seed: u64,
+) -> io::Result<Report> {
+    let sandbox = sandbox.as_ref();
+    let (scratch, ()) = sandbox.unique_child_with(".dirge-oracle-", "", |path| {
+        std::fs::create_dir(path).map_err(|err| crate::fs::with_path(err, path))
+    })?;
+    let result = run(&scratch, cases, seed);
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+fn run(scratch: &AbsPath, cases: usize, seed: u64) -> io::Result<Report> {
+    let links = crate::fs::symlink(Path::new("."), &scratch.try_join(".link-probe")?, true).is_ok();
+    build_tree(scratch, 0, links)?;
+    let root = scratch.canonicalize()?;
+    let base = RelPathBuf::from_path_buf_unchecked(PathBuf::from_iter(std::iter::repeat_n(
+        "a", BASE_DEPTH,
+    )));
+
+    let mut rng = XorShift(seed | 1);
+    let mut disagreements = Vec::new();
+    for _ in 0..cases {
+        let path = generate(&mut rng, links);
+        let lexical = normalize(root.join(&base).join(&path));
+        let lexical = AbsPathBuf::from_path_buf_unchecked(lexical);
+        let resolved = root.join(&base).join(&path).canonicalize();
+        let variant = path
+            .components()
+            .any(|c| VARIANTS.iter().any(|v| c.as_os_str() == *v));
+        let cause = if variant {
+            // The tree has no entry by a variant's name, so only a failure to find it agrees.
+            match &resolved {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                _ => Cause::NameVariant,
+            }
+        } else if resolved.is_ok() && resolved.as_ref().ok() == lexical.canonicalize().as_ref().ok()
+        {
+            // Compared after resolving both, so a link the path merely ends at agrees.
+            continue;
+        } else if path.components().any(|c| c.as_os_str() == "l") {
+            Cause::Symlink
+        } else {
+            Cause::Unexplained
+        };
+        disagreements.push(Disagreement {
+            path,
+            lexical,
+            resolved: resolved.map_err(|err| err.to_string()),
+            cause,
+        });
+    }
+
+    Ok(Report {
+        checked: cases,
+        disagreements,
+    })
+}
+
+fn build_tree(dir: &AbsPath, depth: usize, links: bool) -> io::Result<()> {
+    if depth == DEPTH {
+        return Ok(());
+    }
+    for name in ["a", "b"] {
+        let child = dir.try_join(name)?;
+        std::fs::create_dir(&child)?;
+        build_tree(&child, depth + 1, links)?;
+    }
+    if links {
+        let target = PathBuf::from_iter(
+            std::iter::repeat_n("..", depth).chain(std::iter::repeat_n("b", depth + 1)),
+        );
+        crate::fs::symlink(&target, &dir.try_join("l")?, true)?;
+    }
+    Ok(())
+}
+
+/// Generates a path that stays inside the tree, so every prefix of it exists on disk unless it
+/// goes through a variant. A link leads to a directory as deep as the link itself, so the
+/// same holds on either side of one.
+fn generate(rng: &mut XorShift, links: bool) -> RelPathBuf {
+    let mut depth = BASE_DEPTH;
+    let mut text = String::new();
+    for i in 0..1 + rng.next() % MAX_SEGMENTS {
+        if i > 0 {
+            text.push('/');
+        }
+        let segment = match rng.next() % 5 {
+            0 if depth > 0 => {
+                depth -= 1;
+                ".."
+            }
+            1 => ".",
+            // An empty first segment would make the path absolute.
+            2 if i > 0 => "",
+            _ if depth < DEPTH => {
+                depth += 1;
+                match rng.next() % 8 {
+                    0 => VARIANTS[(rng.next() % 4) as usize],
+                    1 | 2 if links => "l",
+                    n if n & 1 == 0 => "a",
+                    _ => "b",
+                }
+            }
+            _ => ".",
+        };
+        text.push_str(segment);
+    }
+    RelPathBuf::from_path_buf_unchecked(text.into())
+}
+
+/// A small deterministic generator, so reports can be reproduced from the seed.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
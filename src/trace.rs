@@ -0,0 +1,173 @@
+//! Remembering where a path came from, so errors can point the user at its source.
+//!
+//! ```
+//! use dirge::AbsPathBuf;
+//! use dirge::trace::{Origin, Traced};
+//!
+//! let data = Traced::new(
+//!     AbsPathBuf::new("/srv/data").unwrap(),
+//!     Origin::Config { file: "tool.toml".into(), line: 12 },
+//! );
+//! let db = data.map("joined with `db.sqlite`", |dir| dir.try_join("db.sqlite").unwrap());
+//!
+//! let err = db.context(std::io::Error::other("permission denied"));
+//! assert_eq!(
+//!     err.to_string(),
+//!     "permission denied\n  computed: joined with `db.sqlite`\n  config file tool.toml:12"
+//! );
+//! ```
+
+use std::{fmt, io, ops::Deref, path::PathBuf};
+
+/// One step in the history of a [Traced] value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// A command-line argument, such as `--config`.
+    Arg(String),
+    /// A line in a configuration file.
+    Config { file: PathBuf, line: usize },
+    /// An environment variable.
+    Env(String),
+    /// Derived from the previous value, as described.
+    Computed(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Arg(arg) => write!(f, "argument {arg}"),
+            Origin::Config { file, line } => write!(f, "config file {}:{line}", file.display()),
+            Origin::Env(var) => write!(f, "environment variable {var}"),
+            Origin::Computed(how) => write!(f, "computed: {how}"),
+        }
+    }
+}
+
+/// A value, typically a path, together with the chain of [Origin]s that produced it.
+///
+/// Derefs to the value, so a `Traced<AbsPathBuf>` can be used wherever an [crate::AbsPath] is
+/// expected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Traced<T> {
+    value: T,
+    chain: Vec<Origin>,
+}
+
+impl<T> Traced<T> {
+    pub fn new(value: T, origin: Origin) -> Self {
+        Traced {
+            value,
+            chain: vec![origin],
+        }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Where the value originally came from.
+    pub fn origin(&self) -> &Origin {
+        &self.chain[0]
+    }
+
+    /// Every step from the original source to the current value, oldest first.
+    pub fn chain(&self) -> &[Origin] {
+        &self.chain
+    }
+
+    /// Derives a new value, recording `how` in the chain.
+    pub fn map<U, S: Into<String>>(self, how: S, f: impl FnOnce(T) -> U) -> Traced<U> {
+        let mut chain = self.chain;
+        chain.push(Origin::Computed(how.into()));
+        Traced {
+            value: f(self.value),
+            chain,
+        }
+    }
+
+    /// Like [Traced::map], for fallible derivations. Errors are passed through
+    /// [Traced::context].
+    pub fn try_map<U, S: Into<String>>(
+        self,
+        how: S,
+        f: impl FnOnce(&T) -> io::Result<U>,
+    ) -> io::Result<Traced<U>> {
+        match f(&self.value) {
+            Ok(value) => {
+                let mut chain = self.chain;
+                chain.push(Origin::Computed(how.into()));
+                Ok(Traced { value, chain })
+            }
+            Err(err) => Err(self.context(err)),
+        }
+    }
+
+    /// Appends the chain to `err`'s message, newest step first, one per line.
+    pub fn context(&self, err: io::Error) -> io::Error {
+        let mut msg = err.to_string();
+        for origin in self.chain.iter().rev() {
+            msg.push_str(&format!("\n  {origin}"));
+        }
+        io::Error::new(err.kind(), msg)
+    }
+}
+
+impl<T> Deref for Traced<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// Deserializes a [Traced] value from a known [Origin].
+///
+/// Formats don't report where a value was read from, so the caller supplies it:
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use dirge::RelPathBuf;
+/// use dirge::trace::{Origin, TracedSeed};
+/// use serde::de::{DeserializeSeed, IntoDeserializer, value::Error};
+///
+/// let seed = TracedSeed::<RelPathBuf>::new(Origin::Env("OUT".into()));
+/// let traced = seed.deserialize("out/gen".into_deserializer()).map_err(|e: Error| e).unwrap();
+/// assert_eq!(traced.origin(), &Origin::Env("OUT".into()));
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub struct TracedSeed<T> {
+    origin: Origin,
+    marker: std::marker::PhantomData<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TracedSeed<T> {
+    pub fn new(origin: Origin) -> Self {
+        TracedSeed {
+            origin,
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::de::DeserializeSeed<'de> for TracedSeed<T> {
+    type Value = Traced<T>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<Traced<T>, D::Error> {
+        Ok(Traced::new(T::deserialize(d)?, self.origin))
+    }
+}
+
+/// Serializes the value only; the chain is diagnostic information.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Traced<T> {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(s)
+    }
+}
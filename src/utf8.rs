@@ -0,0 +1,159 @@
+use std::{borrow::Cow, fmt, io, path::Path};
+
+/// How to turn a path that may not be valid UTF-8 into a [String].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LossyPolicy {
+    /// Replace invalid sequences with U+FFFD, like [Path::to_string_lossy].
+    Replace,
+    /// Escape each invalid byte as `\xNN`, and literal backslashes as `\\`, so the
+    /// original bytes can always be recovered.
+    Escape,
+    /// Fail with [io::ErrorKind::InvalidData].
+    Error,
+}
+
+pub(crate) fn to_string_policy(path: &Path, policy: LossyPolicy) -> io::Result<String> {
+    match policy {
+        LossyPolicy::Replace => Ok(path.to_string_lossy().into_owned()),
+        LossyPolicy::Escape => Ok(EscapedDisplay(path).to_string()),
+        LossyPolicy::Error => path
+            .to_str()
+            .map(str::to_owned)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "path is not valid UTF-8")),
+    }
+}
+
+/// The error returned when a path that must be UTF-8 is not, holding its raw bytes.
+///
+/// The bytes are those of [std::ffi::OsStr::as_encoded_bytes]: the path itself on Unix, and
+/// WTF-8 on Windows. Converts into an [io::Error] of kind [io::ErrorKind::InvalidData].
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct NonUtf8Error {
+    bytes: Vec<u8>,
+}
+
+impl NonUtf8Error {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl fmt::Debug for NonUtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NonUtf8Error(\"")?;
+        write_escaped(&self.bytes, f)?;
+        write!(f, "\")")
+    }
+}
+
+impl fmt::Display for NonUtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("path is not valid UTF-8: ")?;
+        write_escaped(&self.bytes, f)
+    }
+}
+
+impl std::error::Error for NonUtf8Error {}
+
+impl From<NonUtf8Error> for io::Error {
+    fn from(err: NonUtf8Error) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+pub(crate) fn to_cow_str(path: &Path) -> Result<Cow<'_, str>, NonUtf8Error> {
+    path.to_str()
+        .map(Cow::Borrowed)
+        .ok_or_else(|| NonUtf8Error {
+            bytes: path.as_os_str().as_encoded_bytes().to_vec(),
+        })
+}
+
+/// Displays a path using the [LossyPolicy::Escape] rules.
+///
+/// Created by the `display_escaped` method on the path types.
+#[derive(Clone, Copy)]
+pub struct EscapedDisplay<'a>(pub(crate) &'a Path);
+
+impl fmt::Display for EscapedDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_escaped(self.0.as_os_str().as_encoded_bytes(), f)
+    }
+}
+
+fn write_escaped(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for chunk in bytes.utf8_chunks() {
+        for part in chunk.valid().split_inclusive('\\') {
+            match part.strip_suffix('\\') {
+                Some(rest) => write!(f, "{rest}\\\\")?,
+                None => f.write_str(part)?,
+            }
+        }
+        for byte in chunk.invalid() {
+            write!(f, "\\x{byte:02X}")?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Debug for EscapedDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{self}\"")
+    }
+}
+
+/// `Debug` for the path types: lossless by default, display-style under `{:#?}`.
+pub(crate) fn debug(path: &Path, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+        write!(f, "\"{}\"", path.display())
+    } else {
+        fmt::Debug::fmt(path, f)
+    }
+}
+
+/// Rejects paths that are not valid UTF-8.
+#[cfg(feature = "utf8-strict")]
+pub(crate) fn check(path: &Path) -> io::Result<()> {
+    if path.to_str().is_some() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "path must be valid UTF-8",
+        ))
+    }
+}
+
+/// Like [check], for text the operating system returned, such as a directory entry's name or a
+/// resolved path, rather than text the caller passed in.
+#[cfg(feature = "utf8-strict")]
+pub(crate) fn check_os(path: &Path) -> io::Result<()> {
+    if path.to_str().is_some() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: not valid UTF-8", path.display()),
+        ))
+    }
+}
+
+/// Like [check], for operations that have no way to report an error.
+#[cfg(feature = "utf8-strict")]
+pub(crate) fn assert(path: &Path) {
+    assert!(
+        path.to_str().is_some(),
+        "path must be valid UTF-8 when the `utf8-strict` feature is enabled"
+    );
+}
+
+/// Views a path that is known to be valid UTF-8 as a string slice.
+#[cfg(feature = "utf8-strict")]
+pub(crate) fn as_str(path: &Path) -> &str {
+    path.to_str()
+        .expect("paths are valid UTF-8 when the `utf8-strict` feature is enabled")
+}
@@ -0,0 +1,234 @@
+//! Validating paths with complete reports: [check_all] covers many paths at once and [scan]
+//! every portability problem within one path.
+//!
+//! ```
+//! use dirge::validate::{self, Policy};
+//!
+//! let err = validate::check_all(["src/lib.rs", "/etc/passwd", "../up"], Policy::Contained)
+//!     .unwrap_err();
+//! let indices: Vec<usize> = err.failures().iter().map(|(i, _)| *i).collect();
+//! assert_eq!(indices, [1, 2]);
+//! ```
+
+use std::{error, fmt, io, ops::Range, path::Path};
+
+use crate::{RelPathBuf, ToRelPathBuf};
+
+/// What [check_all] accepts besides being a non-empty relative path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Policy {
+    /// Any relative path.
+    #[default]
+    Relative,
+    /// Relative paths that stay within their root once `..` is resolved lexically.
+    Contained,
+    /// Relative paths already in normalized form, with no `..` at all.
+    Canonical,
+}
+
+/// Validates every path in `paths` against `policy`.
+///
+/// Returns all of them as [RelPathBuf]s, or a [BatchError] listing each failure with its index.
+pub fn check_all<I>(paths: I, policy: Policy) -> Result<Vec<RelPathBuf>, BatchError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<Path>,
+{
+    let mut valid = Vec::new();
+    let mut failures = Vec::new();
+    for (index, path) in paths.into_iter().enumerate() {
+        match check(path.as_ref(), policy) {
+            Ok(rel) => valid.push(rel),
+            Err(err) => failures.push((index, crate::fs::with_path(err, path.as_ref()))),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(valid)
+    } else {
+        Err(BatchError { failures })
+    }
+}
+
+fn check(path: &Path, policy: Policy) -> io::Result<RelPathBuf> {
+    if path.as_os_str().is_empty() {
+        return Err(invalid("path is empty"));
+    }
+    let rel = path.to_rel_path_buf()?;
+    match policy {
+        Policy::Relative => {}
+        Policy::Contained => {
+            if crate::rel::escapes_root(path) {
+                return Err(invalid("path escapes its root"));
+            }
+        }
+        Policy::Canonical => {
+            if !crate::norm::is_canonical_form(path) || crate::rel::escapes_root(path) {
+                return Err(invalid("path is not normalized"));
+            }
+        }
+    }
+    Ok(rel)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg.to_owned())
+}
+
+/// Every failure found by [check_all], in input order.
+#[derive(Debug)]
+pub struct BatchError {
+    failures: Vec<(usize, io::Error)>,
+}
+
+impl BatchError {
+    /// The index of each rejected path in the input, with the reason.
+    pub fn failures(&self) -> &[(usize, io::Error)] {
+        &self.failures
+    }
+
+    pub fn into_failures(self) -> Vec<(usize, io::Error)> {
+        self.failures
+    }
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} invalid path(s)", self.failures.len())?;
+        for (index, err) in &self.failures {
+            write!(f, "\n  entry {index}: {err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for BatchError {}
+
+impl From<BatchError> for io::Error {
+    fn from(err: BatchError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    }
+}
+
+/// A portability problem found by [scan].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// The offending bytes, as a range into the path's text.
+    pub span: Range<usize>,
+    pub issue: Issue,
+}
+
+/// The kinds of problem reported by [scan].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Issue {
+    /// A character Windows does not allow in file names: a control character, one of
+    /// `<>:"|?*`, or `\` (a separator on Windows).
+    InvalidChar(char),
+    /// Bytes that are not valid UTF-8.
+    NotUtf8,
+    /// A name Windows reserves for devices, such as `CON` or `lpt1.txt`.
+    ReservedName,
+    /// A name ending in a space or `.`, which Windows silently strips.
+    TrailingSpaceOrDot,
+    /// A component longer than 255 bytes, the limit of most file systems.
+    TooLong,
+}
+
+const MAX_COMPONENT_LEN: usize = 255;
+
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Reports everything in `path` that would stop it from being a valid file name on any of
+/// Windows, macOS and Linux, in order of position.
+///
+/// Spans index into [OsStr::as_encoded_bytes](std::ffi::OsStr::as_encoded_bytes), which for
+/// valid UTF-8 is the path's text, so they can be used to underline the problem in a report.
+/// A Windows drive or UNC prefix is not scanned.
+///
+/// ```
+/// use dirge::validate::{self, Issue};
+///
+/// let findings = validate::scan("docs/aux.txt/what?");
+/// assert_eq!(findings[0].span, 5..12);
+/// assert_eq!(findings[0].issue, Issue::ReservedName);
+/// assert_eq!(findings[1].span, 17..18);
+/// assert_eq!(findings[1].issue, Issue::InvalidChar('?'));
+/// ```
+pub fn scan<P: AsRef<Path>>(path: P) -> Vec<Finding> {
+    let path = path.as_ref();
+    let bytes = path.as_os_str().as_encoded_bytes();
+    let start = match path.components().next() {
+        Some(std::path::Component::Prefix(prefix)) => prefix.as_os_str().len(),
+        _ => 0,
+    };
+
+    let mut findings = Vec::new();
+    let mut offset = start;
+    for component in bytes[start..].split(|&b| std::path::is_separator(char::from(b))) {
+        scan_component(component, offset, &mut findings);
+        offset += component.len() + 1;
+    }
+    findings.sort_by_key(|f| (f.span.start, f.span.end));
+    findings
+}
+
+/// Returns whether Windows reserves `component` for a device.
+pub(crate) fn is_reserved_name(component: &[u8]) -> bool {
+    // `CON`, `con.txt` and `CON .tar.gz` are all reserved.
+    let stem = component.split(|&b| b == b'.').next().unwrap_or_default();
+    let stem = stem.trim_ascii_end();
+    RESERVED_NAMES
+        .iter()
+        .any(|r| r.as_bytes().eq_ignore_ascii_case(stem))
+}
+
+fn scan_component(component: &[u8], offset: usize, findings: &mut Vec<Finding>) {
+    if matches!(component, b"" | b"." | b"..") {
+        return;
+    }
+    let whole = offset..offset + component.len();
+
+    if component.len() > MAX_COMPONENT_LEN {
+        findings.push(Finding {
+            span: whole.clone(),
+            issue: Issue::TooLong,
+        });
+    }
+
+    if is_reserved_name(component) {
+        findings.push(Finding {
+            span: whole.clone(),
+            issue: Issue::ReservedName,
+        });
+    }
+
+    if let Some(b' ' | b'.') = component.last() {
+        findings.push(Finding {
+            span: whole.end - 1..whole.end,
+            issue: Issue::TrailingSpaceOrDot,
+        });
+    }
+
+    let mut pos = offset;
+    for chunk in component.utf8_chunks() {
+        for (i, c) in chunk.valid().char_indices() {
+            if c < ' ' || matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*' | '\\') {
+                findings.push(Finding {
+                    span: pos + i..pos + i + c.len_utf8(),
+                    issue: Issue::InvalidChar(c),
+                });
+            }
+        }
+        pos += chunk.valid().len();
+        if !chunk.invalid().is_empty() {
+            findings.push(Finding {
+                span: pos..pos + chunk.invalid().len(),
+                issue: Issue::NotUtf8,
+            });
+            pos += chunk.invalid().len();
+        }
+    }
+}
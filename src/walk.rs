@@ -0,0 +1,530 @@
+//! Deterministic, typed directory traversal.
+//!
+//! ```no_run
+//! use dirge::AbsPathBuf;
+//!
+//! let root = AbsPathBuf::new("/srv/site").unwrap();
+//! for entry in root.walk() {
+//!     let entry = entry.unwrap();
+//!     println!("{:?} at depth {}", entry.relative_path(), entry.depth());
+//! }
+//! ```
+
+use std::{
+    fmt,
+    fs::{self, File, FileType, Metadata},
+    io::{self, Read},
+    path::Path,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use crate::{AbsPath, AbsPathBuf, RelPath, RelPathBuf};
+
+impl AbsPath {
+    /// Walks the tree below this directory, depth first, visiting entries in name order.
+    ///
+    /// The order is the same on every run and every platform, so the output is suitable for
+    /// manifests and archives. The root itself is not yielded. Symlinks are yielded but never
+    /// followed. A directory that cannot be read is yielded, followed by an error, and the walk continues
+    /// with its siblings.
+    pub fn walk(&self) -> Walk {
+        Walk {
+            root: self.to_owned(),
+            stack: Vec::new(),
+            pending: None,
+            started: false,
+            budget: Budget::default(),
+            aborted: false,
+        }
+    }
+}
+
+/// Iterator returned by [AbsPath::walk].
+pub struct Walk {
+    root: AbsPathBuf,
+    /// Entries still to visit, in reverse order so the next one is at the end.
+    stack: Vec<Entry>,
+    /// An error reading the directory yielded last, reported on the next call.
+    pending: Option<io::Error>,
+    started: bool,
+    budget: Budget,
+    /// Set once a limit has been exceeded; the walk yields nothing more.
+    aborted: bool,
+}
+
+/// Bounds on the work a [Walk] or [materialize](crate::fs::materialize_with_limits) may do,
+/// for trees that come from untrusted sources.
+///
+/// All limits are off by default. When one is exceeded the walk yields a [LimitExceeded] error
+/// and then ends; [Limits::same_file_system] is the exception, as it only prunes the walk.
+///
+/// ```no_run
+/// use dirge::{AbsPathBuf, walk::{LimitExceeded, Limits}};
+/// use std::time::Duration;
+///
+/// let upload = AbsPathBuf::new("/srv/uploads/1234").unwrap();
+/// let limits = Limits::new()
+///     .max_files(10_000)
+///     .max_bytes(1 << 30)
+///     .max_depth(32)
+///     .timeout(Duration::from_secs(10));
+/// for entry in upload.walk().with_limits(limits) {
+///     match entry {
+///         Ok(entry) => println!("{:?}", entry.relative_path()),
+///         Err(err) if LimitExceeded::from_io(&err).is_some() => {
+///             eprintln!("rejecting upload: {err}");
+///             break;
+///         }
+///         Err(err) => eprintln!("{err}"),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Limits {
+    max_files: Option<u64>,
+    max_bytes: Option<u64>,
+    max_depth: Option<usize>,
+    timeout: Option<Duration>,
+    same_file_system: bool,
+}
+
+impl Limits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The most entries of any type the walk may yield.
+    pub fn max_files(mut self, max: u64) -> Self {
+        self.max_files = Some(max);
+        self
+    }
+
+    /// The largest total size of regular files the walk may yield, as reported by their
+    /// metadata. Setting this fetches the [Entry::metadata] of each file.
+    pub fn max_bytes(mut self, max: u64) -> Self {
+        self.max_bytes = Some(max);
+        self
+    }
+
+    /// The greatest [Entry::depth] the walk may reach, so directories at this depth must be
+    /// empty.
+    pub fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = Some(max);
+        self
+    }
+
+    /// How long the walk may run, measured from its first step. Checked between entries, so a
+    /// single slow directory read can overrun it.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Stays on the file system of the walk's root: a directory on another one, such as a
+    /// mount point or `/proc`, is yielded but not descended into. Checked with
+    /// [AbsPath::same_file_system]. For [materialize](crate::fs::materialize_with_limits) the
+    /// root is each directory being copied, and such a directory is copied empty.
+    pub fn same_file_system(mut self) -> Self {
+        self.same_file_system = true;
+        self
+    }
+}
+
+/// The work done so far against a set of [Limits], shared by everything one operation walks.
+#[derive(Debug, Default)]
+pub(crate) struct Budget {
+    limits: Limits,
+    deadline: Option<Instant>,
+    files: u64,
+    bytes: u64,
+}
+
+impl Budget {
+    /// Starts spending against `limits`; the timeout runs from now.
+    pub(crate) fn new(limits: Limits) -> Budget {
+        Budget {
+            limits,
+            deadline: limits.timeout.map(|t| Instant::now() + t),
+            ..Budget::default()
+        }
+    }
+
+    /// Whether walks should stay on the file system they start on.
+    pub(crate) fn same_file_system(&self) -> bool {
+        self.limits.same_file_system
+    }
+
+    /// Whether [Budget::charge] needs the sizes of regular files.
+    pub(crate) fn counts_bytes(&self) -> bool {
+        self.limits.max_bytes.is_some()
+    }
+
+    /// Counts one entry at `depth` holding `size` bytes, failing if that exceeds a limit.
+    pub(crate) fn charge(
+        &mut self,
+        path: &AbsPath,
+        depth: usize,
+        size: u64,
+    ) -> Result<(), LimitExceeded> {
+        let exceeded = |limit| {
+            Err(LimitExceeded {
+                limit,
+                path: path.to_owned(),
+            })
+        };
+        let limits = self.limits;
+        if let (Some(deadline), Some(timeout)) = (self.deadline, limits.timeout)
+            && Instant::now() >= deadline
+        {
+            return exceeded(Limit::Timeout(timeout));
+        }
+        if let Some(max) = limits.max_depth
+            && depth > max
+        {
+            return exceeded(Limit::Depth(max));
+        }
+        self.files += 1;
+        if let Some(max) = limits.max_files
+            && self.files > max
+        {
+            return exceeded(Limit::Files(max));
+        }
+        self.bytes = self.bytes.saturating_add(size);
+        if let Some(max) = limits.max_bytes
+            && self.bytes > max
+        {
+            return exceeded(Limit::Bytes(max));
+        }
+        Ok(())
+    }
+}
+
+/// Which limit a walk exceeded, with the configured value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    Files(u64),
+    Bytes(u64),
+    Depth(usize),
+    Timeout(Duration),
+}
+
+/// The error a walk reports when it exceeds one of its [Limits].
+///
+/// It is carried inside an [io::Error] of kind [io::ErrorKind::QuotaExceeded], or
+/// [io::ErrorKind::TimedOut] for the timeout; use [LimitExceeded::from_io] to get it back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitExceeded {
+    limit: Limit,
+    path: AbsPathBuf,
+}
+
+impl LimitExceeded {
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    /// The entry at which the limit was exceeded.
+    pub fn path(&self) -> &AbsPath {
+        &self.path
+    }
+
+    /// Returns the [LimitExceeded] inside `err`, if that is what it carries.
+    pub fn from_io(err: &io::Error) -> Option<&LimitExceeded> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: exceeded ", self.path.display())?;
+        match self.limit {
+            Limit::Files(max) => write!(f, "the limit of {max} entries"),
+            Limit::Bytes(max) => write!(f, "the limit of {max} bytes"),
+            Limit::Depth(max) => write!(f, "the depth limit of {max}"),
+            Limit::Timeout(timeout) => write!(f, "its timeout of {timeout:?}"),
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+impl From<LimitExceeded> for io::Error {
+    fn from(err: LimitExceeded) -> io::Error {
+        let kind = match err.limit {
+            Limit::Timeout(_) => io::ErrorKind::TimedOut,
+            _ => io::ErrorKind::QuotaExceeded,
+        };
+        io::Error::new(kind, err)
+    }
+}
+
+/// A file, directory or other entry found by [Walk].
+///
+/// The file type comes from the directory listing, so [Entry::is_dir] and friends never touch
+/// the file system. [Entry::metadata] is fetched on first use and cached; on Windows the
+/// listing already carries it and no extra call is made.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    path: AbsPathBuf,
+    relative: RelPathBuf,
+    file_type: FileType,
+    depth: usize,
+    metadata: OnceLock<Metadata>,
+}
+
+impl Entry {
+    pub fn path(&self) -> &AbsPath {
+        &self.path
+    }
+
+    /// The path relative to the root of the walk.
+    pub fn relative_path(&self) -> &RelPath {
+        &self.relative
+    }
+
+    /// The type of the entry itself; symlinks are not followed.
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type.is_dir()
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file_type.is_file()
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.file_type.is_symlink()
+    }
+
+    /// Metadata of the entry itself; symlinks are not followed. Fetched at most once.
+    pub fn metadata(&self) -> io::Result<&Metadata> {
+        if let Some(metadata) = self.metadata.get() {
+            return Ok(metadata);
+        }
+        let metadata = self
+            .path
+            .symlink_metadata()
+            .map_err(|err| crate::fs::with_path(err, &self.path))?;
+        Ok(self.metadata.get_or_init(|| metadata))
+    }
+
+    /// The number of components below the root, so children of the root have depth 1.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn into_path(self) -> AbsPathBuf {
+        self.path
+    }
+}
+
+/// Reads the children of `dir`, in reverse name order so they can be pushed onto the stack.
+fn read_children(dir: &AbsPath, relative: &Path, depth: usize) -> io::Result<Vec<Entry>> {
+    let mut children = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| {
+                    let entry = entry?;
+                    // `dir` and `relative` are already checked, so the name is the only new text
+                    // in either path built from it below.
+                    #[cfg(feature = "utf8-strict")]
+                    crate::utf8::check_os(Path::new(&entry.file_name()))?;
+                    let metadata = OnceLock::new();
+                    // Windows fills in the metadata while listing the directory.
+                    #[cfg(windows)]
+                    if let Ok(m) = entry.metadata() {
+                        let _ = metadata.set(m);
+                    }
+                    Ok((entry.file_name(), entry.file_type()?, metadata))
+                })
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .map_err(|err| crate::fs::with_path(err, dir))?;
+    // Names within one directory are unique, so an unstable sort gives the same order.
+    children.sort_unstable_by(|(a, ..), (b, ..)| b.cmp(a));
+
+    Ok(children
+        .into_iter()
+        .map(|(name, file_type, metadata)| Entry {
+            path: AbsPathBuf::from_path_buf_unchecked(Path::join(dir, &name)),
+            relative: RelPathBuf::from_path_buf_unchecked(relative.join(&name)),
+            file_type,
+            depth: depth + 1,
+            metadata,
+        })
+        .collect())
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.aborted {
+            return None;
+        }
+        if let Some(err) = self.pending.take() {
+            return Some(Err(err));
+        }
+        if !self.started {
+            self.started = true;
+            self.budget = Budget::new(self.budget.limits);
+            match read_children(&self.root, Path::new(""), 0) {
+                Ok(children) => self.stack = children,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        let entry = self.stack.pop()?;
+        let size = if self.budget.counts_bytes() && entry.is_file() {
+            match entry.metadata() {
+                Ok(metadata) => metadata.len(),
+                // Most likely removed since it was listed; report it and carry on.
+                Err(err) => return Some(Err(err)),
+            }
+        } else {
+            0
+        };
+        if let Err(exceeded) = self.budget.charge(&entry.path, entry.depth, size) {
+            self.aborted = true;
+            self.stack.clear();
+            return Some(Err(exceeded.into()));
+        }
+        if entry.is_dir() {
+            let children = match self.descends_into(&entry) {
+                Ok(true) => read_children(&entry.path, &entry.relative, entry.depth),
+                Ok(false) => Ok(Vec::new()),
+                Err(err) => Err(err),
+            };
+            match children {
+                Ok(children) => self.stack.extend(children),
+                Err(err) => self.pending = Some(err),
+            }
+        }
+        Some(Ok(entry))
+    }
+}
+
+impl Walk {
+    /// Applies `limits` to the walk, replacing any set before; see [Limits].
+    pub fn with_limits(mut self, limits: Limits) -> Walk {
+        self.budget.limits = limits;
+        self
+    }
+
+    /// Keeps the walk on the file system of its root, as [Limits::same_file_system] does,
+    /// leaving the other limits as they are.
+    ///
+    /// ```no_run
+    /// use dirge::AbsPathBuf;
+    ///
+    /// // Like `find / -xdev`: mount points are listed, but not what is mounted on them.
+    /// let root = AbsPathBuf::new("/").unwrap();
+    /// for entry in root.walk().same_file_system() {
+    ///     println!("{:?}", entry.unwrap().relative_path());
+    /// }
+    /// ```
+    pub fn same_file_system(mut self) -> Walk {
+        self.budget.limits = self.budget.limits.same_file_system();
+        self
+    }
+
+    fn descends_into(&self, dir: &Entry) -> io::Result<bool> {
+        if !self.budget.same_file_system() {
+            return Ok(true);
+        }
+        self.root
+            .same_file_system(&dir.path)
+            .map_err(|err| crate::fs::with_path(err, &dir.path))
+    }
+
+    /// Adapts the walk into entries ready to hand to an archive writer such as `tar::Builder`.
+    ///
+    /// Entries come in the same deterministic order as the walk. Names are UTF-8, use `/` as the
+    /// separator and end with `/` for directories; a name that is not valid UTF-8 is an error.
+    ///
+    /// ```no_run
+    /// use dirge::AbsPathBuf;
+    /// use std::io::Read;
+    ///
+    /// let root = AbsPathBuf::new("/srv/site").unwrap();
+    /// for entry in root.walk().archive_entries() {
+    ///     let mut entry = entry.unwrap();
+    ///     let mut data = Vec::new();
+    ///     entry.contents.read_to_end(&mut data).unwrap();
+    ///     println!("{} ({} bytes)", entry.name, data.len());
+    /// }
+    /// ```
+    pub fn archive_entries(self) -> ArchiveEntries {
+        ArchiveEntries { walk: self }
+    }
+}
+
+/// Iterator returned by [Walk::archive_entries].
+pub struct ArchiveEntries {
+    walk: Walk,
+}
+
+/// An entry produced by [Walk::archive_entries].
+#[derive(Debug)]
+pub struct ArchiveEntry {
+    /// The path relative to the walk's root, joined with `/`; directories end with `/`.
+    pub name: String,
+    /// Metadata of the entry itself; symlinks are not followed.
+    pub metadata: Metadata,
+    pub contents: Contents,
+}
+
+/// The contents of an [ArchiveEntry]: the open file, or nothing for other entry types.
+#[derive(Debug)]
+pub struct Contents(Option<File>);
+
+impl Read for Contents {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.0 {
+            Some(file) => file.read(buf),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Iterator for ArchiveEntries {
+    type Item = io::Result<ArchiveEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.walk.next()?.and_then(|entry| {
+            let path = entry.path();
+            let mut name = archive_name(entry.relative_path())
+                .map_err(|err| crate::fs::with_path(err, path))?;
+            if entry.is_dir() {
+                name.push('/');
+            }
+            let metadata = entry.metadata()?.clone();
+            let contents = if metadata.is_file() {
+                Some(File::open(path).map_err(|err| crate::fs::with_path(err, path))?)
+            } else {
+                None
+            };
+            Ok(ArchiveEntry {
+                name,
+                metadata,
+                contents: Contents(contents),
+            })
+        }))
+    }
+}
+
+fn archive_name(relative: &RelPath) -> io::Result<String> {
+    let segments = relative
+        .components()
+        .map(|c| {
+            c.as_os_str().to_str().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "entry name is not valid UTF-8")
+            })
+        })
+        .collect::<io::Result<Vec<&str>>>()?;
+    Ok(segments.join("/"))
+}
@@ -0,0 +1,107 @@
+//! The textual form dirge uses for Windows verbatim paths in configuration.
+//!
+//! `canonicalize` on Windows returns verbatim paths such as `\\?\C:\src` and
+//! `\\?\UNC\server\share`. Written to a config file, they trip up other tools and look
+//! different from what users type. When the `serde` feature serializes an absolute path on
+//! Windows, it therefore drops the `\\?\` prefix with [simplify_verbatim] whenever that does
+//! not change which file the path names. Deserializing accepts both spellings.
+//!
+//! The functions here work on text, so the same rules apply on every host, including Unix CI
+//! checking configs written on Windows.
+
+use std::borrow::Cow;
+
+/// The longest path that Windows accepts without the verbatim prefix.
+const MAX_PATH: usize = 259;
+
+/// Returns `text` without its `\\?\` prefix if the result names the same file, and `text`
+/// unchanged otherwise.
+///
+/// `\\?\C:\x` becomes `C:\x` and `\\?\UNC\server\share\x` becomes `\\server\share\x`. The
+/// prefix is kept when the rest of the path relies on it: `.` or `..` components, empty
+/// components, `/`, names ending in a space or `.`, reserved device names such as `NUL`, and
+/// paths longer than `MAX_PATH`. Device paths (`\\.\`) and volume GUID paths are never
+/// changed.
+///
+/// ```
+/// use dirge::win::simplify_verbatim;
+///
+/// assert_eq!(simplify_verbatim(r"\\?\C:\src\lib.rs"), r"C:\src\lib.rs");
+/// assert_eq!(simplify_verbatim(r"\\?\UNC\srv\share\a"), r"\\srv\share\a");
+/// assert_eq!(simplify_verbatim(r"\\?\C:\trailing."), r"\\?\C:\trailing.");
+/// ```
+pub fn simplify_verbatim(text: &str) -> Cow<'_, str> {
+    match simplified(text) {
+        Some(simple) => Cow::Owned(simple),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// Returns whether `text` is a verbatim path whose prefix [simplify_verbatim] has to keep.
+pub fn needs_verbatim(text: &str) -> bool {
+    text.starts_with(r"\\?\") && simplified(text).is_none()
+}
+
+fn simplified(text: &str) -> Option<String> {
+    let rest = text.strip_prefix(r"\\?\")?;
+    let (simple, tail) = if let Some(unc) = strip_prefix_ignore_case(rest, r"UNC\") {
+        let mut parts = unc.splitn(3, '\\');
+        let (server, share) = (parts.next()?, parts.next()?);
+        if server.is_empty() || share.is_empty() {
+            return None;
+        }
+        (format!(r"\\{server}\{share}"), parts.next())
+    } else {
+        let bytes = rest.as_bytes();
+        if bytes.len() < 2 || !bytes[0].is_ascii_alphabetic() || bytes[1] != b':' {
+            return None;
+        }
+        match rest[2..].strip_prefix('\\') {
+            Some(tail) => (rest[..2].to_owned(), Some(tail)),
+            None if rest.len() == 2 => (rest.to_owned(), None),
+            None => return None,
+        }
+    };
+
+    let mut simple = simple;
+    let is_drive = !simple.starts_with('\\');
+    match tail {
+        Some(tail) if !tail.is_empty() => {
+            if tail.contains('/') || tail.split('\\').any(needs_prefix) {
+                return None;
+            }
+            simple.push('\\');
+            simple.push_str(tail);
+        }
+        // `C:` alone would be relative to the drive's current directory.
+        _ if is_drive => simple.push('\\'),
+        _ => {}
+    }
+    (simple.len() <= MAX_PATH).then_some(simple)
+}
+
+fn needs_prefix(component: &str) -> bool {
+    matches!(component, "" | "." | "..")
+        || component.ends_with([' ', '.'])
+        || crate::validate::is_reserved_name(component.as_bytes())
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+/// Serializes a path, simplifying verbatim prefixes on Windows.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_path<S: serde::Serializer>(
+    path: &std::path::Path,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::Serialize;
+
+    match path.to_str() {
+        Some(text) if cfg!(windows) => serializer.serialize_str(&simplify_verbatim(text)),
+        _ => path.serialize(serializer),
+    }
+}
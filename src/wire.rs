@@ -0,0 +1,222 @@
+//! A compact, host-independent binary form for relative paths.
+//!
+//! The encoding of a [RelPath] is:
+//!
+//! ```text
+//! count    varint              number of segments
+//! segment  varint len, bytes   repeated `count` times
+//! ```
+//!
+//! where `varint` is unsigned LEB128 and each segment is the UTF-8 text of one path component.
+//! Separators are never stored, so a path encodes to the same bytes on every platform. `.`
+//! components are dropped and `..` is stored as the segment `..`. Segments are never empty and
+//! never contain `/`, `\` or NUL. Decoding checks that every other segment is a single ordinary
+//! component on the host, so a segment such as `C:` on Windows cannot turn the result into a
+//! drive-relative path.
+//!
+//! ```
+//! use dirge::{RelPathBuf, wire};
+//!
+//! let rel = RelPathBuf::new("src/lib.rs").unwrap();
+//! let bytes = wire::encode(&rel).unwrap();
+//! assert_eq!(bytes, b"\x02\x03src\x06lib.rs");
+//! assert_eq!(wire::decode(&bytes).unwrap(), rel);
+//! ```
+
+use std::{
+    io,
+    path::{Component, PathBuf},
+};
+
+use crate::{RelPath, RelPathBuf};
+
+/// Encodes `path` in the compact form, failing if a component is not valid UTF-8.
+pub fn encode<P: AsRef<RelPath>>(path: P) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    encode_into(path, &mut out)?;
+    Ok(out)
+}
+
+/// Like [encode], appending to `out`. On error `out` is left unchanged.
+pub fn encode_into<P: AsRef<RelPath>>(path: P, out: &mut Vec<u8>) -> io::Result<()> {
+    let segments = path
+        .as_ref()
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .map(|c| {
+            c.as_os_str().to_str().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "path component is not valid UTF-8",
+                )
+            })
+        })
+        .collect::<io::Result<Vec<&str>>>()?;
+
+    write_varint(out, segments.len());
+    for segment in segments {
+        write_varint(out, segment.len());
+        out.extend_from_slice(segment.as_bytes());
+    }
+    Ok(())
+}
+
+/// Decodes a path from its compact form, which must span all of `bytes`.
+pub fn decode(bytes: &[u8]) -> io::Result<RelPathBuf> {
+    let mut rest = bytes;
+    let count = read_varint(&mut rest)?;
+
+    let mut path = PathBuf::new();
+    for _ in 0..count {
+        let len = read_varint(&mut rest)?;
+        if len > rest.len() {
+            return Err(invalid("segment extends past the end of the input"));
+        }
+        let (segment, tail) = rest.split_at(len);
+        rest = tail;
+
+        let segment = std::str::from_utf8(segment).map_err(|_| invalid("segment is not UTF-8"))?;
+        if segment != ".." {
+            crate::rel::check_portable_segment(segment)
+                .map_err(|_| invalid("segment is not a single path component"))?;
+        }
+        path.push(segment);
+    }
+
+    if !rest.is_empty() {
+        return Err(invalid("trailing bytes after the last segment"));
+    }
+    Ok(RelPathBuf::from_path_buf_unchecked(path))
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_owned())
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: usize) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn read_varint(input: &mut &[u8]) -> io::Result<usize> {
+    let mut n: usize = 0;
+    for shift in (0..usize::BITS).step_by(7) {
+        let (&byte, rest) = input
+            .split_first()
+            .ok_or_else(|| invalid("unexpected end of input"))?;
+        *input = rest;
+        let value = usize::from(byte & 0x7f);
+        if shift > 0 && value >> (usize::BITS - shift) != 0 {
+            return Err(invalid("varint overflows"));
+        }
+        n |= value << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(invalid("varint overflows"))
+}
+
+/// Serde adapter that stores a [RelPathBuf] in the compact form as a byte string.
+///
+/// Use it with `#[serde(with = "dirge::wire::compact")]` for binary formats such as postcard,
+/// where the default string form would carry host separators.
+#[cfg(feature = "serde")]
+pub mod compact {
+    use std::fmt;
+
+    use serde::{Deserializer, Serializer, de};
+
+    use crate::RelPathBuf;
+
+    pub fn serialize<S>(path: &RelPathBuf, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let bytes = super::encode(path).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<RelPathBuf, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = RelPathBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a compact relative path")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                super::decode(v).map_err(E::custom)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                self.visit_bytes(&bytes)
+            }
+        }
+
+        deserializer.deserialize_bytes(Visitor)
+    }
+
+    #[cfg(test)]
+    mod serde_tests {
+        use super::*;
+        use serde::{Deserialize, Serialize};
+        use serde_test::{Token, assert_de_tokens_error, assert_tokens};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Entry {
+            #[serde(with = "crate::wire::compact")]
+            path: RelPathBuf,
+        }
+
+        #[test]
+        fn test_compact_round_trip() {
+            let entry = Entry {
+                path: RelPathBuf::new("a/b").unwrap(),
+            };
+            assert_tokens(
+                &entry,
+                &[
+                    Token::Struct {
+                        name: "Entry",
+                        len: 1,
+                    },
+                    Token::Str("path"),
+                    Token::Bytes(b"\x02\x01a\x01b"),
+                    Token::StructEnd,
+                ],
+            );
+        }
+
+        #[test]
+        fn test_compact_rejects_garbage() {
+            assert_de_tokens_error::<Entry>(
+                &[
+                    Token::Struct {
+                        name: "Entry",
+                        len: 1,
+                    },
+                    Token::Str("path"),
+                    Token::Bytes(b"\x01\x03a/b"),
+                ],
+                "segment is not a single path component",
+            );
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use dirge::{AbsPath, AbsPathBuf, ToAbsPathBuf};
+use dirge::{AbsPath, AbsPathBuf, RelPath, ToAbsPathBuf};
 use std::{
     ffi::OsStr,
     io,
@@ -44,3 +44,419 @@ fn deref_methods() {
 
     assert!(c1.capacity() > 0);
 }
+
+#[test]
+fn as_ref_abs_path() {
+    use std::{borrow::Cow, sync::Arc};
+
+    fn takes<P: AsRef<AbsPath>>(p: P) -> AbsPathBuf {
+        p.as_ref().to_owned()
+    }
+
+    let buf = AbsPathBuf::new("/etc").unwrap();
+    let slice: &AbsPath = &buf;
+    let arc: Arc<AbsPath> = Arc::from(slice);
+    let cow: Cow<'_, AbsPath> = Cow::Borrowed(slice);
+
+    assert_eq!(takes(&buf), buf);
+    assert_eq!(takes(slice), buf);
+    assert_eq!(takes(arc.clone()), buf);
+    assert_eq!(takes(&cow), buf);
+    assert_eq!(takes(buf.clone()), buf);
+    assert_eq!(&*arc, slice);
+}
+
+#[cfg(unix)]
+#[test]
+fn debug_alternate_is_display_style() {
+    let buf = AbsPathBuf::new("/tmp/a\\b").unwrap();
+    assert_eq!(format!("{buf:?}"), r#""/tmp/a\\b""#);
+    assert_eq!(format!("{buf:#?}"), r#""/tmp/a\b""#);
+    assert_eq!(format!("{:#?}", &*buf), r#""/tmp/a\b""#);
+}
+
+#[test]
+fn clear_to_reuses_allocation() {
+    let root = AbsPathBuf::new("/srv/site").unwrap();
+    let mut buf = root.try_join("some/deeply/nested/page.html").unwrap();
+    let capacity = buf.capacity();
+    let ptr = buf.as_os_str().as_encoded_bytes().as_ptr();
+
+    for name in ["a.html", "b/c.html", "d.html"] {
+        buf.clear_to(&root).unwrap();
+        buf.push(RelPath::new(name).unwrap());
+        assert_eq!(buf, root.try_join(name).unwrap());
+    }
+    assert_eq!(buf.capacity(), capacity);
+    assert_eq!(buf.as_os_str().as_encoded_bytes().as_ptr(), ptr);
+}
+
+#[test]
+fn clear_to_rejects_non_ancestor() {
+    let mut buf = AbsPathBuf::new("/srv/site/a").unwrap();
+    let other = AbsPathBuf::new("/srv/sites").unwrap();
+    let err = buf.clear_to(&other).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(buf, AbsPathBuf::new("/srv/site/a").unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn truncate_to_root() {
+    let mut buf = AbsPathBuf::new("/srv/site/a").unwrap();
+    buf.truncate_to_root();
+    assert_eq!(buf, AbsPathBuf::new("/").unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn typed_shadows() {
+    let abs = AbsPathBuf::new("/srv/site/index.html").unwrap();
+
+    let parent: &AbsPath = abs.parent().unwrap();
+    assert_eq!(parent, &*AbsPathBuf::new("/srv/site").unwrap());
+    let renamed: AbsPathBuf = abs.with_file_name("about.html").unwrap();
+    assert_eq!(renamed, AbsPathBuf::new("/srv/site/about.html").unwrap());
+    let ext: AbsPathBuf = abs.with_extension("md");
+    assert_eq!(ext, AbsPathBuf::new("/srv/site/index.md").unwrap());
+    let joined: AbsPathBuf = parent.try_join("a").unwrap();
+    assert_eq!(joined, AbsPathBuf::new("/srv/site/a").unwrap());
+
+    let root = AbsPathBuf::new("/").unwrap();
+    assert_eq!(root.parent(), None);
+    let canonical: AbsPathBuf = root.canonicalize().unwrap();
+    assert_eq!(canonical, root);
+}
+
+#[test]
+fn borrowed_constructor() {
+    let owned = AbsPathBuf::new("x").unwrap();
+    let path: &std::path::Path = &owned;
+    let abs = AbsPath::new(path).unwrap();
+    assert_eq!(abs, &*owned);
+    assert!(std::ptr::eq(abs.as_os_str(), path.as_os_str()));
+
+    let err = AbsPath::new("relative/path").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(AbsPath::new("").is_err());
+}
+
+#[test]
+fn into_path_buf_reuses_buffer() {
+    let abs = AbsPathBuf::new("some/file").unwrap();
+    let ptr = abs.as_os_str().as_encoded_bytes().as_ptr();
+    let path_buf = abs.clone().into_path_buf();
+    assert_eq!(path_buf, abs.as_path());
+    let path_buf: PathBuf = abs.into();
+    assert_eq!(path_buf.as_os_str().as_encoded_bytes().as_ptr(), ptr);
+}
+
+#[cfg(unix)]
+#[test]
+fn display_matches_path_display() {
+    let abs = AbsPathBuf::new("/tmp/a b").unwrap();
+    assert_eq!(abs.to_string(), "/tmp/a b");
+    assert_eq!(format!("{:>10}", &*abs), "  /tmp/a b");
+    assert_eq!(format!("{abs}"), abs.as_path().display().to_string());
+}
+
+#[cfg(unix)]
+#[test]
+fn hash_and_ord_agree_with_path() {
+    use std::collections::{BTreeSet, HashMap, hash_map::DefaultHasher};
+    use std::hash::{Hash, Hasher};
+    use std::path::Path;
+
+    fn hash<T: Hash + ?Sized>(t: &T) -> u64 {
+        let mut h = DefaultHasher::new();
+        t.hash(&mut h);
+        h.finish()
+    }
+
+    let a = AbsPathBuf::new("/a/b").unwrap();
+    assert_eq!(hash(&a), hash(&*a));
+    assert_eq!(hash(&*a), hash(Path::new("/a/b")));
+
+    let mut map = HashMap::new();
+    map.insert(a.clone(), 1);
+    assert_eq!(map.get(&*a), Some(&1));
+
+    let set: BTreeSet<AbsPathBuf> = ["/a/b/c", "/a-b", "/a/b"]
+        .into_iter()
+        .map(|p| AbsPathBuf::new(p).unwrap())
+        .collect();
+    let order: Vec<&Path> = set.iter().map(|p| &***p).collect();
+    assert_eq!(
+        order,
+        [Path::new("/a/b"), Path::new("/a/b/c"), Path::new("/a-b")]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn compares_with_std_and_strings() {
+    use std::{ffi::OsStr, path::Path};
+
+    let etc = AbsPathBuf::new("/etc").unwrap();
+    let borrowed: &dirge::AbsPath = &etc;
+    assert_eq!(etc, Path::new("/etc"));
+    assert_eq!(Path::new("/etc"), etc);
+    assert_eq!(etc, PathBuf::from("/etc/"));
+    assert_eq!(*borrowed, *Path::new("/etc"));
+    assert_eq!(borrowed, PathBuf::from("/etc"));
+    assert_eq!(etc, "/etc");
+    assert_eq!("/etc", etc);
+    assert_eq!(borrowed, "/etc");
+    assert_eq!(etc, String::from("/etc"));
+    assert_eq!(etc, OsStr::new("/etc"));
+    assert_eq!(etc, borrowed);
+    assert_ne!(etc, "/usr");
+}
+
+#[cfg(unix)]
+#[test]
+fn slice_and_vec_casts() {
+    let paths = vec![
+        AbsPathBuf::new("/a").unwrap(),
+        AbsPathBuf::new("/b").unwrap(),
+    ];
+    let std_paths = AbsPathBuf::cast_slice(&paths);
+    assert_eq!(std_paths, [PathBuf::from("/a"), PathBuf::from("/b")]);
+
+    let ptr = paths.as_ptr() as *const PathBuf;
+    let std_paths = AbsPathBuf::into_path_buf_vec(paths);
+    assert_eq!(std_paths.as_ptr(), ptr);
+
+    let back = AbsPathBuf::try_cast_slice(&std_paths).unwrap();
+    assert_eq!(back[1], "/b");
+    let back = AbsPathBuf::try_from_path_buf_vec(std_paths).unwrap();
+    assert_eq!(back.as_ptr() as *const PathBuf, ptr);
+
+    let mixed = vec![PathBuf::from("/a"), PathBuf::from("b")];
+    let err = AbsPathBuf::try_cast_slice(&mixed).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(AbsPathBuf::try_from_path_buf_vec(mixed).is_err());
+}
+
+#[test]
+fn from_str_matches_new() {
+    let parsed: AbsPathBuf = "some/file".parse().unwrap();
+    assert_eq!(parsed, AbsPathBuf::new("some/file").unwrap());
+    assert!(parsed.is_absolute());
+}
+
+#[cfg(unix)]
+#[test]
+fn try_from_std_types() {
+    let buf = PathBuf::from("/a/b");
+    let ptr = buf.as_os_str().as_encoded_bytes().as_ptr();
+    let abs = AbsPathBuf::try_from(buf).unwrap();
+    assert_eq!(abs.as_os_str().as_encoded_bytes().as_ptr(), ptr);
+
+    assert_eq!(
+        AbsPathBuf::try_from(std::path::Path::new("/a")).unwrap(),
+        "/a"
+    );
+    assert_eq!(AbsPathBuf::try_from(String::from("/a")).unwrap(), "/a");
+    assert_eq!(
+        AbsPathBuf::try_from(std::ffi::OsString::from("/a")).unwrap(),
+        "/a"
+    );
+
+    let err = AbsPathBuf::try_from(PathBuf::from("relative")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let converted: Result<AbsPathBuf, _> = String::from("relative").try_into();
+    assert!(converted.is_err());
+}
+
+#[test]
+fn as_ref_os_str() {
+    let abs = AbsPathBuf::new("some/file").unwrap();
+    let mut cmd = std::process::Command::new("ls");
+    cmd.arg(&abs).arg(&*abs);
+    let args: Vec<_> = cmd.get_args().collect();
+    assert_eq!(args, [abs.as_os_str(), abs.as_os_str()]);
+}
+
+#[cfg(unix)]
+#[test]
+fn typed_joins_compose() {
+    use dirge::{NormPathBuf, RelPath, RelPathBuf};
+
+    let base = AbsPathBuf::new("/srv").unwrap();
+    let rel = RelPathBuf::new("site").unwrap();
+    let nested: RelPathBuf = rel.join(RelPath::new("css/a.css").unwrap());
+    let joined: AbsPathBuf = base.join(&nested);
+    assert_eq!(joined, "/srv/site/css/a.css");
+    let from_norm: AbsPathBuf = joined
+        .try_join(NormPathBuf::new("../b.css").unwrap())
+        .unwrap();
+    assert_eq!(from_norm, "/srv/site/css/a.css/../b.css");
+}
+
+#[test]
+fn cow_str_roundtrip() {
+    use std::borrow::Cow;
+
+    let abs = AbsPathBuf::new("some/file").unwrap();
+    let s = abs.to_cow_str().unwrap();
+    assert!(matches!(s, Cow::Borrowed(_)));
+    assert_eq!(AbsPathBuf::from_utf8_cow(s).unwrap(), abs);
+
+    let s = abs.to_cow_str().unwrap().into_owned();
+    let ptr = s.as_ptr();
+    let owned = AbsPathBuf::from_utf8_cow(Cow::Owned(s)).unwrap();
+    assert_eq!(owned.as_os_str().as_encoded_bytes().as_ptr(), ptr);
+    assert_eq!(owned, abs);
+    assert_eq!(
+        AbsPathBuf::from_utf8_cow(Cow::Owned("some/file".into())).unwrap(),
+        abs
+    );
+}
+
+#[cfg(all(unix, not(feature = "utf8-strict")))]
+#[test]
+fn cow_str_non_utf8_keeps_bytes() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let abs = AbsPathBuf::new(OsStr::from_bytes(b"/a\xFFb")).unwrap();
+    let err = abs.to_cow_str().unwrap_err();
+    assert_eq!(err.as_bytes(), b"/a\xFFb");
+    assert_eq!(err.to_string(), "path is not valid UTF-8: /a\\xFFb");
+    let io: std::io::Error = err.into();
+    assert_eq!(io.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(unix)]
+#[test]
+fn try_join_rejects_absolute() {
+    let root = AbsPathBuf::new("/srv").unwrap();
+    assert_eq!(root.try_join("a/../b").unwrap(), "/srv/a/../b");
+    let err = root.try_join("/etc").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().starts_with("/etc:"));
+}
+
+#[cfg(unix)]
+#[test]
+fn parent_chains_stay_typed() {
+    let abs = AbsPathBuf::new("/a/b").unwrap();
+    let grandparent: &AbsPath = abs.parent().and_then(AbsPath::parent).unwrap();
+    assert_eq!(grandparent, "/");
+    assert_eq!(grandparent.parent(), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn set_extension_keeps_type() {
+    let mut abs = AbsPathBuf::new("/srv/index.html").unwrap();
+    assert!(abs.set_extension("md"));
+    assert_eq!(abs, "/srv/index.md");
+    let with: AbsPathBuf = abs.with_extension("");
+    assert_eq!(with, "/srv/index");
+
+    let mut root = AbsPathBuf::new("/").unwrap();
+    assert!(!root.set_extension("x"));
+    assert_eq!(root, "/");
+}
+
+#[cfg(unix)]
+#[test]
+fn file_name_replacement_rejects_non_segments() {
+    let mut abs = AbsPathBuf::new("/srv/index.html").unwrap();
+    abs.set_file_name("about.html").unwrap();
+    assert_eq!(abs, "/srv/about.html");
+    for bad in ["", ".", "..", "a/b", "../../etc", "/etc"] {
+        let err = abs.set_file_name(bad).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(abs.with_file_name(bad).is_err(), "{bad}");
+    }
+    assert_eq!(abs, "/srv/about.html");
+}
+
+#[cfg(unix)]
+#[test]
+fn strip_prefix_is_relative() {
+    let file = AbsPathBuf::new("/src/app/Cargo.toml").unwrap();
+    let rest: &RelPath = file.strip_prefix(AbsPath::new("/src").unwrap()).unwrap();
+    assert_eq!(rest, "app/Cargo.toml");
+    assert!(file.strip_prefix(&file).unwrap().is_empty());
+    assert!(file.strip_prefix(AbsPath::new("/sr").unwrap()).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn relative_to_climbs_with_parent_components() {
+    let abs = |p| AbsPathBuf::new(p).unwrap();
+    let lib = abs("/srv/shared/lib.so");
+    assert_eq!(
+        lib.relative_to(abs("/srv/app/bin")).unwrap(),
+        "../../shared/lib.so"
+    );
+    assert_eq!(lib.relative_to(abs("/srv")).unwrap(), "shared/lib.so");
+    assert_eq!(
+        lib.relative_to(abs("/srv/./x/../shared/")).unwrap(),
+        "lib.so"
+    );
+    assert_eq!(lib.relative_to(&lib).unwrap(), ".");
+    assert_eq!(abs("/").relative_to(abs("/a/b")).unwrap(), "../..");
+}
+
+#[cfg(unix)]
+#[test]
+fn try_join_rejects_instead_of_replacing() {
+    let mut root = AbsPathBuf::new("/srv").unwrap();
+    let err = root.try_join("/etc/passwd").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(root.try_push("/etc").is_err());
+    assert_eq!(root, "/srv");
+}
+
+#[cfg(windows)]
+#[test]
+fn try_join_rejects_drive_relative() {
+    let mut root = AbsPathBuf::new(r"C:\srv").unwrap();
+    for bad in ["D:foo", r"\foo"] {
+        assert!(root.try_join(bad).is_err(), "{bad}");
+        assert!(root.try_push(bad).is_err(), "{bad}");
+        assert!(RelPath::new(bad).is_err(), "{bad}");
+    }
+    assert_eq!(root, r"C:\srv");
+}
+
+#[test]
+fn display_from_str_round_trip() {
+    #[cfg(unix)]
+    let paths = [
+        "/",
+        "/etc/hosts",
+        "/srv/site/",
+        "/a/../b/./c",
+        "/home/user/My Documents/r\u{e9}sum\u{e9}.pdf",
+        "/tmp/\u{1f600}/.hidden",
+    ];
+    #[cfg(windows)]
+    let paths = [
+        r"C:\",
+        r"C:\Windows\System32",
+        r"C:\Users\user\Documents\",
+        "\\\\server\\share\\r\u{e9}sum\u{e9}.pdf",
+        r"\\?\C:\a\..\b",
+    ];
+    for path in paths {
+        let abs = AbsPathBuf::new(path).unwrap();
+        let parsed: AbsPathBuf = abs.to_string().parse().unwrap();
+        assert_eq!(parsed.as_os_str(), abs.as_os_str(), "{path}");
+    }
+}
+
+#[test]
+#[cfg(all(unix, not(feature = "utf8-strict")))]
+fn display_is_lossy_for_non_utf8() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let abs = AbsPathBuf::new(OsStr::from_bytes(b"/caf\xe9")).unwrap();
+    assert_eq!(abs.to_string(), "/caf\u{fffd}");
+    let parsed: AbsPathBuf = abs.to_string().parse().unwrap();
+    assert_ne!(parsed, abs);
+}
@@ -0,0 +1,83 @@
+use dirge::{
+    AbsPathBuf, RelPathBuf,
+    algo::{common_abs_prefix, common_prefix},
+};
+use std::path::{Component, PathBuf};
+
+fn rel(s: &str) -> RelPathBuf {
+    RelPathBuf::new(s).unwrap()
+}
+
+#[test]
+fn common_prefix_basics() {
+    assert_eq!(common_prefix(Vec::<RelPathBuf>::new()), rel(""));
+    assert_eq!(common_prefix([rel("a/b")]), rel("a/b"));
+    assert_eq!(common_prefix([rel("a/b/c"), rel("a/b/d")]), rel("a/b"));
+    assert_eq!(
+        common_prefix([rel("src/lib"), rel("src/library")]),
+        rel("src")
+    );
+    assert_eq!(common_prefix([rel("a"), rel("b")]), rel(""));
+    assert_eq!(common_prefix([rel("a//b/"), rel("a/b/c")]), rel("a/b"));
+}
+
+#[test]
+fn common_prefix_matches_pairwise_definition() {
+    // Every subset of a small universe, checked against a naive definition.
+    let universe = ["a", "a/b", "a/b/c", "a/bc", "a/b/d", "b/c", "../a", "."].map(rel);
+    for mask in 1u32..(1 << universe.len()) {
+        let subset: Vec<&RelPathBuf> = universe
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask & (1 << i) != 0)
+            .map(|(_, p)| p)
+            .collect();
+
+        let components: Vec<Vec<Component<'_>>> =
+            subset.iter().map(|p| p.components().collect()).collect();
+        let expected: PathBuf = components[0]
+            .iter()
+            .enumerate()
+            .take_while(|(i, c)| components.iter().all(|other| other.get(*i) == Some(c)))
+            .map(|(_, c)| c.as_os_str())
+            .collect();
+
+        let actual = common_prefix(subset.iter().copied());
+        assert_eq!(actual.as_path(), expected, "{subset:?}");
+        assert!(subset.iter().all(|p| p.starts_with(&actual)));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn common_abs_prefix_unix() {
+    let abs = |s: &str| AbsPathBuf::new(s).unwrap();
+    assert_eq!(common_abs_prefix(Vec::<AbsPathBuf>::new()), None);
+    assert_eq!(
+        common_abs_prefix([abs("/home/a/x"), abs("/home/a/y"), abs("/home/ab")]),
+        Some(abs("/home"))
+    );
+    assert_eq!(
+        common_abs_prefix([abs("/usr"), abs("/etc")]),
+        Some(abs("/"))
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn common_abs_prefix_windows() {
+    let abs = |s: &str| AbsPathBuf::new(s).unwrap();
+    assert_eq!(
+        common_abs_prefix([abs(r"C:\Users\a"), abs(r"C:\Users\b")]),
+        Some(abs(r"C:\Users"))
+    );
+    assert_eq!(common_abs_prefix([abs(r"C:\a"), abs(r"D:\a")]), None);
+    assert_eq!(
+        common_abs_prefix([abs(r"\\server\share\a"), abs(r"\\server\other\a")]),
+        None
+    );
+    assert_eq!(
+        common_abs_prefix([abs(r"C:\a"), abs(r"C:\b")]),
+        Some(abs(r"C:\"))
+    );
+}
@@ -0,0 +1,32 @@
+use dirge::{AbsPathBuf, AnyPath, Kind, NormPathBuf, PathValue, RelPathBuf};
+use std::path::Path;
+
+#[test]
+fn heterogeneous_collection() {
+    let paths: Vec<Box<dyn AnyPath>> = vec![
+        Box::new(AbsPathBuf::new("/etc").unwrap()),
+        Box::new(RelPathBuf::new("src").unwrap()),
+        Box::new(NormPathBuf::new("a/../b").unwrap()),
+    ];
+
+    let kinds: Vec<Kind> = paths.iter().map(|p| p.any_kind()).collect();
+    assert_eq!(kinds, [Kind::Absolute, Kind::Relative, Kind::Normalized]);
+    assert_eq!(paths[2].any_path(), Path::new("b"));
+    assert_eq!(format!("{:?}", paths[1]), format!("{:?}", Path::new("src")));
+}
+
+#[test]
+fn to_path_value() {
+    let abs = AbsPathBuf::new("/etc").unwrap();
+    assert_eq!(abs.to_path_value(), PathValue::Abs(abs.clone()));
+
+    let norm = NormPathBuf::new("a/./b").unwrap();
+    assert_eq!((*norm).to_path_value(), PathValue::Norm(norm.clone()));
+}
+
+#[test]
+fn classifies() {
+    assert_eq!(PathValue::new("/etc").unwrap().kind(), Kind::Absolute);
+    assert_eq!(PathValue::new("etc").unwrap().kind(), Kind::Relative);
+    assert_eq!(PathValue::new("etc").unwrap().as_path(), Path::new("etc"));
+}
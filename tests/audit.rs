@@ -0,0 +1,55 @@
+use dirge::{AbsPathBuf, AuditIssue};
+
+mod common;
+
+use common::scratch;
+
+#[test]
+fn missing_components_are_skipped() {
+    let tmp = scratch("missing");
+    let path = tmp.try_join("not/there").unwrap();
+    let findings = path.audit_ancestors().unwrap();
+    assert!(findings.iter().all(|f| f.path.exists()));
+}
+
+#[test]
+#[cfg(unix)]
+fn reports_symlinks_and_writable_dirs() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let base = scratch("findings");
+    let open = base.try_join("open").unwrap();
+    std::fs::create_dir(&open).unwrap();
+    std::fs::set_permissions(&open, std::fs::Permissions::from_mode(0o777)).unwrap();
+    std::os::unix::fs::symlink(&open, open.try_join("link").unwrap()).unwrap();
+
+    let findings = open.try_join("link").unwrap().audit_ancestors().unwrap();
+    let issues_for = |p: &AbsPathBuf| -> Vec<AuditIssue> {
+        findings
+            .iter()
+            .filter(|f| &f.path == p)
+            .map(|f| f.issue)
+            .collect()
+    };
+
+    assert_eq!(issues_for(&open), [AuditIssue::WorldWritable]);
+    assert_eq!(
+        issues_for(&open.try_join("link").unwrap()),
+        [AuditIssue::Symlink]
+    );
+    assert!(issues_for(&base).is_empty());
+}
+
+#[test]
+#[cfg(unix)]
+fn sticky_directories_are_not_reported() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = scratch("sticky");
+    let sticky = tmp.try_join("shared").unwrap();
+    std::fs::create_dir(&sticky).unwrap();
+    std::fs::set_permissions(&sticky, std::fs::Permissions::from_mode(0o1777)).unwrap();
+
+    let findings = sticky.audit_ancestors().unwrap();
+    assert!(findings.iter().all(|f| f.path != sticky));
+}
@@ -0,0 +1,57 @@
+#![cfg(unix)]
+
+use dirge::{AbsPathBuf, bulk};
+use std::num::NonZeroUsize;
+
+fn abs(s: &str) -> AbsPathBuf {
+    AbsPathBuf::new(s).unwrap()
+}
+
+#[test]
+fn rewrites_component_wise() {
+    let mut paths = vec![abs("/ws/a"), abs("/ws-old/a"), abs("/ws"), abs("/")];
+    assert_eq!(
+        bulk::rewrite_prefix(&mut paths, abs("/ws"), abs("/new/ws")),
+        2
+    );
+    assert_eq!(
+        paths,
+        [abs("/new/ws/a"), abs("/ws-old/a"), abs("/new/ws"), abs("/")]
+    );
+}
+
+#[test]
+fn rewrites_to_root() {
+    let mut paths = vec![abs("/ws/a/b")];
+    bulk::rewrite_prefix(&mut paths, abs("/ws"), abs("/"));
+    assert_eq!(paths, [abs("/a/b")]);
+}
+
+#[test]
+fn shorter_prefix_keeps_allocation() {
+    let mut paths = vec![abs("/a/long/workspace/root/src/lib.rs")];
+    let before = paths[0].as_os_str().as_encoded_bytes().as_ptr();
+    bulk::rewrite_prefix(&mut paths, abs("/a/long/workspace/root"), abs("/ws"));
+    assert_eq!(paths, [abs("/ws/src/lib.rs")]);
+    assert_eq!(paths[0].as_os_str().as_encoded_bytes().as_ptr(), before);
+}
+
+#[test]
+fn parallel_matches_sequential() {
+    let make = || -> Vec<_> {
+        (0..1000)
+            .map(|i| abs(&format!("/{}/f{i}", if i % 3 == 0 { "ws" } else { "x" })))
+            .collect()
+    };
+    let mut seq = make();
+    let mut par = make();
+    let n = bulk::rewrite_prefix(&mut seq, abs("/ws"), abs("/moved"));
+    let m = bulk::rewrite_prefix_parallel(
+        &mut par,
+        abs("/ws"),
+        abs("/moved"),
+        NonZeroUsize::new(4).unwrap(),
+    );
+    assert_eq!((n, &seq), (m, &par));
+    assert_eq!(n, 334);
+}
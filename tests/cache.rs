@@ -0,0 +1,130 @@
+use dirge::{
+    RelPath, RelPathBuf,
+    cache::{CanonCache, PathCache},
+};
+
+mod common;
+
+use common::scratch;
+
+fn rel(s: &str) -> RelPathBuf {
+    RelPathBuf::new(s).unwrap()
+}
+
+fn cache() -> PathCache<u32> {
+    let mut cache = PathCache::new();
+    for (i, p) in ["a", "a/b", "a/b/c", "a-b", "ab", "b"]
+        .into_iter()
+        .enumerate()
+    {
+        cache.insert(rel(p), i as u32);
+    }
+    cache
+}
+
+#[test]
+fn keys_are_normalized() {
+    let mut cache = PathCache::new();
+    assert_eq!(cache.insert(rel("a/./b"), 1), None);
+    assert_eq!(cache.insert(rel("a/c/../b"), 2), Some(1));
+    assert_eq!(cache.get(rel("a/b/")), Some(&2));
+    assert_eq!(*cache.get_or_insert_with(rel("x"), || 7), 7);
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn invalidate_prefix_removes_subtree_only() {
+    let mut cache = cache();
+    let evicted = cache.invalidate_prefix(rel("a"));
+    assert_eq!(evicted, [(rel("a"), 0), (rel("a/b"), 1), (rel("a/b/c"), 2)]);
+    let left: Vec<&RelPath> = cache.iter().map(|(k, _)| k).collect();
+    assert_eq!(left, [&*rel("a-b"), &*rel("ab"), &*rel("b")]);
+}
+
+#[test]
+fn invalidate_prefix_of_missing_directory() {
+    let mut cache = cache();
+    assert_eq!(cache.invalidate_prefix(rel("a/x")), []);
+    assert_eq!(cache.invalidate_prefix(rel("a/b/./")).len(), 2);
+    assert_eq!(cache.len(), 4);
+}
+
+#[test]
+fn invalidate_root_and_single() {
+    let mut cache = cache();
+    assert_eq!(cache.invalidate(rel("a/b")), Some(1));
+    assert_eq!(cache.get(rel("a/b/c")), Some(&2));
+    assert_eq!(cache.invalidate_prefix(rel(".")).len(), 5);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn canon_cache_evicts_least_recently_used() {
+    let dir = scratch("lru");
+    for name in ["a", "b", "c"] {
+        std::fs::create_dir(dir.try_join(name).unwrap()).unwrap();
+    }
+
+    let mut cache = CanonCache::new(2);
+    cache.canonicalize(dir.try_join("a").unwrap()).unwrap();
+    cache.canonicalize(dir.try_join("b").unwrap()).unwrap();
+    cache.canonicalize(dir.try_join("a").unwrap()).unwrap();
+    cache.canonicalize(dir.try_join("c").unwrap()).unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(cache.invalidate(dir.try_join("a").unwrap()));
+    assert!(!cache.invalidate(dir.try_join("b").unwrap()));
+
+    assert_eq!(cache.invalidate_prefix(&dir), 1);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn canon_cache_evicts_after_invalidation() {
+    let dir = scratch("lru-invalidated");
+    for name in ["a", "b", "c", "d"] {
+        std::fs::create_dir(dir.try_join(name).unwrap()).unwrap();
+    }
+
+    let mut cache = CanonCache::new(2);
+    cache.canonicalize(dir.try_join("a").unwrap()).unwrap();
+    cache.canonicalize(dir.try_join("b").unwrap()).unwrap();
+    cache.invalidate(dir.try_join("a").unwrap());
+    cache.canonicalize(dir.try_join("c").unwrap()).unwrap();
+    cache.canonicalize(dir.try_join("d").unwrap()).unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(!cache.invalidate(dir.try_join("b").unwrap()));
+    assert!(cache.invalidate(dir.try_join("c").unwrap()));
+    assert!(cache.invalidate(dir.try_join("d").unwrap()));
+}
+
+#[cfg(unix)]
+#[test]
+fn canon_cache_notices_retargeted_symlink() {
+    let dir = scratch("retarget");
+    std::fs::create_dir(dir.try_join("one").unwrap()).unwrap();
+    std::fs::create_dir(dir.try_join("two").unwrap()).unwrap();
+    let link = dir.try_join("link").unwrap();
+    std::os::unix::fs::symlink(dir.try_join("one").unwrap(), &link).unwrap();
+
+    let mut cache = CanonCache::new(8);
+    assert_eq!(
+        cache.canonicalize(&link).unwrap(),
+        dir.try_join("one").unwrap()
+    );
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    std::fs::remove_file(&link).unwrap();
+    std::os::unix::fs::symlink(dir.try_join("two").unwrap(), &link).unwrap();
+    assert_eq!(
+        cache.canonicalize(&link).unwrap(),
+        dir.try_join("two").unwrap()
+    );
+}
+
+#[test]
+fn canon_cache_zero_capacity() {
+    let dir = scratch("zero");
+    let mut cache = CanonCache::new(0);
+    assert_eq!(cache.canonicalize(&dir).unwrap(), *dir);
+    assert!(cache.is_empty());
+}
@@ -0,0 +1,31 @@
+use dirge::cargo;
+use std::path::Path;
+
+#[test]
+fn manifest_dir_matches_compile_time() {
+    let dir = cargo::manifest_dir().unwrap();
+    assert_eq!(dir.as_path(), Path::new(env!("CARGO_MANIFEST_DIR")));
+}
+
+#[test]
+fn out_dir_outside_build_script() {
+    // This crate has no build script, so Cargo never sets OUT_DIR for its tests.
+    let err = cargo::out_dir().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("OUT_DIR"));
+}
+
+#[cfg(feature = "cargo-locate")]
+#[test]
+fn workspace_root_contains_manifest() {
+    let root = cargo::workspace_root().unwrap();
+    assert_eq!(root.as_path(), Path::new(env!("CARGO_MANIFEST_DIR")));
+    assert!(root.try_join("Cargo.toml").unwrap().is_file());
+}
+
+#[cfg(feature = "cargo-locate")]
+#[test]
+fn target_dir_is_absolute() {
+    let target = cargo::target_dir().unwrap();
+    assert!(target.is_absolute());
+}
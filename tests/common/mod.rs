@@ -0,0 +1,50 @@
+//! Fixtures shared by the integration tests; each test file declares `mod common;`.
+
+#![allow(dead_code)]
+
+use dirge::{AbsPath, AbsPathBuf};
+use std::{ops::Deref, path::Path};
+
+/// A fresh, empty directory for one test, removed again when dropped.
+#[derive(Debug)]
+pub struct Scratch(AbsPathBuf);
+
+/// Creates `dirge-{suite}-{pid}-{name}` under the system temp directory, where the suite is the
+/// test file's name. A directory left behind by an aborted run is emptied first. The path is
+/// canonical, so it compares equal to paths the tests resolve below it.
+pub fn scratch(name: &str) -> Scratch {
+    let dir = std::env::temp_dir().join(format!(
+        "dirge-{}-{}-{name}",
+        env!("CARGO_CRATE_NAME"),
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    Scratch(AbsPathBuf::new(dir.canonicalize().unwrap()).unwrap())
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+impl Deref for Scratch {
+    type Target = AbsPathBuf;
+
+    fn deref(&self) -> &AbsPathBuf {
+        &self.0
+    }
+}
+
+impl AsRef<AbsPath> for Scratch {
+    fn as_ref(&self) -> &AbsPath {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for Scratch {
+    fn as_ref(&self) -> &Path {
+        self.0.as_ref()
+    }
+}
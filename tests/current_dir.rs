@@ -0,0 +1,33 @@
+//! Changes the current directory outside any guard, so it lives in its own test binary where
+//! no other test can observe that.
+
+use dirge::fs::CurrentDirGuard;
+use std::io;
+
+mod common;
+
+use common::scratch;
+
+#[test]
+fn failed_restore_on_drop_is_reported_by_the_next_guard() {
+    let root = scratch("restore-on-drop");
+    let gone = root.try_join("gone").unwrap();
+    std::fs::create_dir(&gone).unwrap();
+    std::env::set_current_dir(&gone).unwrap();
+
+    let guard = CurrentDirGuard::change_to(&root).unwrap();
+    std::fs::remove_dir(&gone).unwrap();
+    drop(guard);
+
+    let Err(err) = CurrentDirGuard::change_to(&root) else {
+        panic!("the failed restore was not reported");
+    };
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("gone"), "{err}");
+
+    // The error is reported once; the directory stayed at `root`, which is what is restored.
+    let guard = CurrentDirGuard::change_to(&root).unwrap();
+    assert_eq!(guard.previous(), &**root);
+    guard.restore().unwrap();
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+}
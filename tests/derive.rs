@@ -0,0 +1,103 @@
+#![cfg(feature = "derive")]
+#![forbid(unsafe_code)]
+
+use dirge::{AbsPath, AbsPathBuf, DirgePath, RelPath, RelPathBuf};
+use std::{borrow::Borrow, io, path::Path};
+
+fn under_src(p: &RelPath) -> io::Result<()> {
+    if p.starts_with("src") {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not under src/",
+        ))
+    }
+}
+
+#[derive(DirgePath, Clone, PartialEq, Eq)]
+#[dirge(borrowed = SourceRelPath, validate = under_src)]
+struct SourceRelPathBuf(RelPathBuf);
+
+#[derive(DirgePath, Clone, PartialEq, Eq)]
+#[dirge(borrowed = InstallAbsPath)]
+struct InstallAbsPathBuf(AbsPathBuf);
+
+#[test]
+fn basic() {
+    let src = SourceRelPathBuf::new("src/lib.rs").unwrap();
+    let _: &SourceRelPath = &src;
+    let _: &RelPath = &src;
+    let _: &Path = src.as_ref();
+}
+
+#[test]
+fn validator_rejects() {
+    let err = SourceRelPathBuf::new("tests/abs.rs").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn inner_invariant_still_applies() {
+    assert!(SourceRelPathBuf::new("/src/lib.rs").is_err());
+}
+
+#[test]
+fn from_inner() {
+    let inner = RelPathBuf::new("src/lib.rs").unwrap();
+    let borrowed = SourceRelPath::from_inner(&inner).unwrap();
+    assert_eq!(borrowed.to_owned().into_inner(), inner);
+
+    assert!(SourceRelPathBuf::from_inner(RelPathBuf::new("docs").unwrap()).is_err());
+}
+
+#[test]
+fn borrow_and_to_owned() {
+    let src = SourceRelPathBuf::new("src/lib.rs").unwrap();
+    let borrowed: &SourceRelPath = src.borrow();
+    assert_eq!(borrowed.to_owned(), src);
+}
+
+#[test]
+fn without_validator() {
+    let install = InstallAbsPathBuf::new("/opt/tool").unwrap();
+    let _: &InstallAbsPath = &install;
+    let _: &AbsPath = &install;
+}
+
+#[test]
+fn debug_is_transparent() {
+    let src = SourceRelPathBuf::new("src/lib.rs").unwrap();
+    assert_eq!(
+        format!("{:?}", src),
+        format!("{:?}", Path::new("src/lib.rs"))
+    );
+}
+
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::*;
+    use serde_test::{Token, assert_de_tokens_error, assert_ser_tokens, assert_tokens};
+
+    #[derive(DirgePath, Clone, PartialEq, Eq)]
+    #[dirge(borrowed = SerdeSourcePath, validate = under_src, serde)]
+    struct SerdeSourcePathBuf(RelPathBuf);
+
+    #[test]
+    fn round_trip() {
+        let src = SerdeSourcePathBuf::new("src/lib.rs").unwrap();
+        assert_tokens(&src, &[Token::Str("src/lib.rs")]);
+
+        let borrowed: &SerdeSourcePath = &src;
+        assert_ser_tokens(&borrowed, &[Token::Str("src/lib.rs")]);
+    }
+
+    #[test]
+    fn deserialize_validates() {
+        assert_de_tokens_error::<SerdeSourcePathBuf>(&[Token::Str("docs/x.md")], "not under src/");
+        assert_de_tokens_error::<SerdeSourcePathBuf>(
+            &[Token::Str("/src/x.rs")],
+            "path must be relative",
+        );
+    }
+}
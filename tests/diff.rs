@@ -0,0 +1,120 @@
+use dirge::{RelPathBuf, diff::tree_diff};
+
+fn rel(s: &str) -> RelPathBuf {
+    RelPathBuf::new(s).unwrap()
+}
+
+#[test]
+fn tree_diff_sets() {
+    let diff = tree_diff(
+        [rel("a"), rel("b/c"), rel("b/c"), rel("e")],
+        [rel("b/c"), rel("d"), rel("e"), rel("d")],
+    );
+    assert_eq!(diff.added.iter().collect::<Vec<_>>(), [&rel("d")]);
+    assert_eq!(diff.removed.iter().collect::<Vec<_>>(), [&rel("a")]);
+    assert_eq!(
+        diff.common.iter().collect::<Vec<_>>(),
+        [&rel("b/c"), &rel("e")]
+    );
+    assert!(!diff.is_unchanged());
+}
+
+#[test]
+fn tree_diff_unchanged() {
+    let diff = tree_diff([rel("x"), rel("y")], [rel("y"), rel("x")]);
+    assert!(diff.is_unchanged());
+    assert_eq!(diff.changes().to_string(), "");
+}
+
+#[test]
+fn tree_diff_rendering_is_component_ordered() {
+    let diff = tree_diff([rel("a/b"), rel("a-b")], [rel("a-b"), rel("a/c")]);
+    assert_eq!(diff.to_string(), "- a/b\n+ a/c\n  a-b\n");
+    assert_eq!(diff.changes().to_string(), "- a/b\n+ a/c\n");
+}
+
+mod renames {
+    use super::rel;
+    use dirge::diff::{Rename, detect_renames};
+    use std::collections::BTreeMap;
+
+    fn manifest(entries: &[(&str, u32)]) -> BTreeMap<dirge::RelPathBuf, u32> {
+        entries.iter().map(|&(p, d)| (rel(p), d)).collect()
+    }
+
+    fn rename(from: &str, to: &str) -> Rename {
+        Rename {
+            from: rel(from),
+            to: rel(to),
+        }
+    }
+
+    #[test]
+    fn matches_by_digest() {
+        let old = manifest(&[("a.txt", 1), ("b.txt", 2), ("kept", 3)]);
+        let new = manifest(&[("x/a.txt", 1), ("c.txt", 9), ("kept", 3)]);
+        assert_eq!(detect_renames(&old, &new), [rename("a.txt", "x/a.txt")]);
+    }
+
+    #[test]
+    fn kept_paths_are_copies_not_moves() {
+        let old = manifest(&[("a", 1)]);
+        let new = manifest(&[("a", 1), ("b", 1)]);
+        assert!(detect_renames(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn duplicates_pair_by_similarity() {
+        let old = manifest(&[("src/a/LICENSE", 7), ("src/b/LICENSE", 7)]);
+        let new = manifest(&[("lib/b/LICENSE", 7), ("src/a/COPYING", 7)]);
+        assert_eq!(
+            detect_renames(&old, &new),
+            [
+                rename("src/a/LICENSE", "lib/b/LICENSE"),
+                rename("src/b/LICENSE", "src/a/COPYING"),
+            ]
+        );
+
+        let old = manifest(&[("x/a.rs", 7), ("y/b.md", 7)]);
+        let new = manifest(&[("z/b.md", 7), ("z/a.rs", 7), ("z/c.rs", 7)]);
+        assert_eq!(
+            detect_renames(&old, &new),
+            [rename("x/a.rs", "z/a.rs"), rename("y/b.md", "z/b.md")]
+        );
+    }
+
+    #[test]
+    fn many_identical_digests_pair_by_name() {
+        // Every file is empty, so all 5000 share one digest.
+        let old: BTreeMap<_, _> = (0..5000)
+            .map(|i| (rel(&format!("old/{i}.txt")), 0))
+            .collect();
+        let new: BTreeMap<_, _> = (0..5000)
+            .map(|i| (rel(&format!("new/{i}.txt")), 0))
+            .collect();
+        let renames = detect_renames(&old, &new);
+        assert_eq!(renames.len(), 5000);
+        assert!(
+            renames
+                .iter()
+                .all(|r| r.from.file_name() == r.to.file_name())
+        );
+    }
+
+    #[test]
+    fn many_identical_names_pair_in_path_order() {
+        let old: BTreeMap<_, _> = (0..1000)
+            .map(|i| (rel(&format!("old/{i:04}/.gitkeep")), 0))
+            .collect();
+        let new: BTreeMap<_, _> = (0..1000)
+            .map(|i| (rel(&format!("new/{i:04}/.gitkeep")), 0))
+            .collect();
+        let renames = detect_renames(&old, &new);
+        assert_eq!(renames.len(), 1000);
+        assert!(
+            renames
+                .iter()
+                .all(|r| r.from.strip_prefix("old").unwrap() == r.to.strip_prefix("new").unwrap())
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use dirge::{AbsPathBuf, encode};
+use std::io;
+
+fn abs(s: &str) -> AbsPathBuf {
+    AbsPathBuf::new(s).unwrap()
+}
+
+#[test]
+fn empty_list() {
+    assert_eq!(encode::join_list(Vec::<AbsPathBuf>::new()), "");
+    assert!(encode::split_list("").unwrap().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn round_trips_awkward_paths() {
+    let paths = [
+        abs("/a"),
+        abs("/with:colon"),
+        abs("/back\\slash"),
+        abs("/trailing\\"),
+        abs("/"),
+    ];
+    let joined = encode::join_list(&paths);
+    assert_eq!(joined, r"/a:/with\:colon:/back\\slash:/trailing\\:/");
+    assert_eq!(encode::split_list(&joined).unwrap(), paths);
+}
+
+#[cfg(all(unix, not(feature = "utf8-strict")))]
+#[test]
+fn round_trips_non_utf8() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let path = AbsPathBuf::new(OsStr::from_bytes(b"/caf\xe9")).unwrap();
+    let joined = encode::join_list([&path]);
+    assert_eq!(joined, r"/caf\xE9");
+    assert_eq!(encode::split_list(&joined).unwrap(), [path]);
+}
+
+#[test]
+fn split_rejects_malformed() {
+    for input in [r"/a\q", r"/a\x4", r"/a\xzz", "/a\\", "relative"] {
+        let err = encode::split_list(input).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{input:?}");
+    }
+}
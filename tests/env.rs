@@ -0,0 +1,77 @@
+use dirge::env::PathEnv;
+use std::{io, path::Path};
+
+mod common;
+
+use common::scratch;
+
+#[test]
+fn expand_user() {
+    let dir = scratch("expand");
+    let env = PathEnv::new(&dir).with_home(dir.try_join("home").unwrap());
+    assert_eq!(
+        env.expand_user(Path::new("~").join("x")).unwrap(),
+        dir.try_join("home")
+            .unwrap()
+            .try_join("x")
+            .unwrap()
+            .as_path()
+    );
+    assert_eq!(
+        env.expand_user("~").unwrap(),
+        dir.try_join("home").unwrap().as_path()
+    );
+    assert_eq!(env.expand_user("~bob/x").unwrap(), Path::new("~bob/x"));
+    assert_eq!(env.expand_user("a/~").unwrap(), Path::new("a/~"));
+
+    let err = PathEnv::new(&dir).expand_user("~").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn absolutize_uses_snapshot_cwd() {
+    let dir = scratch("absolutize");
+    let env = PathEnv::new(&dir);
+    assert_eq!(env.absolutize("a").unwrap(), dir.try_join("a").unwrap());
+    assert_eq!(env.absolutize(&dir).unwrap(), *dir);
+    assert!(env.absolutize("").is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn find_executable_uses_snapshot_path() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch("find");
+    let bin = dir.try_join("bin").unwrap();
+    std::fs::create_dir(&bin).unwrap();
+    std::fs::write(bin.try_join("tool").unwrap(), "#!/bin/sh\n").unwrap();
+    std::fs::write(bin.try_join("data").unwrap(), "").unwrap();
+    std::fs::set_permissions(
+        bin.try_join("tool").unwrap(),
+        std::fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+
+    let env = PathEnv::new(&dir).with_path([&bin]);
+    assert_eq!(
+        env.find_executable("tool").unwrap(),
+        bin.try_join("tool").unwrap()
+    );
+    assert_eq!(
+        env.find_executable("data").unwrap_err().kind(),
+        io::ErrorKind::NotFound
+    );
+    assert_eq!(
+        env.find_executable("bin/tool").unwrap(),
+        bin.try_join("tool").unwrap()
+    );
+    assert!(PathEnv::new(&dir).find_executable("tool").is_err());
+}
+
+#[test]
+fn capture_matches_process() {
+    let env = PathEnv::capture().unwrap();
+    assert_eq!(&**env.cwd(), std::env::current_dir().unwrap());
+    assert!(env.path().iter().all(|p| p.is_absolute()));
+}
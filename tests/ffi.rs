@@ -0,0 +1,38 @@
+use dirge::{
+    AbsPathBuf, RelPathBuf,
+    ffi::{AsRawPath, RawPath, WithRawPath},
+};
+use std::io;
+
+#[cfg(unix)]
+#[test]
+fn raw_path_is_nul_terminated() {
+    let rel = RelPathBuf::new("a/b.txt").unwrap();
+    let raw = rel.to_raw_path().unwrap();
+    assert_eq!(raw.as_c_str().to_bytes_with_nul(), b"a/b.txt\0");
+}
+
+#[cfg(windows)]
+#[test]
+fn raw_path_is_nul_terminated() {
+    let rel = RelPathBuf::new("a\\b.txt").unwrap();
+    let raw = rel.to_raw_path().unwrap();
+    let expected: Vec<u16> = "a\\b.txt\0".encode_utf16().collect();
+    assert_eq!(raw.as_wide_with_nul(), expected);
+}
+
+#[test]
+fn rejects_interior_nul() {
+    let err = RawPath::new("a\0b").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn with_raw_path_converts_once() {
+    let path = AbsPathBuf::new("data.db").unwrap();
+    let cached = WithRawPath::new(path.clone()).unwrap();
+    assert_eq!(*cached, path);
+    assert_eq!(cached.raw(), &path.to_raw_path().unwrap());
+    assert_eq!(cached.raw().as_ptr(), cached.raw().as_ptr());
+    assert_eq!(cached.into_inner(), path);
+}
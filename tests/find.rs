@@ -0,0 +1,73 @@
+mod common;
+
+use common::Scratch;
+
+fn scratch(name: &str) -> Scratch {
+    let dir = common::scratch(name);
+    std::fs::create_dir_all(dir.try_join("a/b/c").unwrap()).unwrap();
+    dir
+}
+
+#[test]
+fn finds_nearest() {
+    let root = scratch("nearest");
+    std::fs::write(root.try_join("marker").unwrap(), b"").unwrap();
+    std::fs::write(root.try_join("a/marker").unwrap(), b"").unwrap();
+
+    let start = root.try_join("a/b/c").unwrap();
+    assert_eq!(
+        start.find_up("marker"),
+        Some(root.try_join("a/marker").unwrap())
+    );
+}
+
+#[test]
+fn finds_in_start_directory() {
+    let root = scratch("start");
+    std::fs::write(root.try_join("a/b/c/marker").unwrap(), b"").unwrap();
+
+    let start = root.try_join("a/b/c").unwrap();
+    assert_eq!(
+        start.find_up("marker"),
+        Some(start.try_join("marker").unwrap())
+    );
+}
+
+#[test]
+fn not_found() {
+    let root = scratch("missing");
+    assert_eq!(
+        root.try_join("a/b")
+            .unwrap()
+            .find_up("dirge-no-such-marker"),
+        None
+    );
+}
+
+#[test]
+fn any_prefers_nearest_directory_then_order() {
+    let root = scratch("any");
+    std::fs::write(root.try_join("package.json").unwrap(), b"").unwrap();
+    std::fs::write(root.try_join("a/.editorconfig").unwrap(), b"").unwrap();
+    std::fs::write(root.try_join("a/b/.git").unwrap(), b"").unwrap();
+    std::fs::write(root.try_join("a/b/.editorconfig").unwrap(), b"").unwrap();
+
+    let start = root.try_join("a/b/c").unwrap();
+    assert_eq!(
+        start.find_up_any(&["package.json", ".editorconfig", ".git"]),
+        Some(root.try_join("a/b/.editorconfig").unwrap())
+    );
+}
+
+#[test]
+fn within_limit() {
+    let root = scratch("limit");
+    std::fs::write(root.try_join("marker").unwrap(), b"").unwrap();
+
+    let start = root.try_join("a/b/c").unwrap();
+    assert_eq!(start.find_up_within(&["marker"], 3), None);
+    assert_eq!(
+        start.find_up_within(&["marker"], 4),
+        Some(root.try_join("marker").unwrap())
+    );
+}
@@ -0,0 +1,665 @@
+use dirge::{AbsPathBuf, fs};
+use std::{io, thread, time::Duration};
+
+mod common;
+
+use common::scratch;
+
+#[test]
+fn wait_for_existing_path() {
+    let dir = scratch("existing");
+    fs::wait_for(&dir, Duration::ZERO).unwrap();
+}
+
+#[test]
+fn wait_for_times_out() {
+    let tmp = scratch("timeout");
+    let missing = tmp.try_join("missing").unwrap();
+    let err = fs::wait_for(&missing, Duration::from_millis(20)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn wait_for_appearing_path() {
+    let tmp = scratch("appear");
+    let file = tmp.try_join("ready").unwrap();
+    let writer = {
+        let file = file.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            std::fs::write(&file, b"").unwrap();
+        })
+    };
+
+    fs::wait_for(&file, Duration::from_secs(10)).unwrap();
+    writer.join().unwrap();
+}
+
+#[test]
+fn wait_for_removal() {
+    let tmp = scratch("removal");
+    let file = tmp.try_join("pid").unwrap();
+    std::fs::write(&file, b"").unwrap();
+    let remover = {
+        let file = file.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            std::fs::remove_file(&file).unwrap();
+        })
+    };
+
+    fs::wait_for_removal(&file, Duration::from_secs(10)).unwrap();
+    remover.join().unwrap();
+}
+
+#[test]
+fn open_with_create_parents() {
+    let tmp = scratch("open-parents");
+    let file = tmp.try_join("a/b/c.txt").unwrap();
+    file.open_with()
+        .write(true)
+        .create(true)
+        .create_parents(true)
+        .open()
+        .unwrap();
+    assert!(file.is_file());
+}
+
+#[test]
+fn open_with_error_names_path() {
+    let tmp = scratch("open-missing");
+    let file = tmp.try_join("missing.txt").unwrap();
+    let err = file.open_with().read(true).open().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("missing.txt"));
+}
+
+#[test]
+#[cfg(unix)]
+fn open_with_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = scratch("open-mode");
+    let file = tmp.try_join("key").unwrap();
+    file.open_with()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open()
+        .unwrap();
+    let mode = file.metadata().unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn ensure_dir_secure_creates() {
+    let tmp = scratch("secure-create");
+    let dir = tmp.try_join("run/app").unwrap();
+    fs::ensure_dir_secure(&dir, 0o700).unwrap();
+    assert!(dir.is_dir());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = dir.metadata().unwrap().permissions().mode();
+        assert_eq!(mode & 0o077, 0);
+    }
+
+    // Existing, correctly owned directories are accepted.
+    fs::ensure_dir_secure(&dir, 0o700).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn ensure_dir_secure_rejects_symlinks() {
+    let base = scratch("secure-symlink");
+    let real = base.try_join("real").unwrap();
+    std::fs::create_dir(&real).unwrap();
+    std::os::unix::fs::symlink(&real, base.try_join("link").unwrap()).unwrap();
+
+    let err = fs::ensure_dir_secure(base.try_join("link/state").unwrap(), 0o700).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+#[cfg(unix)]
+fn ensure_dir_secure_rejects_world_writable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let base = scratch("secure-writable");
+    let open = base.try_join("open").unwrap();
+    std::fs::create_dir(&open).unwrap();
+    std::fs::set_permissions(&open, std::fs::Permissions::from_mode(0o777)).unwrap();
+
+    let err = fs::ensure_dir_secure(open.try_join("state").unwrap(), 0o700).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+    let err = fs::ensure_dir_secure(&open, 0o700).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn canonicalize_lenient_missing_tail() {
+    let dir = scratch("lenient-missing");
+    let canonical = dir.canonicalize().unwrap();
+
+    let lenient = dir
+        .try_join("new/./sub/../file.txt")
+        .unwrap()
+        .canonicalize_lenient()
+        .unwrap();
+    assert_eq!(lenient, canonical.try_join("new/file.txt").unwrap());
+
+    let escaped = dir
+        .try_join("new/../../x")
+        .unwrap()
+        .canonicalize_lenient()
+        .unwrap();
+    assert_eq!(escaped, canonical.parent().unwrap().try_join("x").unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn canonicalize_lenient_resolves_existing_symlinks() {
+    let dir = scratch("lenient-symlink");
+    std::fs::create_dir(dir.try_join("real").unwrap()).unwrap();
+    std::os::unix::fs::symlink(dir.try_join("real").unwrap(), dir.try_join("link").unwrap())
+        .unwrap();
+
+    let lenient = dir
+        .try_join("link/missing.txt")
+        .unwrap()
+        .canonicalize_lenient()
+        .unwrap();
+    assert_eq!(
+        lenient,
+        dir.canonicalize()
+            .unwrap()
+            .try_join("real/missing.txt")
+            .unwrap()
+    );
+}
+
+#[test]
+fn hash_tree_keys_regular_files() {
+    use std::io::Read;
+
+    let dir = scratch("hash-tree");
+    std::fs::create_dir_all(dir.try_join("sub/empty").unwrap()).unwrap();
+    std::fs::write(dir.try_join("a.txt").unwrap(), "hello").unwrap();
+    std::fs::write(dir.try_join("sub/b.txt").unwrap(), "hi").unwrap();
+
+    let sizes = fs::hash_tree(&dir, |r: &mut dyn Read| io::copy(r, &mut io::sink())).unwrap();
+    let sizes: Vec<_> = sizes
+        .iter()
+        .map(|(k, v)| (k.to_string_lossy().into_owned(), *v))
+        .collect();
+    assert_eq!(
+        sizes,
+        [("a.txt".to_owned(), 5), ("sub/b.txt".to_owned(), 2)]
+    );
+}
+
+#[test]
+fn read_dir_sorted_orders() {
+    use dirge::fs::SortOrder;
+
+    let dir = scratch("sorted");
+    for name in ["file10", "file2", "File1", "file02", "b", "a"] {
+        std::fs::write(dir.try_join(name).unwrap(), "").unwrap();
+    }
+    let names = |order| -> Vec<String> {
+        fs::read_dir_sorted(&dir, order)
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    };
+
+    assert_eq!(
+        names(SortOrder::Bytes),
+        ["File1", "a", "b", "file02", "file10", "file2"]
+    );
+    assert_eq!(
+        names(SortOrder::Natural),
+        ["File1", "a", "b", "file2", "file02", "file10"]
+    );
+    assert_eq!(
+        names(SortOrder::Human),
+        ["a", "b", "File1", "file2", "file02", "file10"]
+    );
+}
+
+#[test]
+fn sort_order_human_folds_unicode_case() {
+    use dirge::fs::SortOrder;
+    use std::ffi::OsStr;
+
+    let mut names = ["Ärger", "ärger", "Zug", "über"].map(OsStr::new);
+    names.sort_by(|a, b| SortOrder::Human.compare(a, b));
+    assert_eq!(names, ["Zug", "Ärger", "ärger", "über"]);
+}
+
+#[test]
+fn read_dir_sorted_by_custom_order() {
+    let dir = scratch("sorted-by");
+    for name in ["a", "c", "b"] {
+        std::fs::write(dir.try_join(name).unwrap(), "").unwrap();
+    }
+    let sorted = fs::read_dir_sorted_by(&dir, |a, b| b.cmp(a)).unwrap();
+    assert_eq!(
+        sorted,
+        [
+            dir.try_join("c").unwrap(),
+            dir.try_join("b").unwrap(),
+            dir.try_join("a").unwrap()
+        ]
+    );
+}
+
+#[test]
+fn read_dir_sorted_error_names_dir() {
+    let tmp = scratch("sorted-missing");
+    let missing = tmp.try_join("nope").unwrap();
+    let err = fs::read_dir_sorted(&missing, Default::default()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("nope"));
+}
+
+#[test]
+fn check_writable_directory() {
+    let dir = scratch("writable");
+    dir.check_writable().unwrap();
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+}
+
+#[test]
+fn check_writable_rejects_files_and_missing() {
+    let dir = scratch("writable-file");
+    std::fs::write(dir.try_join("f").unwrap(), "").unwrap();
+    let err = dir.try_join("f").unwrap().check_writable().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let err = dir
+        .try_join("missing")
+        .unwrap()
+        .check_writable()
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("missing"));
+}
+
+#[cfg(unix)]
+#[test]
+fn check_writable_read_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch("writable-ro");
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o555)).unwrap();
+    // Privileged users (e.g. root in CI containers) bypass permission bits.
+    let privileged = std::fs::write(dir.try_join("probe").unwrap(), "").is_ok();
+    let result = dir.check_writable();
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    match result {
+        Ok(()) => assert!(privileged),
+        Err(err) => assert_eq!(err.kind(), io::ErrorKind::PermissionDenied),
+    }
+}
+
+#[test]
+fn current_dir_guard_restores() {
+    let dir = scratch("cwd");
+    let before = std::env::current_dir().unwrap();
+    {
+        let guard = fs::CurrentDirGuard::change_to(&dir).unwrap();
+        assert_eq!(&**guard.previous(), before);
+        assert_eq!(
+            AbsPathBuf::new(std::env::current_dir().unwrap().canonicalize().unwrap()).unwrap(),
+            dir.canonicalize().unwrap()
+        );
+    }
+    assert_eq!(std::env::current_dir().unwrap(), before);
+}
+
+#[test]
+fn current_dir_guard_restores_explicitly() {
+    let dir = scratch("cwd-restore");
+    let before = std::env::current_dir().unwrap();
+    let guard = fs::CurrentDirGuard::change_to(&dir).unwrap();
+    guard.restore().unwrap();
+    assert_eq!(std::env::current_dir().unwrap(), before);
+    // The lock was released, so this does not count as nesting.
+    fs::CurrentDirGuard::change_to(&dir)
+        .unwrap()
+        .restore()
+        .unwrap();
+}
+
+#[test]
+fn current_dir_guard_serializes_threads() {
+    let dirs: Vec<_> = (0..4).map(|i| scratch(&format!("cwd-{i}"))).collect();
+    thread::scope(|s| {
+        for dir in &dirs {
+            s.spawn(move || {
+                for _ in 0..10 {
+                    let _guard = fs::CurrentDirGuard::change_to(dir).unwrap();
+                    thread::yield_now();
+                    assert_eq!(
+                        AbsPathBuf::new(std::env::current_dir().unwrap().canonicalize().unwrap())
+                            .unwrap(),
+                        dir.canonicalize().unwrap()
+                    );
+                }
+            });
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "already holds a guard")]
+fn current_dir_guard_nested_panics() {
+    let dir = scratch("cwd-nested");
+    let _outer = fs::CurrentDirGuard::change_to(&dir).unwrap();
+    let _inner = fs::CurrentDirGuard::change_to(&dir);
+}
+
+#[test]
+fn probe_cleans_up() {
+    let dir = scratch("probe");
+    let caps = fs::probe(&dir).unwrap();
+    assert!((1..=fs::MAX_PROBED_NAME_LEN).contains(&caps.max_name_len));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    #[cfg(target_os = "linux")]
+    {
+        assert!(caps.case_sensitive);
+        assert!(caps.symlinks_supported);
+        assert_eq!(caps.max_name_len, 255);
+    }
+}
+
+#[test]
+fn probe_missing_dir_names_path() {
+    let tmp = scratch("probe-missing");
+    let dir = tmp.try_join("missing").unwrap();
+    let err = fs::probe(&dir).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("missing"));
+}
+
+#[test]
+fn replace_file_over_existing() {
+    let dir = scratch("replace-file");
+    let (src, dst) = (
+        dir.try_join("new").unwrap(),
+        dir.try_join("current").unwrap(),
+    );
+    std::fs::write(&src, "new").unwrap();
+    std::fs::write(&dst, "old").unwrap();
+
+    fs::replace_file(&src, &dst).unwrap();
+    assert_eq!(std::fs::read_to_string(&dst).unwrap(), "new");
+    assert!(!src.exists());
+
+    std::fs::write(&src, "newer").unwrap();
+    fs::replace_file(&src, dir.try_join("fresh").unwrap()).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.try_join("fresh").unwrap()).unwrap(),
+        "newer"
+    );
+}
+
+#[test]
+fn replace_file_rejects_directories() {
+    let dir = scratch("replace-file-dirs");
+    std::fs::create_dir(dir.try_join("d").unwrap()).unwrap();
+    std::fs::write(dir.try_join("f").unwrap(), "").unwrap();
+
+    let err = fs::replace_file(dir.try_join("d").unwrap(), dir.try_join("g").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::IsADirectory);
+    let err = fs::replace_file(dir.try_join("f").unwrap(), dir.try_join("d").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::IsADirectory);
+    assert!(
+        err.to_string()
+            .contains(&*dir.try_join("d").unwrap().to_string_lossy())
+    );
+    assert!(dir.try_join("f").unwrap().exists());
+}
+
+#[test]
+fn replace_dir_over_non_empty() {
+    let dir = scratch("replace-dir");
+    let (src, dst) = (
+        dir.try_join("staged").unwrap(),
+        dir.try_join("app").unwrap(),
+    );
+    std::fs::create_dir_all(src.try_join("bin").unwrap()).unwrap();
+    std::fs::write(src.try_join("bin/app").unwrap(), "v2").unwrap();
+    std::fs::create_dir_all(dst.try_join("lib").unwrap()).unwrap();
+    std::fs::write(dst.try_join("lib/old").unwrap(), "v1").unwrap();
+
+    fs::replace_dir(&src, &dst).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dst.try_join("bin/app").unwrap()).unwrap(),
+        "v2"
+    );
+    assert!(!dst.try_join("lib").unwrap().exists());
+    let left: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+    assert_eq!(left.len(), 1);
+}
+
+#[test]
+fn replace_dir_into_missing_and_over_file() {
+    let dir = scratch("replace-dir-missing");
+    std::fs::create_dir(dir.try_join("a").unwrap()).unwrap();
+    fs::replace_dir(dir.try_join("a").unwrap(), dir.try_join("b").unwrap()).unwrap();
+    assert!(dir.try_join("b").unwrap().is_dir());
+
+    std::fs::write(dir.try_join("f").unwrap(), "").unwrap();
+    let err = fs::replace_dir(dir.try_join("b").unwrap(), dir.try_join("f").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotADirectory);
+    assert!(dir.try_join("b").unwrap().is_dir());
+}
+
+#[cfg(unix)]
+#[test]
+fn materialize_links_and_reports() {
+    use dirge::RelPathBuf;
+    use fs::{MaterializeStrategy, PathMapping};
+
+    let dir = scratch("materialize");
+    let store = dir.try_join("store").unwrap();
+    std::fs::create_dir_all(store.try_join("pkg/lib").unwrap()).unwrap();
+    std::fs::write(store.try_join("pkg/lib/a.js").unwrap(), "a").unwrap();
+    std::fs::write(store.try_join("tool").unwrap(), "#!/bin/sh").unwrap();
+    std::fs::write(store.try_join("busy").unwrap(), "").unwrap();
+    let out = dir.try_join("out").unwrap();
+    std::fs::create_dir_all(out.try_join("bin").unwrap()).unwrap();
+    std::fs::write(out.try_join("bin/busy").unwrap(), "mine").unwrap();
+
+    let rel = |s: &str| RelPathBuf::new(s).unwrap();
+    let mut layout = PathMapping::new();
+    layout
+        .insert(rel("deps/pkg"), store.try_join("pkg").unwrap())
+        .unwrap();
+    layout
+        .insert(rel("deps/pkg/extra"), store.try_join("tool").unwrap())
+        .unwrap();
+    layout
+        .insert(rel("bin/./tool"), store.try_join("tool").unwrap())
+        .unwrap();
+    layout
+        .insert(rel("bin/busy"), store.try_join("busy").unwrap())
+        .unwrap();
+    layout
+        .insert(rel("bin/gone"), store.try_join("missing").unwrap())
+        .unwrap();
+
+    let report = fs::materialize(&layout, &out, MaterializeStrategy::Link).unwrap();
+    assert_eq!(report.created, [rel("bin/tool"), rel("deps/pkg")]);
+    assert_eq!(report.conflicts, [rel("bin/busy"), rel("deps/pkg/extra")]);
+    assert_eq!(report.dangling, [rel("bin/gone")]);
+    assert!(!report.is_complete());
+
+    assert!(
+        out.try_join("deps/pkg")
+            .unwrap()
+            .symlink_metadata()
+            .unwrap()
+            .is_symlink()
+    );
+    assert_eq!(
+        std::fs::read_to_string(out.try_join("deps/pkg/lib/a.js").unwrap()).unwrap(),
+        "a"
+    );
+    assert!(!store.try_join("pkg/extra").unwrap().exists());
+    assert_eq!(
+        std::fs::read_to_string(out.try_join("bin/busy").unwrap()).unwrap(),
+        "mine"
+    );
+
+    let again = fs::materialize(&layout, &out, MaterializeStrategy::Link).unwrap();
+    assert!(again.created.is_empty());
+    assert_eq!(again.unchanged, [rel("bin/tool"), rel("deps/pkg")]);
+}
+
+#[test]
+fn materialize_copies_trees() {
+    use dirge::RelPathBuf;
+    use fs::{MaterializeStrategy, PathMapping};
+
+    let dir = scratch("materialize-copy");
+    std::fs::create_dir_all(dir.try_join("src/sub").unwrap()).unwrap();
+    std::fs::write(dir.try_join("src/sub/f").unwrap(), "data").unwrap();
+
+    let mut layout = PathMapping::new();
+    layout
+        .insert(
+            RelPathBuf::new("copy").unwrap(),
+            dir.try_join("src").unwrap(),
+        )
+        .unwrap();
+    let report = fs::materialize(
+        &layout,
+        dir.try_join("out").unwrap(),
+        MaterializeStrategy::Copy,
+    )
+    .unwrap();
+    assert!(report.is_complete());
+
+    std::fs::write(dir.try_join("src/sub/f").unwrap(), "changed").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.try_join("out/copy/sub/f").unwrap()).unwrap(),
+        "data"
+    );
+}
+
+#[test]
+fn materialize_respects_limits() {
+    use dirge::{
+        RelPathBuf,
+        walk::{Limit, LimitExceeded, Limits},
+    };
+    use fs::{MaterializeStrategy, PathMapping};
+
+    let dir = scratch("materialize-limits");
+    std::fs::create_dir_all(dir.try_join("src/sub").unwrap()).unwrap();
+    std::fs::write(dir.try_join("src/sub/f").unwrap(), "data").unwrap();
+    let mut layout = PathMapping::new();
+    layout
+        .insert(
+            RelPathBuf::new("copy").unwrap(),
+            dir.try_join("src").unwrap(),
+        )
+        .unwrap();
+
+    let copy = |out: &str, limits| {
+        fs::materialize_with_limits(
+            &layout,
+            dir.try_join(out).unwrap(),
+            MaterializeStrategy::Copy,
+            limits,
+        )
+    };
+    assert!(copy("roomy", Limits::new().max_files(3).max_bytes(4)).is_ok());
+    for (out, limits, limit) in [
+        ("files", Limits::new().max_files(2), Limit::Files(2)),
+        ("bytes", Limits::new().max_bytes(3), Limit::Bytes(3)),
+        ("depth", Limits::new().max_depth(1), Limit::Depth(1)),
+    ] {
+        let err = copy(out, limits).unwrap_err();
+        assert_eq!(LimitExceeded::from_io(&err).unwrap().limit(), limit);
+    }
+}
+
+#[test]
+fn path_mapping_rejects_root_and_escapes() {
+    use dirge::RelPathBuf;
+
+    let mut layout = fs::PathMapping::new();
+    let source = scratch("mapping");
+    for bad in ["", ".", "a/..", "../x"] {
+        let err = layout
+            .insert(RelPathBuf::new(bad).unwrap(), &source)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{bad:?}");
+    }
+    assert!(layout.is_empty());
+}
+
+#[test]
+fn unique_child_names() {
+    let dir = scratch("unique");
+    let a = dir.unique_child("stage-", "tmp");
+    let b = dir.unique_child("stage-", "tmp");
+    assert_ne!(a, b);
+    assert_eq!(a.parent(), Some(&**dir));
+    let name = a.file_name().unwrap().to_str().unwrap();
+    assert!(
+        name.starts_with("stage-") && name.ends_with(".tmp"),
+        "{name}"
+    );
+    assert_eq!(dir.unique_child("x", "").extension(), None);
+}
+
+#[test]
+#[should_panic(expected = "path separators")]
+fn unique_child_rejects_separators() {
+    scratch("unique-sep").unique_child("a/b", "");
+}
+
+#[test]
+fn unique_child_with_retries_existing() {
+    let dir = scratch("unique-with");
+    let mut attempts = 0;
+    let (path, file) = dir
+        .unique_child_with("f-", "", |candidate| {
+            attempts += 1;
+            if attempts < 3 {
+                return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+            }
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(candidate)
+        })
+        .unwrap();
+    assert_eq!(attempts, 3);
+    assert!(file.metadata().unwrap().is_file());
+    assert!(path.exists());
+
+    let err = dir
+        .unique_child_with("f-", "", |_| -> io::Result<()> {
+            Err(io::Error::from(io::ErrorKind::AlreadyExists))
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+    let err = dir
+        .unique_child_with("f-", "", |_| -> io::Result<()> {
+            Err(io::Error::from(io::ErrorKind::PermissionDenied))
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
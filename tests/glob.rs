@@ -0,0 +1,89 @@
+use dirge::{
+    RelPathBuf,
+    glob::{GlobPattern, Input},
+};
+
+fn rel(s: &str) -> RelPathBuf {
+    RelPathBuf::new(s).unwrap()
+}
+
+fn glob(s: &str) -> GlobPattern {
+    GlobPattern::new(s).unwrap()
+}
+
+#[test]
+fn classifies_inputs() {
+    assert_eq!(
+        Input::parse("src/main.rs").unwrap(),
+        Input::Literal(rel("src/main.rs"))
+    );
+    assert_eq!(Input::parse("a[b").unwrap(), Input::Literal(rel("a[b")));
+    assert_eq!(Input::parse("[[]x]").unwrap(), Input::Literal(rel("[x]")));
+    assert_eq!(Input::parse("why[?]").unwrap(), Input::Literal(rel("why?")));
+    assert!(matches!(Input::parse("*.rs").unwrap(), Input::Glob(_)));
+    assert!(matches!(Input::parse("file?.txt").unwrap(), Input::Glob(_)));
+    assert!(matches!(Input::parse("[ab].txt").unwrap(), Input::Glob(_)));
+    assert!(Input::parse("/etc/*").is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn backslash_escapes_on_unix() {
+    assert_eq!(Input::parse(r"a\*b").unwrap(), Input::Literal(rel("a*b")));
+    let Input::Glob(pattern) = Input::parse(r"\**").unwrap() else {
+        panic!("expected a glob");
+    };
+    assert!(pattern.matches(rel("*x")));
+    assert!(!pattern.matches(rel("x")));
+}
+
+#[test]
+fn wildcards_match() {
+    let pattern = glob("src/*.rs");
+    assert!(pattern.matches(rel("src/lib.rs")));
+    assert!(pattern.matches(rel("./src/.rs")));
+    assert!(!pattern.matches(rel("src/a/lib.rs")));
+    assert!(!pattern.matches(rel("src/lib.rsx")));
+
+    assert!(glob("?.md").matches(rel("a.md")));
+    assert!(!glob("?.md").matches(rel("ab.md")));
+    assert!(glob("[a-c]x").matches(rel("bx")));
+    assert!(!glob("[!a-c]x").matches(rel("bx")));
+    assert!(glob("[]]").matches(rel("]")));
+}
+
+#[test]
+fn any_dirs() {
+    let pattern = glob("src/**/mod.rs");
+    assert!(pattern.matches(rel("src/mod.rs")));
+    assert!(pattern.matches(rel("src/a/b/mod.rs")));
+    assert!(!pattern.matches(rel("tests/mod.rs")));
+    assert!(glob("**").matches(rel("a/b")));
+}
+
+#[test]
+fn many_stars_match_in_polynomial_time() {
+    let name = "a".repeat(60);
+    let stars = "*a".repeat(30) + "b";
+    assert!(!glob(&stars).matches(rel(&name)));
+    assert!(glob(&("*a".repeat(30) + "*")).matches(rel(&name)));
+
+    let dirs = vec!["a"; 40].join("/");
+    let any_dirs = "**/a/".repeat(20) + "b";
+    assert!(!glob(&any_dirs).matches(rel(&dirs)));
+    assert!(glob(&("**/a/".repeat(20) + "**")).matches(rel(&dirs)));
+}
+
+#[test]
+fn rejects_bad_patterns() {
+    for pattern in ["/abs/*", "a[b", "a**/b"] {
+        assert!(GlobPattern::new(pattern).is_err(), "{pattern:?}");
+    }
+}
+
+#[test]
+fn literal_prefix() {
+    assert_eq!(glob("src/gen/**/*.rs").literal_prefix(), rel("src/gen"));
+    assert_eq!(glob("*.rs").literal_prefix(), RelPathBuf::empty());
+    assert_eq!(glob("a/[*]/b?").literal_prefix(), rel("a/*"));
+}
@@ -0,0 +1,76 @@
+use dirge::{AbsPathBuf, RelPathBuf, iter::PathIteratorExt, map::PathSet};
+
+mod common;
+
+use common::scratch;
+
+fn abs(s: &str) -> AbsPathBuf {
+    AbsPathBuf::new(s).unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn relative_to_drops_outside_paths() {
+    let rel: Vec<RelPathBuf> = [abs("/ws/a.rs"), abs("/other/b.rs"), abs("/ws/src/c.rs")]
+        .into_iter()
+        .relative_to(abs("/ws"))
+        .collect();
+    assert_eq!(
+        rel,
+        ["a.rs", "src/c.rs"].map(|p| RelPathBuf::new(p).unwrap())
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn filter_extensions_is_exact() {
+    let kept: Vec<AbsPathBuf> = [abs("/a.rs"), abs("/b.RS"), abs("/c.toml"), abs("/rs")]
+        .into_iter()
+        .filter_extensions(["rs", "toml"])
+        .collect();
+    assert_eq!(kept, [abs("/a.rs"), abs("/c.toml")]);
+}
+
+#[cfg(unix)]
+#[test]
+fn exclude_covers_subtrees() {
+    let excluded: PathSet<AbsPathBuf> = [abs("/ws/target"), abs("/ws/.git/config")].into();
+    let kept: Vec<AbsPathBuf> = [
+        abs("/ws/target/debug/app"),
+        abs("/ws/Target"),
+        abs("/ws/target-old/x"),
+        abs("/ws/.git/config"),
+        abs("/ws/.git/HEAD"),
+    ]
+    .into_iter()
+    .exclude(&excluded)
+    .collect();
+    // The default normalizer folds case.
+    assert_eq!(kept, [abs("/ws/target-old/x"), abs("/ws/.git/HEAD")]);
+}
+
+#[cfg(unix)]
+#[test]
+fn dedup_by_identity_follows_links() {
+    let dir = scratch("dedup");
+    std::fs::write(dir.try_join("a").unwrap(), "").unwrap();
+    std::fs::write(dir.try_join("b").unwrap(), "").unwrap();
+    std::os::unix::fs::symlink(dir.try_join("a").unwrap(), dir.try_join("link").unwrap()).unwrap();
+    std::fs::hard_link(dir.try_join("b").unwrap(), dir.try_join("hard").unwrap()).unwrap();
+
+    let names: Vec<RelPathBuf> = dir
+        .walk()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .chain([
+            dir.try_join("missing").unwrap(),
+            dir.try_join("missing").unwrap(),
+        ])
+        .dedup_by_identity()
+        .relative_to(&dir)
+        .collect();
+    assert_eq!(
+        names,
+        ["a", "b", "missing", "missing"].map(|p| RelPathBuf::new(p).unwrap())
+    );
+}
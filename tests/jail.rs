@@ -0,0 +1,78 @@
+use dirge::{RelPathBuf, jail::ReadOnlyJail};
+use std::io::{self, Read, Seek, SeekFrom};
+
+mod common;
+
+use common::Scratch;
+
+fn rel(s: &str) -> RelPathBuf {
+    RelPathBuf::new(s).unwrap()
+}
+
+fn scratch(name: &str) -> Scratch {
+    let dir = common::scratch(name);
+    std::fs::create_dir_all(dir.try_join("root/sub").unwrap()).unwrap();
+    std::fs::write(dir.try_join("root/a.txt").unwrap(), "hello").unwrap();
+    std::fs::write(dir.try_join("root/sub/b.txt").unwrap(), "").unwrap();
+    std::fs::write(dir.try_join("secret").unwrap(), "s3cret").unwrap();
+    dir
+}
+
+#[test]
+fn reads_inside_root() {
+    let dir = scratch("read");
+    let jail = ReadOnlyJail::new(dir.try_join("root").unwrap()).unwrap();
+
+    assert_eq!(jail.read_to_string(rel("a.txt")).unwrap(), "hello");
+    assert_eq!(jail.read(rel("sub/../a.txt")).unwrap(), b"hello");
+    assert_eq!(jail.read_dir(rel(".")).unwrap(), [rel("a.txt"), rel("sub")]);
+    assert_eq!(jail.read_dir(rel("sub")).unwrap(), [rel("sub/b.txt")]);
+    assert!(jail.metadata(rel("sub")).unwrap().is_dir());
+    assert!(jail.exists(rel("sub/b.txt")));
+    assert!(!jail.exists(rel("missing")));
+
+    let mut file = jail.open(rel("a.txt")).unwrap();
+    file.seek(SeekFrom::Start(1)).unwrap();
+    let mut rest = String::new();
+    file.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "ello");
+    assert_eq!(file.metadata().unwrap().len(), 5);
+}
+
+#[test]
+fn rejects_escapes() {
+    let dir = scratch("escape");
+    let jail = ReadOnlyJail::new(dir.try_join("root").unwrap()).unwrap();
+    for path in ["../secret", "sub/../../secret"] {
+        let err = jail.read(rel(path)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{path}");
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn rejects_symlinks_out_of_root() {
+    let dir = scratch("symlink");
+    std::os::unix::fs::symlink(
+        dir.try_join("secret").unwrap(),
+        dir.try_join("root/link").unwrap(),
+    )
+    .unwrap();
+    std::os::unix::fs::symlink(
+        dir.try_join("root/a.txt").unwrap(),
+        dir.try_join("root/inside").unwrap(),
+    )
+    .unwrap();
+    let jail = ReadOnlyJail::new(dir.try_join("root").unwrap()).unwrap();
+
+    let err = jail.read(rel("link")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(jail.read_to_string(rel("inside")).unwrap(), "hello");
+}
+
+#[test]
+fn root_must_be_directory() {
+    let dir = scratch("root-file");
+    let err = ReadOnlyJail::new(dir.try_join("secret").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
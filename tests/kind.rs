@@ -0,0 +1,36 @@
+use dirge::{AbsPathBuf, Kind, NormPathBuf, PathKind, RelPathBuf};
+use std::path::Path;
+
+fn describe<P: PathKind + ?Sized>(p: &P) -> (Kind, &Path) {
+    (p.kind(), p.as_path())
+}
+
+#[test]
+fn kinds() {
+    let abs = AbsPathBuf::new("/tmp").unwrap();
+    let rel = RelPathBuf::new("src").unwrap();
+    let norm = NormPathBuf::new("a/./b").unwrap();
+
+    assert_eq!(describe(&abs), (Kind::Absolute, Path::new("/tmp")));
+    assert_eq!(describe(&*abs), (Kind::Absolute, Path::new("/tmp")));
+    assert_eq!(describe(&rel).0, Kind::Relative);
+    assert_eq!(describe(&*norm), (Kind::Normalized, Path::new("a/b")));
+}
+
+#[test]
+fn to_owned_buf() {
+    let rel = RelPathBuf::new("src/lib.rs").unwrap();
+    let owned: RelPathBuf = (*rel).to_owned_buf();
+    assert_eq!(owned, rel);
+    assert_eq!(rel.to_owned_buf(), rel);
+}
+
+#[test]
+fn associated_kind() {
+    fn kind_of<P: PathKind + ?Sized>() -> Kind {
+        P::KIND
+    }
+
+    assert_eq!(kind_of::<dirge::AbsPath>(), Kind::Absolute);
+    assert_eq!(kind_of::<NormPathBuf>(), Kind::Normalized);
+}
@@ -0,0 +1,50 @@
+use dirge::{LossyPolicy, RelPathBuf};
+
+#[test]
+fn utf8_paths_are_unchanged() {
+    let rel = RelPathBuf::new("docs/café.md").unwrap();
+    for policy in [
+        LossyPolicy::Replace,
+        LossyPolicy::Escape,
+        LossyPolicy::Error,
+    ] {
+        assert_eq!(rel.to_string_policy(policy).unwrap(), "docs/café.md");
+    }
+    assert_eq!(rel.display_escaped().to_string(), "docs/café.md");
+}
+
+#[test]
+fn escape_doubles_backslashes() {
+    let rel = RelPathBuf::new("a\\xFF").unwrap();
+    assert_eq!(rel.display_escaped().to_string(), "a\\\\xFF");
+}
+
+// Non-UTF-8 paths can't be constructed at all under `utf8-strict`.
+#[cfg(all(unix, not(feature = "utf8-strict")))]
+mod unix {
+    use super::*;
+    use std::{ffi::OsStr, io, os::unix::ffi::OsStrExt};
+
+    fn non_utf8() -> RelPathBuf {
+        RelPathBuf::new(OsStr::from_bytes(b"caf\xe9/x")).unwrap()
+    }
+
+    #[test]
+    fn replace() {
+        let s = non_utf8().to_string_policy(LossyPolicy::Replace).unwrap();
+        assert_eq!(s, "caf\u{FFFD}/x");
+    }
+
+    #[test]
+    fn escape() {
+        let s = non_utf8().to_string_policy(LossyPolicy::Escape).unwrap();
+        assert_eq!(s, "caf\\xE9/x");
+        assert_eq!(non_utf8().display_escaped().to_string(), s);
+    }
+
+    #[test]
+    fn error() {
+        let err = non_utf8().to_string_policy(LossyPolicy::Error).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
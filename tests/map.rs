@@ -0,0 +1,80 @@
+use dirge::{
+    RelPathBuf,
+    map::{AsciiCaseFold, PathMap, PathSet, Separators},
+};
+use std::path::{Path, PathBuf};
+
+fn rel(s: &str) -> RelPathBuf {
+    RelPathBuf::new(s).unwrap()
+}
+
+#[test]
+fn case_insensitive_by_default() {
+    let mut map: PathMap<RelPathBuf, u32> = PathMap::new();
+    assert_eq!(map.insert(rel("Docs/README.md"), 1), None);
+    assert_eq!(map.insert(rel("docs/readme.MD"), 2), Some(1));
+
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get("DOCS/readme.md"), Some(&2));
+    let (key, value) = map.get_key_value("docs/readme.md").unwrap();
+    assert_eq!((key, *value), (&rel("Docs/README.md"), 2));
+
+    assert_eq!(
+        map.remove("docs/Readme.md"),
+        Some((rel("Docs/README.md"), 2))
+    );
+    assert!(map.is_empty());
+}
+
+#[test]
+fn separator_normalization_is_component_wise() {
+    let map: PathMap<PathBuf, u32, Separators> =
+        [(PathBuf::from("a//b/"), 1), (PathBuf::from("A/b"), 2)]
+            .into_iter()
+            .collect();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get("a/b"), Some(&1));
+    assert!(!map.contains_key("a/B"));
+}
+
+#[test]
+fn iteration_keeps_original_keys_in_normalized_order() {
+    let map: PathMap<&str, u32> = [("b.txt", 1), ("A.txt", 2), ("c.txt", 3)]
+        .into_iter()
+        .collect();
+    let keys: Vec<&str> = map.keys().copied().collect();
+    assert_eq!(keys, ["A.txt", "b.txt", "c.txt"]);
+}
+
+#[test]
+fn custom_normalizer() {
+    let strip_ext = |p: &Path| p.with_extension("").to_string_lossy().into_owned();
+    let mut map = PathMap::with_normalizer(strip_ext);
+    map.insert("config.toml", 1);
+    assert_eq!(map.get("config.yaml"), Some(&1));
+    *map.get_mut("config").unwrap() += 1;
+    assert_eq!(map.values().copied().collect::<Vec<_>>(), [2]);
+}
+
+#[test]
+fn ascii_case_fold_leaves_non_ascii() {
+    let mut map: PathMap<&str, u32, AsciiCaseFold> = PathMap::new();
+    map.insert("Docs/ÉTÉ.md", 1);
+    assert_eq!(map.get("docs/ÉTÉ.md"), Some(&1));
+    assert_eq!(map.get("docs/été.md"), None);
+}
+
+#[test]
+fn path_set_policies() {
+    let paths = ["src/Main.rs", "src/main.rs", "SRC/main.rs"];
+
+    let exact: PathSet<&str, Separators> = paths.into_iter().collect();
+    assert_eq!(exact.len(), 3);
+
+    let mut folded: PathSet<&str> = paths.into_iter().collect();
+    assert_eq!(folded.len(), 1);
+    assert_eq!(folded.get("SRC/MAIN.RS"), Some(&"src/Main.rs"));
+    assert!(!folded.insert("src/MAIN.rs"));
+    assert_eq!(folded.remove("src/main.RS"), Some("src/Main.rs"));
+    assert!(folded.is_empty());
+}
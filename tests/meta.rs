@@ -0,0 +1,68 @@
+use dirge::PathValue;
+
+mod common;
+
+use common::scratch;
+
+#[test]
+fn metadata_typed_of_file() {
+    let dir = scratch("file");
+    std::fs::write(dir.try_join("f").unwrap(), "abc").unwrap();
+
+    let meta = dir.try_join("f").unwrap().metadata_typed().unwrap();
+    assert!(meta.file_type().is_file());
+    assert_eq!(meta.len(), 3);
+    assert!(meta.symlink_target().is_none());
+}
+
+#[test]
+fn metadata_typed_error_names_path() {
+    let tmp = scratch("missing");
+    let missing = tmp.try_join("nope").unwrap();
+    let err = missing.symlink_metadata_typed().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("nope"));
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_targets_are_classified() {
+    use std::os::unix::fs::symlink;
+
+    let dir = scratch("symlink");
+    std::fs::create_dir(dir.try_join("sub").unwrap()).unwrap();
+    symlink("../sibling", dir.try_join("sub/up").unwrap()).unwrap();
+    symlink("../../outside", dir.try_join("sub/escape").unwrap()).unwrap();
+    symlink("/etc/hosts", dir.try_join("sub/abs").unwrap()).unwrap();
+
+    let up = dir
+        .try_join("sub/up")
+        .unwrap()
+        .symlink_metadata_typed()
+        .unwrap();
+    let target = up.symlink_target().unwrap();
+    assert!(up.file_type().is_symlink());
+    assert!(matches!(target.target(), PathValue::Rel(_)));
+    assert_eq!(target.resolve(), dir.try_join("sibling").unwrap());
+    assert!(target.is_within(&dir));
+
+    let escape = dir
+        .try_join("sub/escape")
+        .unwrap()
+        .symlink_metadata_typed()
+        .unwrap();
+    assert!(!escape.symlink_target().unwrap().is_within(&dir));
+
+    let abs = dir
+        .try_join("sub/abs")
+        .unwrap()
+        .symlink_metadata_typed()
+        .unwrap();
+    let target = abs.symlink_target().unwrap();
+    assert!(target.is_absolute());
+    assert!(!target.is_within(&dir));
+
+    // Following the link loses the target.
+    let followed = dir.try_join("sub/abs").unwrap().metadata_typed();
+    assert!(followed.map_or(true, |m| m.symlink_target().is_none()));
+}
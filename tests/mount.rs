@@ -0,0 +1,47 @@
+use dirge::AbsPathBuf;
+
+#[test]
+#[cfg(unix)]
+fn unix_paths_are_never_unc() {
+    assert!(!AbsPathBuf::new("/mnt/share").unwrap().is_unc());
+}
+
+#[test]
+#[cfg(windows)]
+fn unc() {
+    assert!(AbsPathBuf::new(r"\\server\share\file").unwrap().is_unc());
+    assert!(AbsPathBuf::new(r"\\?\UNC\server\share").unwrap().is_unc());
+    assert!(!AbsPathBuf::new(r"C:\Users").unwrap().is_unc());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn network_drive_requires_existing_path() {
+    let missing = AbsPathBuf::new("/definitely/not/a/real/path").unwrap();
+    assert!(missing.is_network_drive().is_err());
+
+    let here = AbsPathBuf::new(".").unwrap();
+    assert!(here.is_network_drive().is_ok());
+}
+
+#[test]
+#[cfg(unix)]
+fn mount_point_of_root() {
+    let root = AbsPathBuf::new("/").unwrap();
+    assert_eq!(root.mount_point().unwrap(), root);
+}
+
+#[test]
+fn mount_point_contains_path() {
+    let here = AbsPathBuf::new(".").unwrap();
+    let mount = here.mount_point().unwrap();
+    assert!(here.canonicalize().unwrap().starts_with(&mount));
+    assert!(here.same_file_system(&mount).unwrap());
+}
+
+#[test]
+fn same_file_system_requires_existing_paths() {
+    let here = AbsPathBuf::new(".").unwrap();
+    let missing = AbsPathBuf::new("definitely-not-here").unwrap();
+    assert!(here.same_file_system(&missing).is_err());
+}
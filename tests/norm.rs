@@ -1,4 +1,4 @@
-use dirge::{NormPath, NormPathBuf};
+use dirge::{NormPath, NormPathBuf, RelPath};
 use std::path::Path;
 
 #[test]
@@ -79,3 +79,317 @@ fn preserves_absolute_paths() {
     let norm = NormPathBuf::new("/path/./to/../file.txt").unwrap();
     assert_eq!(norm.to_string_lossy(), "/path/file.txt");
 }
+
+#[test]
+fn to_norm_path_buf_is_infallible() {
+    use dirge::ToNormPathBuf;
+
+    let norm: NormPathBuf = "a/./b/../c".to_norm_path_buf();
+    assert_eq!(norm, NormPathBuf::new("a/c").unwrap());
+}
+
+#[test]
+fn preserves_every_leading_parent_dir() {
+    let norm = NormPathBuf::new("../../../etc/passwd").unwrap();
+    assert_eq!(norm.to_string_lossy(), "../../../etc/passwd");
+
+    let norm = NormPathBuf::new("a/../../b").unwrap();
+    assert_eq!(norm.to_string_lossy(), "../b");
+}
+
+#[test]
+fn parent_of_root_is_root() {
+    let norm = NormPathBuf::new("/../a").unwrap();
+    assert_eq!(norm.to_string_lossy(), "/a");
+}
+
+#[test]
+fn normalize_is_idempotent() {
+    for input in [
+        "a/./b/../c",
+        "../../x",
+        "./",
+        "/../a",
+        "a//b/",
+        "x/../..",
+        "",
+    ] {
+        let once = dirge::normalize(input);
+        assert_eq!(
+            dirge::normalize(&once).as_os_str(),
+            once.as_os_str(),
+            "{input:?}"
+        );
+        assert!(
+            NormPathBuf::new(input).unwrap().is_canonical_form(),
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn join_norm_stays_normalized() {
+    let norm = |s: &str| NormPathBuf::new(s).unwrap();
+
+    for (base, path, expected) in [
+        ("a/b", "c", "a/b/c"),
+        ("a/b", "../c/./d", "a/c/d"),
+        ("a", "../..", ".."),
+        ("a", "..", "."),
+        (".", "x", "x"),
+        ("..", "../x", "../../x"),
+        ("/a", "../../b", "/b"),
+    ] {
+        let joined = norm(base).join_norm(RelPath::new(path).unwrap());
+        assert_eq!(joined.as_os_str(), expected, "{base:?} + {path:?}");
+        assert!(joined.is_canonical_form());
+        assert_eq!(norm(base).try_join(path).unwrap(), joined);
+    }
+    #[cfg(unix)]
+    assert!(norm("a").try_join("/abs/./x").is_err());
+}
+
+#[test]
+fn typed_shadows_stay_normalized() {
+    let norm = NormPathBuf::new("site/blog/post.md").unwrap();
+
+    let parent: &NormPath = norm.parent().unwrap();
+    assert_eq!(parent, &*NormPathBuf::new("site/blog").unwrap());
+    assert_eq!(
+        norm.with_file_name("x.md").unwrap(),
+        NormPathBuf::new("site/blog/x.md").unwrap()
+    );
+    assert_eq!(
+        norm.with_extension("html"),
+        NormPathBuf::new("site/blog/post.html").unwrap()
+    );
+    assert!(norm.with_file_name("../x.md").is_err());
+    assert!(norm.with_file_name("a/./b").is_err());
+}
+
+#[test]
+fn is_normalized_agrees_with_normalize() {
+    let mut cases = vec![
+        "", ".", "..", "./", "a", "a/", "a/b", "a//b", "./a", "a/.", "a/..", "../a", "../../a",
+        "a/../b", "/", "/a", "//a", "/..", "/../a", "/a/b/", "..a", "a..", ".a/b.",
+    ];
+    if cfg!(windows) {
+        cases.extend([
+            r"C:\a",
+            r"C:/a",
+            r"C:a",
+            r"C:..\a",
+            r"C:\..\a",
+            r"\\srv\share\a",
+        ]);
+    }
+    for case in cases {
+        let expected = dirge::normalize(case).as_os_str() == case;
+        assert_eq!(dirge::is_normalized(case), expected, "{case:?}");
+        assert_eq!(NormPath::try_new(case).is_ok(), expected, "{case:?}");
+    }
+}
+
+#[test]
+fn into_path_buf() {
+    let norm = NormPathBuf::new("a/./b").unwrap();
+    assert_eq!(norm.clone().into_path_buf(), Path::new("a/b"));
+    let path_buf: std::path::PathBuf = norm.into();
+    assert_eq!(path_buf, Path::new("a/b"));
+}
+
+#[test]
+fn display_matches_path_display() {
+    let norm = NormPathBuf::new("a/./b").unwrap();
+    assert_eq!(norm.to_string(), Path::new("a/b").display().to_string());
+    assert_eq!(format!("{}", &*norm), norm.to_string());
+}
+
+#[test]
+fn hash_and_ord_agree_with_path() {
+    use std::collections::{BTreeSet, HashMap, hash_map::DefaultHasher};
+    use std::hash::{Hash, Hasher};
+
+    fn hash<T: Hash + ?Sized>(t: &T) -> u64 {
+        let mut h = DefaultHasher::new();
+        t.hash(&mut h);
+        h.finish()
+    }
+
+    let norm = NormPathBuf::new("a/./b").unwrap();
+    assert_eq!(hash(&norm), hash(&*norm));
+    assert_eq!(hash(&*norm), hash(Path::new("a/b")));
+
+    let mut map = HashMap::new();
+    map.insert(norm.clone(), 1);
+    assert_eq!(map.get(&*norm), Some(&1));
+
+    let set: BTreeSet<NormPathBuf> = ["a/b/c", "a-b", "a/b"]
+        .into_iter()
+        .map(|p| NormPathBuf::new(p).unwrap())
+        .collect();
+    let order: Vec<&Path> = set.iter().map(|p| &***p).collect();
+    assert_eq!(
+        order,
+        [Path::new("a/b"), Path::new("a/b/c"), Path::new("a-b")]
+    );
+}
+
+#[test]
+fn compares_with_std_and_strings() {
+    use std::ffi::OsStr;
+
+    let norm = NormPathBuf::new("a/./b").unwrap();
+    assert_eq!(norm, Path::new("a/b"));
+    assert_eq!(norm, "a/b");
+    assert_eq!("a/b", norm);
+    assert_eq!(norm, String::from("a/b"));
+    assert_eq!(norm, OsStr::new("a/b"));
+    assert_eq!(&*norm, std::path::PathBuf::from("a/b"));
+    assert_ne!(norm, "a/./b/c");
+}
+
+#[test]
+fn slice_and_vec_casts() {
+    use std::path::PathBuf;
+
+    let paths = vec![NormPathBuf::new("a/./b").unwrap()];
+    assert_eq!(NormPathBuf::cast_slice(&paths), [PathBuf::from("a/b")]);
+    let std_paths = NormPathBuf::into_path_buf_vec(paths);
+    assert_eq!(NormPathBuf::try_cast_slice(&std_paths).unwrap()[0], "a/b");
+    assert_eq!(
+        NormPathBuf::try_from_path_buf_vec(std_paths).unwrap().len(),
+        1
+    );
+
+    let unnormalized = vec![PathBuf::from("a/../b")];
+    assert!(NormPathBuf::try_cast_slice(&unnormalized).is_err());
+    assert!(NormPathBuf::try_from_path_buf_vec(unnormalized).is_err());
+}
+
+#[test]
+fn from_str_matches_new() {
+    let parsed: NormPathBuf = "a/../b/./c".parse().unwrap();
+    assert_eq!(parsed, NormPathBuf::new("b/c").unwrap());
+}
+
+#[test]
+fn try_from_std_types() {
+    use std::{ffi::OsString, path::PathBuf};
+
+    let buf = PathBuf::from("a/b");
+    let ptr = buf.as_os_str().as_encoded_bytes().as_ptr();
+    let norm = NormPathBuf::try_from(buf).unwrap();
+    assert_eq!(norm.as_os_str().as_encoded_bytes().as_ptr(), ptr);
+
+    assert_eq!(
+        NormPathBuf::try_from(PathBuf::from("a/./b")).unwrap(),
+        "a/b"
+    );
+    assert_eq!(NormPathBuf::try_from(Path::new("a/../b")).unwrap(), "b");
+    assert_eq!(NormPathBuf::try_from(String::from("a//b")).unwrap(), "a/b");
+    let norm: NormPathBuf = OsString::from("./a").try_into().unwrap();
+    assert_eq!(norm, "a");
+}
+
+#[test]
+fn as_ref_os_str() {
+    use std::ffi::OsStr;
+
+    fn os<S: AsRef<OsStr> + ?Sized>(s: &S) -> &OsStr {
+        s.as_ref()
+    }
+    let norm = NormPathBuf::new("a/./b").unwrap();
+    assert_eq!(os(&norm), "a/b");
+    assert_eq!(os(&*norm), "a/b");
+}
+
+#[test]
+fn cow_str_roundtrip() {
+    use std::borrow::Cow;
+
+    let norm = NormPathBuf::from_utf8_cow(Cow::Borrowed("a/./b")).unwrap();
+    assert_eq!(norm.to_cow_str().unwrap(), "a/b");
+    let again = NormPathBuf::from_utf8_cow(norm.to_cow_str().unwrap()).unwrap();
+    assert_eq!(again, norm);
+}
+
+#[test]
+fn try_join_rejects_absolute() {
+    let base = NormPathBuf::new("a/b").unwrap();
+    assert_eq!(base.try_join("../c/./d").unwrap(), "a/c/d");
+    #[cfg(unix)]
+    assert!(base.try_join("/c").is_err());
+}
+
+#[test]
+fn ancestors_stay_typed() {
+    use dirge::NormPath;
+
+    let norm = NormPathBuf::new("a/./b").unwrap();
+    let ancestors: Vec<&NormPath> = norm.ancestors().collect();
+    assert_eq!(ancestors, ["a/b", "a", ""]);
+    assert!(ancestors.iter().all(dirge::is_normalized));
+}
+
+#[test]
+fn set_extension_keeps_type() {
+    let mut norm = NormPathBuf::new("a/./b.tar.gz").unwrap();
+    assert!(norm.set_extension("xz"));
+    assert_eq!(norm, "a/b.tar.xz");
+    let with: NormPathBuf = norm.with_extension("");
+    assert_eq!(with, "a/b.tar");
+
+    let mut parent = NormPathBuf::new("a/..").unwrap();
+    assert!(!parent.set_extension("x"));
+    assert_eq!(parent, ".");
+}
+
+#[test]
+fn set_file_name_stays_normalized() {
+    let mut norm = NormPathBuf::new("a/b.md").unwrap();
+    norm.set_file_name("c.md").unwrap();
+    assert_eq!(norm, "a/c.md");
+    assert!(norm.set_file_name("..").is_err());
+    assert!(norm.set_file_name("x/y").is_err());
+    assert_eq!(norm, "a/c.md");
+
+    let mut dot = NormPathBuf::new("a/..").unwrap();
+    dot.set_file_name("x").unwrap();
+    assert_eq!(dot, "x");
+    let mut up = NormPathBuf::new("..").unwrap();
+    up.set_file_name("x").unwrap();
+    assert_eq!(up, "../x");
+}
+
+#[test]
+fn strip_prefix_is_relative() {
+    use dirge::RelPath;
+
+    let norm = NormPathBuf::new("a/./b/c").unwrap();
+    let rest: &RelPath = norm.strip_prefix("a").unwrap();
+    assert_eq!(rest, "b/c");
+    assert!(norm.strip_prefix("b").is_err());
+
+    #[cfg(unix)]
+    {
+        let abs = NormPathBuf::new("/a/b").unwrap();
+        assert_eq!(abs.strip_prefix("/").unwrap(), "a/b");
+        assert!(abs.strip_prefix("").is_err());
+    }
+}
+
+#[test]
+fn display_from_str_round_trip() {
+    for path in [
+        "file.txt",
+        "a/../b/./c",
+        "../../up",
+        "dir/",
+        "My Documents/r\u{e9}sum\u{e9}.pdf",
+    ] {
+        let norm = NormPathBuf::new(path).unwrap();
+        let parsed: NormPathBuf = norm.to_string().parse().unwrap();
+        assert_eq!(parsed.as_os_str(), norm.as_os_str(), "{path}");
+    }
+}
@@ -0,0 +1,15 @@
+use dirge::{Kind, prelude::*};
+
+#[test]
+fn traits_in_scope() {
+    let abs: AbsPathBuf = "Cargo.toml".to_abs_path_buf().unwrap();
+    let rel: RelPathBuf = "src/lib.rs".to_rel_path_buf().unwrap();
+    let norm: NormPathBuf = "a/../b".to_norm_path_buf();
+
+    let _: &AbsPath = &abs;
+    let _: &RelPath = &rel;
+    let _: &NormPath = &norm;
+    assert_eq!((*norm).as_path(), std::path::Path::new("b"));
+    assert_eq!((*norm).kind(), Kind::Normalized);
+    assert_eq!(norm.any_kind(), Kind::Normalized);
+}
@@ -0,0 +1,68 @@
+use dirge::{
+    AbsPathBuf, RelPathBuf,
+    process::{ArgStyle, CommandExt},
+};
+use std::{ffi::OsStr, io, process::Command};
+
+#[test]
+fn path_arg_guards_leading_dash() {
+    let mut cmd = Command::new("prog");
+    cmd.path_arg(RelPathBuf::new("-rf").unwrap(), ArgStyle::Script)
+        .path_arg(RelPathBuf::new("a-b").unwrap(), ArgStyle::Script);
+    let args: Vec<_> = cmd.get_args().collect();
+    assert_eq!(args, [OsStr::new("./-rf"), OsStr::new("a-b")]);
+}
+
+#[cfg(unix)]
+#[test]
+fn path_arg_keeps_backslashes_on_unix() {
+    let mut cmd = Command::new("prog");
+    cmd.path_arg("a\\b", ArgStyle::Script);
+    assert_eq!(cmd.get_args().next().unwrap(), "a\\b");
+}
+
+#[cfg(windows)]
+#[test]
+fn path_arg_separators_on_windows() {
+    let mut cmd = Command::new("prog");
+    cmd.path_arg("a/b\\c", ArgStyle::Script)
+        .path_arg("a/b\\c", ArgStyle::Native);
+    let args: Vec<_> = cmd.get_args().collect();
+    assert_eq!(args, [OsStr::new("a/b/c"), OsStr::new("a\\b\\c")]);
+}
+
+#[test]
+fn current_dir_abs_sets_dir() {
+    let dir = AbsPathBuf::new(std::env::temp_dir()).unwrap();
+    let mut cmd = Command::new("prog");
+    cmd.current_dir_abs(&dir);
+    assert_eq!(cmd.get_current_dir(), Some(dir.as_ref()));
+}
+
+#[cfg(unix)]
+#[test]
+fn resolve_program_searches_path() {
+    let resolved = Command::new("sh").resolve_program().unwrap();
+    assert!(resolved.is_absolute());
+    assert_eq!(resolved.file_name().unwrap(), "sh");
+}
+
+#[test]
+fn resolve_program_relative_to_current_dir() {
+    let dir = AbsPathBuf::new(std::env::temp_dir()).unwrap();
+    let mut cmd = Command::new(std::path::Path::new("bin").join("tool"));
+    cmd.current_dir_abs(&dir);
+    assert_eq!(
+        cmd.resolve_program().unwrap(),
+        dir.try_join("bin").unwrap().try_join("tool").unwrap()
+    );
+}
+
+#[test]
+fn resolve_program_not_found() {
+    let mut cmd = Command::new("dirge-no-such-program");
+    cmd.env("PATH", "");
+    let err = cmd.resolve_program().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("dirge-no-such-program"));
+}
@@ -0,0 +1,54 @@
+use dirge::{
+    AbsPath,
+    project::{Marker, detect_root},
+};
+
+mod common;
+
+use common::Scratch;
+
+fn scratch(name: &str) -> Scratch {
+    let dir = common::scratch(name);
+    std::fs::create_dir_all(dir.try_join("crates/core/src").unwrap()).unwrap();
+    dir
+}
+
+#[test]
+fn file_marker() {
+    let root = scratch("file");
+    std::fs::write(root.try_join("crates/core/Cargo.toml").unwrap(), b"").unwrap();
+
+    let markers = [Marker::File("Cargo.toml")];
+    let (found, marker) = detect_root(root.try_join("crates/core/src").unwrap(), &markers).unwrap();
+    assert_eq!(*found, *root.try_join("crates/core").unwrap());
+    assert!(matches!(marker, Marker::File("Cargo.toml")));
+}
+
+#[test]
+fn dir_marker_ignores_files() {
+    let root = scratch("dir");
+    std::fs::write(root.try_join("crates/.git").unwrap(), b"gitdir: elsewhere").unwrap();
+    std::fs::create_dir(root.try_join(".git").unwrap()).unwrap();
+
+    let markers = [Marker::Dir(".git")];
+    let (found, _) = detect_root(root.try_join("crates/core/src").unwrap(), &markers).unwrap();
+    assert_eq!(found.into_abs_path_buf(), *root);
+}
+
+#[test]
+fn predicate_marker() {
+    let root = scratch("predicate");
+    let is_crates = |dir: &AbsPath| dir.file_name().is_some_and(|n| n == "crates");
+    let markers = [Marker::File("Cargo.toml"), Marker::Predicate(&is_crates)];
+
+    let (found, marker) = detect_root(root.try_join("crates/core/src").unwrap(), &markers).unwrap();
+    assert_eq!(*found, *root.try_join("crates").unwrap());
+    assert!(matches!(marker, Marker::Predicate(_)));
+}
+
+#[test]
+fn no_match() {
+    let root = scratch("none");
+    let markers = [Marker::File("dirge-no-such-marker")];
+    assert!(detect_root(&root, &markers).is_none());
+}
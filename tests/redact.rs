@@ -0,0 +1,53 @@
+#![cfg(unix)]
+
+use dirge::redact::RedactPolicy;
+use std::path::Path;
+
+fn show(policy: &RedactPolicy, path: &str) -> String {
+    policy.redact(Path::new(path)).to_string()
+}
+
+#[test]
+fn home_becomes_tilde() {
+    let policy = RedactPolicy::new().home("/home/alice");
+    assert_eq!(show(&policy, "/home/alice/secret/x"), "~/secret/x");
+    assert_eq!(show(&policy, "/home/alice"), "~");
+    assert_eq!(show(&policy, "/home/alicebob/x"), "/home/alicebob/x");
+    assert_eq!(show(&policy, "/etc/passwd"), "/etc/passwd");
+}
+
+#[test]
+fn collapse_keeps_file_name() {
+    let policy = RedactPolicy::new().home("/home/alice").collapse(true);
+    assert_eq!(show(&policy, "/home/alice/secret/deep/x"), "~/…/x");
+    assert_eq!(show(&policy, "/home/alice/x"), "~/x");
+    assert_eq!(show(&policy, "/srv/a/b"), "/srv/a/b");
+}
+
+#[test]
+fn longest_prefix_wins() {
+    let policy = RedactPolicy::new()
+        .home("/home/alice")
+        .prefix("/home/alice/secret", "<redacted>");
+    assert_eq!(show(&policy, "/home/alice/secret/x"), "<redacted>/x");
+    assert_eq!(show(&policy, "/home/alice/public/x"), "~/public/x");
+}
+
+#[test]
+fn mask_users() {
+    let policy = RedactPolicy::new().mask_users(true);
+    assert_eq!(
+        show(&policy, "/home/bob/.ssh/id_rsa"),
+        "/home/<user>/.ssh/id_rsa"
+    );
+    assert_eq!(show(&policy, "/Users/bob"), "/Users/<user>");
+    assert_eq!(show(&policy, "/home"), "/home");
+    assert_eq!(show(&policy, "home/bob"), "home/bob");
+}
+
+#[test]
+fn debug_is_quoted() {
+    let policy = RedactPolicy::new().home("/home/alice");
+    let redacted = policy.redact(Path::new("/home/alice/x"));
+    assert_eq!(format!("{redacted:?}"), "\"~/x\"");
+}
@@ -50,3 +50,517 @@ fn accepts_relative_paths() {
     assert!(RelPathBuf::new("../relative/path").is_ok());
     assert!(RelPathBuf::new("file.txt").is_ok());
 }
+
+#[test]
+fn link_to() {
+    let link = |from: &str, to: &str| {
+        let from = RelPathBuf::new(from).unwrap();
+        let to = RelPathBuf::new(to).unwrap();
+        from.link_to(&to).unwrap().to_str().unwrap().to_owned()
+    };
+
+    assert_eq!(link("index.html", "about.html"), "about.html");
+    assert_eq!(link("index.html", "blog/post.html"), "blog/post.html");
+    assert_eq!(link("blog/post.html", "index.html"), "../index.html");
+    assert_eq!(link("blog/a/post.html", "blog/b/img.png"), "../b/img.png");
+    assert_eq!(link("blog/post.html", "blog/post.html"), "post.html");
+    assert_eq!(link("blog/post.html", "blog"), ".");
+    assert_eq!(link("./blog/../docs/x.html", "docs/y.html"), "y.html");
+    assert_eq!(link("x.html", "../outside.html"), "../outside.html");
+}
+
+#[test]
+fn link_from_outside_root_fails() {
+    let from = RelPathBuf::new("../x.html").unwrap();
+    let to = RelPathBuf::new("y.html").unwrap();
+    assert!(from.link_to(&to).is_err());
+}
+
+#[test]
+fn breadcrumbs() {
+    let rel = RelPathBuf::new("./docs/guide/intro.md").unwrap();
+    let crumbs: Vec<_> = rel.breadcrumbs().collect();
+
+    assert_eq!(crumbs.len(), 3);
+    assert_eq!(crumbs[0].0, RelPathBuf::new("docs").unwrap());
+    assert_eq!(crumbs[0].1, "docs");
+    assert_eq!(crumbs[1].0, RelPathBuf::new("docs/guide").unwrap());
+    assert_eq!(crumbs[2].0, RelPathBuf::new("docs/guide/intro.md").unwrap());
+    assert_eq!(crumbs[2].1, "intro.md");
+}
+
+#[test]
+fn breadcrumbs_of_single_component() {
+    let rel = RelPathBuf::new("README.md").unwrap();
+    let crumbs: Vec<_> = rel.breadcrumbs().collect();
+    assert_eq!(crumbs, [(rel.clone(), std::ffi::OsStr::new("README.md"))]);
+}
+
+#[test]
+fn arc_rel_path() {
+    use std::sync::Arc;
+
+    let rel = RelPathBuf::new("a/b").unwrap();
+    let arc: Arc<RelPath> = Arc::from(rel.clone());
+    assert_eq!(&*arc, &*rel);
+    assert_eq!(arc.to_string_lossy(), "a/b");
+}
+
+#[test]
+fn rebase_between_roots() {
+    let rel = |s: &str| RelPathBuf::new(s).unwrap();
+
+    assert_eq!(
+        rel("src/nested/foo.rs")
+            .rebase(rel("src"), rel("out/gen"))
+            .unwrap(),
+        rel("out/gen/nested/foo.rs")
+    );
+    assert_eq!(
+        rel("src").rebase(rel("src"), rel("out")).unwrap(),
+        rel("out")
+    );
+}
+
+#[test]
+fn rebase_rejects_non_matching_prefix() {
+    let rel = |s: &str| RelPathBuf::new(s).unwrap();
+
+    let err = rel("srcs/foo.rs")
+        .rebase(rel("src"), rel("out"))
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn portable_string_round_trip() {
+    for (input, portable) in [
+        ("a/b/c.txt", "a/b/c.txt"),
+        ("./a//b/", "a/b"),
+        ("a/x/../b", "a/b"),
+        ("", "."),
+        (".", "."),
+        ("cafe\u{301}/x", "caf\u{e9}/x"),
+        ("\u{1100}\u{1161}\u{11a8}", "\u{ac01}"),
+    ] {
+        let rel = RelPathBuf::new(input).unwrap();
+        assert_eq!(rel.to_portable_string().unwrap(), portable, "{input:?}");
+        let parsed = RelPathBuf::from_portable_string(portable).unwrap();
+        assert_eq!(parsed.to_portable_string().unwrap(), portable);
+    }
+}
+
+#[test]
+fn portable_string_rejects_escapes() {
+    let rel = RelPathBuf::new("a/../../b").unwrap();
+    assert_eq!(
+        rel.to_portable_string().unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn from_portable_string_is_strict() {
+    for input in [
+        "",
+        "/a",
+        "a/",
+        "a//b",
+        "./a",
+        "a/../b",
+        "..",
+        "a\\b",
+        "cafe\u{301}",
+    ] {
+        assert!(
+            RelPathBuf::from_portable_string(input).is_err(),
+            "{input:?}"
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn debug_alternate_in_structs() {
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Entry {
+        path: RelPathBuf,
+    }
+
+    let entry = Entry {
+        path: RelPathBuf::new("a\\b").unwrap(),
+    };
+    assert_eq!(format!("{entry:?}"), r#"Entry { path: "a\\b" }"#);
+    assert_eq!(format!("{entry:#?}"), "Entry {\n    path: \"a\\b\",\n}");
+}
+
+#[test]
+fn from_url_path_decodes_and_normalizes() {
+    for (raw, expected) in [
+        ("/", "."),
+        ("/index.html", "index.html"),
+        ("/a/./b//c/../d%2Etxt", "a/b/d.txt"),
+        ("/caf%C3%A9/menu", "café/menu"),
+        ("/a/%2e%2e/b", "b"),
+    ] {
+        assert_eq!(
+            RelPathBuf::from_url_path(raw).unwrap(),
+            RelPathBuf::new(expected).unwrap(),
+            "{raw:?}"
+        );
+    }
+}
+
+#[test]
+fn from_url_path_rejects_unsafe_input() {
+    for raw in [
+        "/../etc/passwd",
+        "/static/%2e%2e/%2e%2e/etc/passwd",
+        "/a%2Fb",
+        "/a%5cb",
+        "/a\\b",
+        "/a%00b",
+        "/a%zz",
+        "/a%2",
+        "/a%+f",
+        "/%ff",
+    ] {
+        let err = RelPathBuf::from_url_path(raw).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{raw:?}");
+    }
+}
+
+#[test]
+fn typed_shadows() {
+    let rel = RelPathBuf::new("docs/guide.md").unwrap();
+
+    let parent: &RelPath = rel.parent().unwrap();
+    assert_eq!(parent, &*RelPathBuf::new("docs").unwrap());
+    assert_eq!(
+        parent.join(RelPathBuf::new("api.md").unwrap()),
+        RelPathBuf::new("docs/api.md").unwrap()
+    );
+    assert_eq!(
+        rel.with_file_name("intro.md").unwrap(),
+        RelPathBuf::new("docs/intro.md").unwrap()
+    );
+    assert_eq!(
+        rel.with_extension("html"),
+        RelPathBuf::new("docs/guide.html").unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn with_file_name_keeps_relative() {
+    let rel = RelPathBuf::new("docs/guide.md").unwrap();
+    let err = rel.with_file_name("/etc/passwd").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn canonicalize_is_absolute() {
+    let rel = RelPathBuf::new(".").unwrap();
+    let abs = rel.canonicalize().unwrap();
+    assert_eq!(
+        abs.as_path(),
+        std::env::current_dir().unwrap().canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn explicit_empty_handling() {
+    let empty = RelPathBuf::empty();
+    assert!(empty.is_empty());
+    assert_eq!(empty, RelPathBuf::new("").unwrap());
+    assert!(!RelPathBuf::new(".").unwrap().is_empty());
+    assert_eq!(
+        empty.join(RelPathBuf::new("a").unwrap()),
+        RelPathBuf::new("a").unwrap()
+    );
+
+    let err = RelPathBuf::new_non_empty("").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(err.to_string(), "path is empty");
+    assert!(RelPathBuf::new_non_empty("/abs").is_err());
+    assert!(RelPathBuf::new_non_empty("a").is_ok());
+}
+
+#[test]
+fn segment_editing() {
+    let rel = |s: &str| RelPathBuf::new(s).unwrap();
+
+    let mut path = rel("a/b/c");
+    path.replace_segment(0, "x").unwrap();
+    assert_eq!(path, rel("x/b/c"));
+    path.insert_segment(1, "y").unwrap();
+    assert_eq!(path, rel("x/y/b/c"));
+    path.insert_segment(4, "z").unwrap();
+    assert_eq!(path, rel("x/y/b/c/z"));
+    path.remove_segment(2).unwrap();
+    assert_eq!(path, rel("x/y/c/z"));
+
+    let mut path = rel("../a");
+    path.remove_segment(0).unwrap();
+    assert_eq!(path, rel("a"));
+    path.remove_segment(0).unwrap();
+    assert!(path.is_empty());
+}
+
+#[test]
+fn segment_editing_rejects_bad_input() {
+    let mut path = RelPathBuf::new("a/b").unwrap();
+    for name in ["", ".", "..", "x/y", "/x"] {
+        let err = path.replace_segment(0, name).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{name:?}");
+    }
+    assert!(path.replace_segment(2, "x").is_err());
+    assert!(path.insert_segment(3, "x").is_err());
+    assert!(path.remove_segment(2).is_err());
+    assert_eq!(path, RelPathBuf::new("a/b").unwrap());
+}
+
+#[test]
+fn borrowed_constructor() {
+    let path = std::path::Path::new("docs/guide.md");
+    let rel = RelPath::new(path).unwrap();
+    assert!(std::ptr::eq(rel.as_os_str(), path.as_os_str()));
+    assert_eq!(rel.to_owned(), RelPathBuf::new("docs/guide.md").unwrap());
+
+    let err = RelPath::new("/absolute").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn into_path_buf() {
+    let rel = RelPathBuf::new("a/b").unwrap();
+    assert_eq!(rel.clone().into_path_buf(), std::path::PathBuf::from("a/b"));
+    let path_buf: std::path::PathBuf = rel.into();
+    assert_eq!(path_buf, Path::new("a/b"));
+}
+
+#[test]
+fn display_matches_path_display() {
+    let rel = RelPathBuf::new("a/../b").unwrap();
+    assert_eq!(rel.to_string(), Path::new("a/../b").display().to_string());
+    assert_eq!(format!("{}", &*rel), rel.to_string());
+}
+
+#[test]
+fn hash_and_ord_agree_with_path() {
+    use std::collections::{BTreeSet, HashMap, hash_map::DefaultHasher};
+    use std::hash::{Hash, Hasher};
+
+    fn hash<T: Hash + ?Sized>(t: &T) -> u64 {
+        let mut h = DefaultHasher::new();
+        t.hash(&mut h);
+        h.finish()
+    }
+
+    let rel = RelPathBuf::new("a/b").unwrap();
+    assert_eq!(hash(&rel), hash(&*rel));
+    assert_eq!(hash(&*rel), hash(Path::new("a/b")));
+
+    let mut map = HashMap::new();
+    map.insert(rel.clone(), 1);
+    assert_eq!(map.get(&*rel), Some(&1));
+
+    let set: BTreeSet<RelPathBuf> = ["a/b/c", "a-b", "a/b"]
+        .into_iter()
+        .map(|p| RelPathBuf::new(p).unwrap())
+        .collect();
+    let order: Vec<&Path> = set.iter().map(|p| &***p).collect();
+    assert_eq!(
+        order,
+        [Path::new("a/b"), Path::new("a/b/c"), Path::new("a-b")]
+    );
+}
+
+#[test]
+fn compares_with_std_and_strings() {
+    use std::ffi::{OsStr, OsString};
+
+    let rel = RelPathBuf::new("a/b").unwrap();
+    assert_eq!(rel, Path::new("a/b"));
+    assert_eq!(rel, std::path::PathBuf::from("a//b"));
+    assert_eq!(rel, "a/b");
+    assert_eq!(*rel, *"a/b");
+    assert_eq!(rel, OsString::from("a/b"));
+    assert_eq!(OsStr::new("a/b"), &*rel);
+    assert_eq!(&*rel, rel);
+    assert_ne!(rel, "a/c");
+}
+
+#[test]
+fn slice_and_vec_casts() {
+    use std::path::PathBuf;
+
+    let paths = vec![
+        RelPathBuf::new("a").unwrap(),
+        RelPathBuf::new("b/c").unwrap(),
+    ];
+    assert_eq!(
+        RelPathBuf::cast_slice(&paths),
+        [PathBuf::from("a"), PathBuf::from("b/c")]
+    );
+    let std_paths = RelPathBuf::into_path_buf_vec(paths);
+    assert_eq!(RelPathBuf::try_cast_slice(&std_paths).unwrap()[1], "b/c");
+    assert_eq!(
+        RelPathBuf::try_from_path_buf_vec(std_paths).unwrap().len(),
+        2
+    );
+
+    let mixed = [PathBuf::from("a"), PathBuf::from("/b")];
+    assert!(RelPathBuf::try_cast_slice(&mixed).is_err());
+}
+
+#[test]
+fn from_str_matches_new() {
+    let parsed: RelPathBuf = "foo/bar".parse().unwrap();
+    assert_eq!(parsed, "foo/bar");
+    #[cfg(unix)]
+    assert_eq!(
+        "/foo".parse::<RelPathBuf>().unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn try_from_std_types() {
+    use std::{ffi::OsString, path::PathBuf};
+
+    let buf = PathBuf::from("a/b");
+    let ptr = buf.as_os_str().as_encoded_bytes().as_ptr();
+    let rel = RelPathBuf::try_from(buf).unwrap();
+    assert_eq!(rel.as_os_str().as_encoded_bytes().as_ptr(), ptr);
+
+    assert_eq!(RelPathBuf::try_from(Path::new("a")).unwrap(), "a");
+    assert_eq!(RelPathBuf::try_from(String::from("a")).unwrap(), "a");
+    assert_eq!(RelPathBuf::try_from(OsString::from("a")).unwrap(), "a");
+    #[cfg(unix)]
+    assert!(RelPathBuf::try_from(String::from("/a")).is_err());
+}
+
+#[test]
+fn as_ref_os_str() {
+    use std::ffi::OsStr;
+
+    fn os<S: AsRef<OsStr> + ?Sized>(s: &S) -> &OsStr {
+        s.as_ref()
+    }
+    let rel = RelPathBuf::new("a/b").unwrap();
+    assert_eq!(os(&rel), "a/b");
+    assert_eq!(os(&*rel), "a/b");
+}
+
+#[test]
+fn cow_str_roundtrip() {
+    use std::borrow::Cow;
+
+    let s = String::from("a/b");
+    let ptr = s.as_ptr();
+    let rel = RelPathBuf::from_utf8_cow(Cow::Owned(s)).unwrap();
+    assert_eq!(rel.as_os_str().as_encoded_bytes().as_ptr(), ptr);
+    assert_eq!(rel.to_cow_str().unwrap(), "a/b");
+    assert!(RelPathBuf::from_utf8_cow(Cow::Borrowed("c")).is_ok());
+}
+
+#[test]
+fn try_join_rejects_absolute() {
+    let base = RelPathBuf::new("a").unwrap();
+    assert_eq!(base.try_join("b").unwrap(), "a/b");
+    #[cfg(unix)]
+    assert!(base.try_join("/b").is_err());
+    #[cfg(windows)]
+    for bad in [r"\b", "C:b", r"C:\b"] {
+        assert!(base.try_join(bad).is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn parent_chains_stay_typed() {
+    let rel = RelPathBuf::new("a/b/c").unwrap();
+    let mut chain: Vec<&RelPath> = Vec::new();
+    let mut current: &RelPath = &rel;
+    while let Some(parent) = current.parent() {
+        chain.push(parent);
+        current = parent;
+    }
+    assert_eq!(chain, ["a/b", "a", ""].map(|p| RelPath::new(p).unwrap()));
+}
+
+#[test]
+fn ancestors_stay_typed() {
+    let rel = RelPathBuf::new("a/b/c").unwrap();
+    let ancestors: Vec<&RelPath> = rel.ancestors().collect();
+    assert_eq!(
+        ancestors,
+        ["a/b/c", "a/b", "a", ""].map(|p| RelPath::new(p).unwrap())
+    );
+}
+
+#[test]
+fn set_extension_keeps_type() {
+    let mut rel = RelPathBuf::new("docs/intro.md").unwrap();
+    assert!(rel.set_extension("html"));
+    assert_eq!(rel, "docs/intro.html");
+    let with: RelPathBuf = rel.with_extension("txt");
+    assert_eq!(with, "docs/intro.txt");
+    assert!(!RelPathBuf::empty().set_extension("x"));
+}
+
+#[test]
+fn file_name_replacement_rejects_non_segments() {
+    let mut rel = RelPathBuf::new("docs/intro.md").unwrap();
+    rel.set_file_name("guide.md").unwrap();
+    assert_eq!(rel, "docs/guide.md");
+    for bad in ["", ".", "..", "a/b", "../../etc", "/etc"] {
+        let err = rel.set_file_name(bad).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(rel.with_file_name(bad).is_err(), "{bad}");
+    }
+    assert_eq!(rel, "docs/guide.md");
+}
+
+#[test]
+fn try_join_accepts_any_relative_path() {
+    let base = RelPathBuf::new("a").unwrap();
+    assert_eq!(base.try_join("b").unwrap(), "a/b");
+    assert_eq!(base.try_join(std::path::PathBuf::from("c")).unwrap(), "a/c");
+    assert_eq!(base.join(RelPath::new("d").unwrap()), "a/d");
+}
+
+#[cfg(unix)]
+#[test]
+fn try_join_rejects_instead_of_replacing() {
+    let err = RelPathBuf::new("a").unwrap().try_join("/etc").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn display_from_str_round_trip() {
+    for path in [
+        "file.txt",
+        "src/lib.rs",
+        "target/debug/",
+        "../sibling/./x",
+        "My Documents/r\u{e9}sum\u{e9}.pdf",
+        ".config/\u{1f600}",
+    ] {
+        let rel = RelPathBuf::new(path).unwrap();
+        let parsed: RelPathBuf = rel.to_string().parse().unwrap();
+        assert_eq!(parsed.as_os_str(), rel.as_os_str(), "{path}");
+    }
+}
+
+#[test]
+#[cfg(all(unix, not(feature = "utf8-strict")))]
+fn display_is_lossy_for_non_utf8() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let rel = RelPathBuf::new(OsStr::from_bytes(b"caf\xe9")).unwrap();
+    assert_eq!(rel.to_string(), "caf\u{fffd}");
+    let parsed: RelPathBuf = rel.to_string().parse().unwrap();
+    assert_ne!(parsed, rel);
+}
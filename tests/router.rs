@@ -0,0 +1,74 @@
+use dirge::{RelPathBuf, router::PathRouter};
+use std::io;
+
+fn rel(s: &str) -> RelPathBuf {
+    RelPathBuf::new(s).unwrap()
+}
+
+fn router() -> PathRouter<&'static str> {
+    let mut router = PathRouter::new();
+    router.insert("", "index").unwrap();
+    router.insert("users/{id}", "user").unwrap();
+    router.insert("users/me", "me").unwrap();
+    router.insert("users/{id}/avatar", "avatar").unwrap();
+    router.insert("static/{*rest}", "static").unwrap();
+    router.insert("{*any}", "fallback").unwrap();
+    router
+}
+
+#[test]
+fn most_specific_route_wins() {
+    let router = router();
+    let route = |p: &str| router.at(rel(p)).map(|(v, _)| *v);
+
+    assert_eq!(route(""), Some("index"));
+    assert_eq!(route("."), Some("index"));
+    assert_eq!(route("users/me"), Some("me"));
+    assert_eq!(route("users/7"), Some("user"));
+    assert_eq!(route("users/7/avatar"), Some("avatar"));
+    assert_eq!(route("static/a/b.css"), Some("static"));
+    assert_eq!(route("users/7/other"), Some("fallback"));
+}
+
+#[test]
+fn captures() {
+    let router = router();
+
+    let (_, captures) = router.at(rel("users/7/avatar")).unwrap();
+    assert_eq!(captures.get("id"), Some("7"));
+    assert_eq!(captures.rest(), None);
+
+    let (_, captures) = router.at(rel("static/./css/site.css")).unwrap();
+    assert_eq!(captures.get("rest"), Some("css/site.css"));
+    assert_eq!(captures.rest(), Some(&*rel("css/site.css")));
+}
+
+#[test]
+fn traversal_matches_nothing() {
+    let router = router();
+    assert!(router.at(rel("..")).is_none());
+    assert!(router.at(rel("static/../../etc/passwd")).is_none());
+
+    // `..` that stays inside the root is resolved before matching.
+    let (route, _) = router.at(rel("static/../users/me")).unwrap();
+    assert_eq!(*route, "me");
+}
+
+#[test]
+fn rest_needs_a_segment() {
+    let router = router();
+    let (route, captures) = router.at(rel("static")).unwrap();
+    assert_eq!(*route, "fallback");
+    assert_eq!(captures.get("any"), Some("static"));
+}
+
+#[test]
+fn invalid_and_conflicting_patterns() {
+    let mut router = router();
+    for pattern in ["a//b", "/a", "a/", "{*rest}/a", "a/{}", "a/..", "a/{id"] {
+        let err = router.insert(pattern, "x").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{pattern:?}");
+    }
+    let err = router.insert("users/{name}", "x").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+}
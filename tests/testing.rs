@@ -0,0 +1,74 @@
+#![cfg(feature = "testing")]
+
+use dirge::testing::{self, Cause};
+
+mod common;
+
+use common::scratch;
+
+#[test]
+fn lexical_agrees_with_fs_on_plain_trees() {
+    let dir = scratch("agree");
+    let report = testing::check_lexical_fs_agreement(&dir, 2000, 7).unwrap();
+    assert_eq!(report.checked, 2000);
+    assert!(
+        report
+            .disagreements
+            .iter()
+            .all(|d| d.cause != Cause::Unexplained),
+        "{report:#?}"
+    );
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn reports_dot_dot_through_symlinks() {
+    let dir = scratch("symlink");
+    let report = testing::check_lexical_fs_agreement(&dir, 2000, 7).unwrap();
+    let through_links: Vec<_> = report
+        .disagreements
+        .iter()
+        .filter(|d| d.cause == Cause::Symlink)
+        .collect();
+    assert!(!through_links.is_empty(), "{report:#?}");
+    for d in through_links {
+        assert!(d.path.components().any(|c| c.as_os_str() == ".."), "{d:#?}");
+        assert_ne!(d.resolved.as_ref().ok(), Some(&d.lexical), "{d:#?}");
+    }
+}
+
+#[test]
+fn name_variants_disagree_only_where_the_fs_folds_them() {
+    let dir = scratch("variants");
+    let caps = dirge::fs::probe(&dir).unwrap();
+    let report = testing::check_lexical_fs_agreement(&dir, 2000, 7).unwrap();
+    let variants: Vec<_> = report
+        .disagreements
+        .iter()
+        .filter(|d| d.cause == Cause::NameVariant)
+        .collect();
+    for d in &variants {
+        assert!(d.resolved.is_ok(), "{d:#?}");
+    }
+    if !caps.case_sensitive {
+        assert!(!variants.is_empty(), "{report:#?}");
+    }
+}
+
+#[test]
+fn same_seed_same_report() {
+    let dir = scratch("seed");
+    // Each run builds its tree in a fresh scratch directory, so compare what was generated.
+    let summary = |report: testing::Report| {
+        assert_eq!(report.checked, 100);
+        report
+            .disagreements
+            .into_iter()
+            .map(|d| (d.path, d.cause))
+            .collect::<Vec<_>>()
+    };
+    let a = testing::check_lexical_fs_agreement(&dir, 100, 1).unwrap();
+    let b = testing::check_lexical_fs_agreement(&dir, 100, 1).unwrap();
+    assert_eq!(summary(a), summary(b));
+}
@@ -0,0 +1,48 @@
+use dirge::trace::{Origin, Traced};
+use dirge::{AbsPath, AbsPathBuf};
+use std::io;
+
+fn config_dir() -> Traced<AbsPathBuf> {
+    Traced::new(
+        AbsPathBuf::new("/srv/data").unwrap(),
+        Origin::Config {
+            file: "tool.toml".into(),
+            line: 3,
+        },
+    )
+}
+
+#[test]
+fn map_extends_chain() {
+    let file = config_dir().map("joined with `x`", |d| d.try_join("x").unwrap());
+    let _: &AbsPath = &file;
+    assert_eq!(file.chain().len(), 2);
+    assert_eq!(
+        file.origin(),
+        &Origin::Config {
+            file: "tool.toml".into(),
+            line: 3
+        }
+    );
+    assert_eq!(file.chain()[1], Origin::Computed("joined with `x`".into()));
+}
+
+#[test]
+fn try_map_error_names_sources() {
+    let err = config_dir()
+        .try_map("canonicalized", |d| {
+            d.try_join("missing-dirge-trace").unwrap().canonicalize()
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.to_string().ends_with("\n  config file tool.toml:3"));
+}
+
+#[test]
+fn origins_display() {
+    assert_eq!(Origin::Arg("--out".into()).to_string(), "argument --out");
+    assert_eq!(
+        Origin::Env("HOME".into()).to_string(),
+        "environment variable HOME"
+    );
+}
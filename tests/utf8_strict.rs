@@ -0,0 +1,71 @@
+#![cfg(feature = "utf8-strict")]
+
+use dirge::{AbsPathBuf, NormPathBuf, RelPathBuf};
+
+mod common;
+
+#[test]
+fn as_str() {
+    assert_eq!(
+        RelPathBuf::new("src/lib.rs").unwrap().as_str(),
+        "src/lib.rs"
+    );
+    assert_eq!(NormPathBuf::new("a/./b").unwrap().as_str(), "a/b");
+    assert_eq!(AbsPathBuf::new("/etc").unwrap().as_str(), "/etc");
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::{ffi::OsStr, io, os::unix::ffi::OsStrExt, path::Path};
+
+    fn non_utf8() -> &'static Path {
+        Path::new(OsStr::from_bytes(b"caf\xe9"))
+    }
+
+    #[test]
+    fn constructors_reject_non_utf8() {
+        for err in [
+            RelPathBuf::new(non_utf8()).unwrap_err(),
+            NormPathBuf::new(non_utf8()).unwrap_err(),
+            AbsPathBuf::new(non_utf8()).unwrap_err(),
+        ] {
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn try_push_rejects_non_utf8() {
+        let mut abs = AbsPathBuf::new("/tmp").unwrap();
+        let err = abs.try_push(non_utf8()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(abs, "/tmp");
+    }
+
+    #[test]
+    fn paths_built_from_the_os_are_checked() {
+        use dirge::fs::{self, CurrentDirGuard, SortOrder};
+
+        let root = crate::common::scratch("os");
+        std::fs::create_dir(root.as_path().join(non_utf8())).unwrap();
+        std::os::unix::fs::symlink(non_utf8(), root.try_join("link").unwrap()).unwrap();
+
+        let err = root.walk().find_map(Result::err).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        for order in [SortOrder::Bytes, SortOrder::Natural] {
+            let err = fs::read_dir_sorted(&root, order).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        let err = root
+            .try_join("link/new.txt")
+            .unwrap()
+            .canonicalize_lenient()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        {
+            let _guard = CurrentDirGuard::change_to(root.try_join("link").unwrap()).unwrap();
+            let err = AbsPathBuf::new("ok.txt").unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+}
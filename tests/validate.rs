@@ -0,0 +1,105 @@
+use dirge::{
+    RelPathBuf,
+    validate::{Issue, Policy, check_all, scan},
+};
+use std::io;
+
+#[test]
+fn check_all_accepts_valid_paths() {
+    let paths = check_all(["a/b", "c"], Policy::Canonical).unwrap();
+    assert_eq!(
+        paths,
+        [
+            RelPathBuf::new("a/b").unwrap(),
+            RelPathBuf::new("c").unwrap()
+        ]
+    );
+}
+
+#[test]
+fn check_all_reports_every_failure() {
+    let err = check_all(["ok", "", "/abs", "ok/too", "../up"], Policy::Contained).unwrap_err();
+    let failures: Vec<(usize, io::ErrorKind)> =
+        err.failures().iter().map(|(i, e)| (*i, e.kind())).collect();
+    assert_eq!(
+        failures,
+        [
+            (1, io::ErrorKind::InvalidInput),
+            (2, io::ErrorKind::InvalidInput),
+            (4, io::ErrorKind::InvalidInput),
+        ]
+    );
+    assert!(err.to_string().starts_with("3 invalid path(s)"));
+    assert!(
+        err.to_string()
+            .contains("entry 4: ../up: path escapes its root")
+    );
+}
+
+#[test]
+fn check_all_policies() {
+    assert!(check_all(["../up"], Policy::Relative).is_ok());
+    assert!(check_all(["a/../b"], Policy::Contained).is_ok());
+    assert!(check_all(["a/../b"], Policy::Canonical).is_err());
+    assert!(check_all(["../b"], Policy::Canonical).is_err());
+}
+
+#[test]
+fn batch_error_converts_to_io_error() {
+    fn load() -> io::Result<Vec<RelPathBuf>> {
+        Ok(check_all(["/abs"], Policy::Relative)?)
+    }
+    assert_eq!(load().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn scan_clean_path() {
+    assert_eq!(scan("src/lib.rs"), []);
+    assert_eq!(scan("./a/../b.tar.gz"), []);
+}
+
+#[test]
+fn scan_reports_every_issue_with_spans() {
+    let findings = scan("a<b/CON.txt/dir./nul /x|y");
+    let found: Vec<_> = findings.iter().map(|f| (f.span.clone(), f.issue)).collect();
+    assert_eq!(
+        found,
+        [
+            (1..2, Issue::InvalidChar('<')),
+            (4..11, Issue::ReservedName),
+            (15..16, Issue::TrailingSpaceOrDot),
+            (17..21, Issue::ReservedName),
+            (20..21, Issue::TrailingSpaceOrDot),
+            (23..24, Issue::InvalidChar('|')),
+        ]
+    );
+}
+
+#[test]
+fn scan_reserved_names_need_exact_stem() {
+    assert_eq!(scan("console/com10/lpt"), []);
+    assert_eq!(scan("Com1.log")[0].issue, Issue::ReservedName);
+}
+
+#[test]
+fn scan_long_and_control() {
+    let long = "x".repeat(256);
+    let findings = scan(format!("ok/{long}"));
+    assert_eq!(findings[0].span, 3..259);
+    assert_eq!(findings[0].issue, Issue::TooLong);
+
+    let findings = scan("tab\there");
+    assert_eq!(findings[0].span, 3..4);
+    assert_eq!(findings[0].issue, Issue::InvalidChar('\t'));
+}
+
+#[cfg(unix)]
+#[test]
+fn scan_unix_specifics() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    assert_eq!(scan("a\\b")[0].issue, Issue::InvalidChar('\\'));
+    let findings = scan(OsStr::from_bytes(b"ok/caf\xe9"));
+    assert_eq!(findings[0].span, 6..7);
+    assert_eq!(findings[0].issue, Issue::NotUtf8);
+}
@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+
+mod common;
+
+use common::scratch;
+
+#[test]
+fn walk_is_depth_first_in_name_order() {
+    let root = scratch("order");
+    for dir in ["b/d", "a"] {
+        std::fs::create_dir_all(root.try_join(dir).unwrap()).unwrap();
+    }
+    for file in ["c.txt", "b/z.txt", "b/d/e.txt", "a/f.txt"] {
+        std::fs::write(root.try_join(file).unwrap(), file).unwrap();
+    }
+
+    let entries: Vec<(PathBuf, usize)> = root
+        .walk()
+        .map(|e| {
+            let e = e.unwrap();
+            (e.relative_path().to_path_buf(), e.depth())
+        })
+        .collect();
+    let expected: Vec<(PathBuf, usize)> = [
+        ("a", 1),
+        ("a/f.txt", 2),
+        ("b", 1),
+        ("b/d", 2),
+        ("b/d/e.txt", 3),
+        ("b/z.txt", 2),
+        ("c.txt", 1),
+    ]
+    .into_iter()
+    .map(|(p, d)| (PathBuf::from(p), d))
+    .collect();
+    assert_eq!(entries, expected);
+}
+
+#[test]
+fn walk_missing_root_errors() {
+    let tmp = scratch("missing");
+    let root = tmp.try_join("nope").unwrap();
+    let results: Vec<_> = root.walk().collect();
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].as_ref().unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn walk_does_not_follow_symlinks() {
+    let root = scratch("symlink");
+    std::fs::create_dir(root.try_join("real").unwrap()).unwrap();
+    std::fs::write(root.try_join("real/file").unwrap(), "").unwrap();
+    std::os::unix::fs::symlink(
+        root.try_join("real").unwrap(),
+        root.try_join("link").unwrap(),
+    )
+    .unwrap();
+
+    let entries: Vec<_> = root.walk().map(|e| e.unwrap()).collect();
+    let names: Vec<_> = entries
+        .iter()
+        .map(|e| e.relative_path().to_path_buf())
+        .collect();
+    assert_eq!(names, ["link", "real", "real/file"].map(PathBuf::from));
+    assert!(entries[0].file_type().is_symlink());
+}
+
+#[test]
+fn archive_entries_names_and_contents() {
+    use std::io::Read;
+
+    let root = scratch("archive");
+    std::fs::create_dir(root.try_join("dir").unwrap()).unwrap();
+    std::fs::write(root.try_join("dir/file.txt").unwrap(), "data").unwrap();
+
+    let entries: Vec<_> = root
+        .walk()
+        .archive_entries()
+        .map(|e| {
+            let mut e = e.unwrap();
+            let mut contents = String::new();
+            e.contents.read_to_string(&mut contents).unwrap();
+            (e.name, e.metadata.is_dir(), contents)
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("dir/".to_owned(), true, String::new()),
+            ("dir/file.txt".to_owned(), false, "data".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn entry_caches_metadata() {
+    let root = scratch("metadata");
+    std::fs::create_dir(root.try_join("dir").unwrap()).unwrap();
+    std::fs::write(root.try_join("file").unwrap(), "12345").unwrap();
+
+    let entries: Vec<_> = root.walk().map(|e| e.unwrap()).collect();
+    assert!(entries[0].is_dir() && !entries[0].is_file());
+    assert!(entries[1].is_file() && !entries[1].is_symlink());
+
+    assert_eq!(entries[1].metadata().unwrap().len(), 5);
+    // Later calls are answered from the cache, even once the file is gone.
+    std::fs::remove_file(root.try_join("file").unwrap()).unwrap();
+    assert_eq!(entries[1].metadata().unwrap().len(), 5);
+}
+
+#[test]
+fn limits_abort_the_walk() {
+    use dirge::walk::{Limit, LimitExceeded, Limits};
+
+    let root = scratch("limits");
+    std::fs::create_dir_all(root.try_join("a/b/c").unwrap()).unwrap();
+    std::fs::write(root.try_join("a/f").unwrap(), "0123456789").unwrap();
+    std::fs::write(root.try_join("g").unwrap(), "0123456789").unwrap();
+
+    let run = |limits: Limits| -> (usize, Option<Limit>) {
+        let results: Vec<_> = root.walk().with_limits(limits).collect();
+        let ok = results.iter().filter(|r| r.is_ok()).count();
+        let limit = results
+            .last()
+            .and_then(|r| r.as_ref().err())
+            .and_then(LimitExceeded::from_io)
+            .map(|e| e.limit());
+        (ok, limit)
+    };
+
+    assert_eq!(run(Limits::new()), (5, None));
+    assert_eq!(run(Limits::new().max_files(5)), (5, None));
+    assert_eq!(run(Limits::new().max_files(2)), (2, Some(Limit::Files(2))));
+    assert_eq!(run(Limits::new().max_depth(2)), (2, Some(Limit::Depth(2))));
+    assert_eq!(
+        run(Limits::new().max_bytes(15)),
+        (4, Some(Limit::Bytes(15)))
+    );
+
+    let err = root
+        .walk()
+        .with_limits(Limits::new().max_depth(1))
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::QuotaExceeded);
+    let exceeded = LimitExceeded::from_io(&err).unwrap();
+    assert_eq!(exceeded.path(), &*root.try_join("a/b").unwrap());
+    assert!(err.to_string().contains("depth limit of 1"));
+}
+
+#[test]
+fn vanished_file_does_not_abort_a_limited_walk() {
+    use dirge::walk::Limits;
+
+    let root = scratch("vanished");
+    for name in ["a", "b", "c"] {
+        std::fs::write(root.try_join(name).unwrap(), "x").unwrap();
+    }
+    let mut walk = root.walk().with_limits(Limits::new().max_bytes(100));
+    assert_eq!(walk.next().unwrap().unwrap().relative_path(), "a");
+    std::fs::remove_file(root.try_join("b").unwrap()).unwrap();
+    let err = walk.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(walk.next().unwrap().unwrap().relative_path(), "c");
+    assert!(walk.next().is_none());
+}
+
+#[test]
+fn limits_timeout() {
+    use dirge::walk::{Limit, LimitExceeded, Limits};
+    use std::time::Duration;
+
+    let root = scratch("timeout");
+    std::fs::write(root.try_join("f").unwrap(), "").unwrap();
+    let err = root
+        .walk()
+        .with_limits(Limits::new().timeout(Duration::ZERO))
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert_eq!(
+        LimitExceeded::from_io(&err).unwrap().limit(),
+        Limit::Timeout(Duration::ZERO)
+    );
+}
+
+#[test]
+fn same_file_system_walks_a_single_volume_fully() {
+    let root = scratch("one-fs");
+    std::fs::create_dir_all(root.try_join("a/b").unwrap()).unwrap();
+    std::fs::write(root.try_join("a/b/f").unwrap(), "").unwrap();
+
+    let all: Vec<_> = root.walk().map(|e| e.unwrap().into_path()).collect();
+    let same: Vec<_> = root
+        .walk()
+        .same_file_system()
+        .map(|e| e.unwrap().into_path())
+        .collect();
+    assert_eq!(same, all);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn same_file_system_does_not_descend_into_mount_points() {
+    use dirge::AbsPathBuf;
+
+    // /dev/pts, /dev/shm and /dev/mqueue are usually mounted over /dev.
+    let dev = AbsPathBuf::new("/dev").unwrap();
+    let Some(mount) = ["pts", "shm", "mqueue"]
+        .into_iter()
+        .map(|name| dev.try_join(name).unwrap())
+        .find(|dir| dev.same_file_system(dir).is_ok_and(|same| !same))
+    else {
+        return;
+    };
+
+    let paths: Vec<_> = dev
+        .walk()
+        .same_file_system()
+        .filter_map(Result::ok)
+        .map(|e| e.into_path())
+        .collect();
+    assert!(paths.contains(&mount));
+    assert!(!paths.iter().any(|p| p.starts_with(&mount) && *p != mount));
+}
@@ -0,0 +1,42 @@
+use dirge::win::{needs_verbatim, simplify_verbatim};
+
+#[test]
+fn simplifies_when_equivalent() {
+    for (input, expected) in [
+        (r"\\?\C:\src\lib.rs", r"C:\src\lib.rs"),
+        (r"\\?\c:\", r"c:\"),
+        (r"\\?\C:", r"C:\"),
+        (r"\\?\UNC\server\share", r"\\server\share"),
+        (
+            r"\\?\unc\server\share\dir\f.txt",
+            r"\\server\share\dir\f.txt",
+        ),
+        (r"C:\already\simple", r"C:\already\simple"),
+        ("/unix/path", "/unix/path"),
+    ] {
+        assert_eq!(simplify_verbatim(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn keeps_prefix_when_needed() {
+    let long = format!(r"\\?\C:\{}", "a".repeat(300));
+    for input in [
+        r"\\?\C:\a\..\b",
+        r"\\?\C:\a\.\b",
+        r"\\?\C:\a\\b",
+        r"\\?\C:\dir\name.",
+        r"\\?\C:\dir\name ",
+        r"\\?\C:\dir\nul.txt",
+        r"\\?\C:\a/b",
+        r"\\?\Volume{0b1a-42}\x",
+        r"\\?\UNC\server",
+        r"\\.\pipe\name",
+        long.as_str(),
+    ] {
+        assert_eq!(simplify_verbatim(input), input, "{input:?}");
+    }
+    assert!(needs_verbatim(r"\\?\C:\dir\name."));
+    assert!(!needs_verbatim(r"\\?\C:\dir\name"));
+    assert!(!needs_verbatim(r"C:\dir\name."));
+}
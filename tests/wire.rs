@@ -0,0 +1,90 @@
+use dirge::{RelPathBuf, wire};
+use std::io;
+
+#[test]
+fn round_trip() {
+    for path in [
+        "a",
+        "src/lib.rs",
+        "../up/there",
+        "deeply/nested/path/to/a/file.txt",
+    ] {
+        let rel = RelPathBuf::new(path).unwrap();
+        let bytes = wire::encode(&rel).unwrap();
+        assert_eq!(wire::decode(&bytes).unwrap(), rel);
+    }
+}
+
+#[test]
+fn cur_dir_is_dropped() {
+    let bytes = wire::encode(RelPathBuf::new("./a/./b").unwrap()).unwrap();
+    assert_eq!(bytes, b"\x02\x01a\x01b");
+}
+
+#[test]
+fn empty_path() {
+    let bytes = wire::encode(RelPathBuf::new("").unwrap()).unwrap();
+    assert_eq!(bytes, b"\x00");
+    assert_eq!(wire::decode(&bytes).unwrap(), RelPathBuf::new("").unwrap());
+}
+
+#[test]
+fn long_segment_uses_multibyte_length() {
+    let name = "x".repeat(200);
+    let bytes = wire::encode(RelPathBuf::new(&name).unwrap()).unwrap();
+    assert_eq!(&bytes[..3], b"\x01\xc8\x01");
+    assert_eq!(
+        wire::decode(&bytes).unwrap(),
+        RelPathBuf::new(&name).unwrap()
+    );
+}
+
+#[test]
+fn encode_into_appends() {
+    let mut out = vec![0xff];
+    wire::encode_into(RelPathBuf::new("a").unwrap(), &mut out).unwrap();
+    assert_eq!(out, b"\xff\x01\x01a");
+}
+
+#[test]
+fn decode_rejects_malformed_input() {
+    for bytes in [
+        &b""[..],
+        b"\x01",
+        b"\x01\x05ab",
+        b"\x01\x01a\x00",
+        b"\x01\x00",
+        b"\x01\x01.",
+        b"\x01\x03a/b",
+        b"\x01\x03a\\b",
+        b"\x01\x02\xff\xfe",
+        b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01",
+    ] {
+        let err = wire::decode(bytes).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{bytes:?}");
+    }
+}
+
+#[test]
+#[cfg(windows)]
+fn decode_rejects_segments_that_are_not_components_here() {
+    for bytes in [&b"\x01\x02C:"[..], b"\x02\x01a\x02C:", b"\x01\x03C:x"] {
+        let err = wire::decode(bytes).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{bytes:?}");
+    }
+}
+
+#[test]
+fn decode_keeps_parent_segments() {
+    let rel = wire::decode(b"\x02\x02..\x01a").unwrap();
+    assert_eq!(rel, RelPathBuf::new("../a").unwrap());
+}
+
+#[test]
+#[cfg(all(unix, not(feature = "utf8-strict")))]
+fn encode_rejects_non_utf8() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let rel = RelPathBuf::new(OsStr::from_bytes(b"caf\xe9")).unwrap();
+    assert!(wire::encode(&rel).is_err());
+}